@@ -0,0 +1,183 @@
+use std::fmt::{Debug, Formatter};
+use crate::Error;
+use crate::schema::{KeySchema, parse_hex};
+use crate::value::{self, Value};
+
+/// A key decoded with its schema, which `Debug` displays the same way as the generated `Debug`
+/// of the key, without the hidden fields and with the raw format of the key.
+pub struct DecodedKey<'a> {
+    schema: &'a KeySchema,
+    fields: Vec<(&'a str, Value)>,
+    key: Vec<u8>,
+}
+
+impl Debug for DecodedKey<'_> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let mut debug = f.debug_struct(&self.schema.key);
+        for (name, value) in self.fields.iter() {
+            debug.field(name, value);
+        }
+        debug.field("raw", &RawDebug { schema: self.schema, key: &self.key }).finish()
+    }
+}
+
+/// The raw key in the `raw_debug` format of the schema.
+struct RawDebug<'a> {
+    schema: &'a KeySchema,
+    key: &'a [u8],
+}
+
+impl Debug for RawDebug<'_> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self.schema.raw_debug.as_str() {
+            "std" => write!(f, "{:?}", self.key),
+            "lower_hex" => write!(f, "{:x?}", self.key),
+            "upper_hex" => write!(f, "{:X?}", self.key),
+            "pretty_lower_hex" => self.fmt_pretty(f, |f, byte| write!(f, "{:#04x}", byte)),
+            "pretty_upper_hex" => self.fmt_pretty(f, |f, byte| write!(f, "{:#04X}", byte)),
+            _ => self.fmt_compact(f),
+        }
+    }
+}
+
+impl RawDebug<'_> {
+    /// Display the bytes of the key in hexadecimal with the leading `0x`, separated by commas.
+    fn fmt_pretty(
+        &self,
+        f: &mut Formatter<'_>,
+        fmt_byte: impl Fn(&mut Formatter<'_>, u8) -> std::fmt::Result,
+    ) -> std::fmt::Result {
+        f.write_str("[")?;
+        for (i, byte) in self.key.iter().enumerate() {
+            if 0 < i {
+                f.write_str(", ")?;
+            }
+            fmt_byte(f, *byte)?;
+        }
+        f.write_str("]")
+    }
+
+    /// Display the compact raw format, where the fields that are whole bytes are displayed in
+    /// hexadecimal and the bit fields are displayed in binary.
+    fn fmt_compact(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let mut bit = 0;
+        let mut binary = None;
+        for size in self.schema.raw_sizes() {
+            if 0 == bit % 8 && 0 == size % 8 {
+                match binary {
+                    None => f.write_str("0x")?,
+                    Some(true) => f.write_str("_0x")?,
+                    Some(false) => f.write_str("_")?,
+                }
+                for byte in self.key[bit / 8..(bit + size) / 8].iter() {
+                    write!(f, "{:02X}", byte)?;
+                }
+                binary = Some(false);
+            }
+            else {
+                match binary {
+                    None => f.write_str("0b")?,
+                    Some(false) => f.write_str("_0b")?,
+                    Some(true) => f.write_str("_")?,
+                }
+                for i in bit..(bit + size) {
+                    let set = 0 != self.key[i / 8] & (0x80 >> (i % 8));
+                    f.write_str(if set { "1" } else { "0" })?;
+                }
+                binary = Some(true);
+            }
+            bit += size;
+        }
+        Ok(())
+    }
+}
+
+/// Decode a key written in hexadecimal.
+pub fn decode<'a>(schema: &'a KeySchema, hex: &str) -> Result<DecodedKey<'a>, Error> {
+    let key = parse_hex(hex)?;
+    if schema.length != key.len() {
+        return Err(Error::new(format!("{} has {} bytes, but a {} has {}", hex.trim(), key.len(),
+            schema.key, schema.length)));
+    }
+    // Hidden fields are only displayed in the raw key.
+    let fields = schema.fields.iter()
+        .filter(|field| !field.hidden)
+        .map(|field| (field.name.as_str(), value::decode(field, &key)))
+        .collect();
+    Ok(DecodedKey { schema, fields, key })
+}
+
+/// Split an assignment of a field (e.g. `id=5`) into the name and the value.
+fn split_assignment(assignment: &str) -> Result<(&str, &str), Error> {
+    let mut split = assignment.splitn(2, '=');
+    match (split.next(), split.next()) {
+        (Some(name), Some(value)) => Ok((name.trim(), value)),
+        _ => Err(Error::new(format!("{} isn't a FIELD=VALUE assignment", assignment))),
+    }
+}
+
+/// Create a key from the assignments of its fields, where the fields that aren't assigned keep
+/// their default values. Computed fields aren't computed, so the names of the computed fields
+/// that kept their default values are also returned.
+pub fn encode<'a>(schema: &'a KeySchema, assignments: &[String])
+    -> Result<(Vec<u8>, Vec<&'a str>), Error>
+{
+    let mut key = schema.default_key();
+    let mut assigned = Vec::new();
+    for assignment in assignments.iter() {
+        let (name, text) = split_assignment(assignment)?;
+        let field = schema.field(name)?;
+        field.store(&mut key, &value::encode(field, text)?);
+        assigned.push(field.name.as_str());
+    }
+    let uncomputed = schema.fields.iter()
+        .filter(|field| field.computed && !assigned.contains(&field.name.as_str()))
+        .map(|field| field.name.as_str())
+        .collect();
+    Ok((key, uncomputed))
+}
+
+/// Return the first and the last key that start with the assigned fields, which must be the
+/// first fields of the key other than the version and padding fields.
+///
+/// The fields after the prefix are set to their minimum and maximum values, or to all zeros and
+/// all ones when the key doesn't have a minimum or maximum key.
+pub fn range(schema: &KeySchema, assignments: &[String]) -> Result<(Vec<u8>, Vec<u8>), Error> {
+    let mut values = Vec::with_capacity(assignments.len());
+    for assignment in assignments.iter() {
+        let (name, text) = split_assignment(assignment)?;
+        let field = schema.field(name)?;
+        if values.iter().any(|(assigned, _)| assigned == &field.name) {
+            return Err(Error::new(format!("the {} field is assigned twice", name)));
+        }
+        values.push((field.name.clone(), value::encode(field, text)?));
+    }
+    let mut start = schema.default_key();
+    let mut end = start.clone();
+    let mut prefix = true;
+    for field in schema.fields.iter() {
+        if field.version || field.padding {
+            continue;
+        }
+        match values.iter().find(|(name, _)| name == &field.name) {
+            Some((_, bytes)) if prefix => {
+                field.store(&mut start, bytes);
+                field.store(&mut end, bytes);
+            }
+            Some(_) => {
+                return Err(Error::new(format!("the {} field isn't part of a prefix of the key \
+                    (the fields before it must be assigned)", field.name)));
+            }
+            None => {
+                prefix = false;
+                let size = (field.bits + 7) / 8;
+                let min = field.min.as_deref().map_or(Ok(vec![0; size]), parse_hex)?;
+                let max = field.max.as_deref().map_or(Ok(vec![0xFF; size]), parse_hex)?;
+                field.store(&mut start, &min);
+                field.store(&mut end, &max);
+            }
+        }
+    }
+    Ok((start, end))
+}
+
@@ -0,0 +1,393 @@
+//! The `dbkey` command decodes and creates the keys of the `db-key-macro` crate with the JSON
+//! schemas returned by the `schema_json()` function of the keys, so the keys stored in a database
+//! can be inspected without writing a program.
+
+mod key;
+mod schema;
+mod value;
+
+use std::fmt::{Display, Formatter};
+use std::io::{BufRead, Write};
+use std::process::exit;
+use schema::{KeySchema, to_hex};
+
+const USAGE: &str = "\
+Decode and create db-key keys with the JSON schemas returned by their schema_json() function.
+
+Usage:
+    dbkey decode --schema FILE [--key NAME] [--pretty] [KEY...]
+    dbkey encode --schema FILE [--key NAME] FIELD=VALUE...
+    dbkey range --schema FILE [--key NAME] FIELD=VALUE...
+
+Commands:
+    decode  Display the fields of the keys written in hexadecimal the same way as the Debug of
+            the key does. The keys are read from the standard input, one per line, when they
+            aren't on the command line.
+    encode  Display the key with the assigned fields in hexadecimal. The other fields keep their
+            default values.
+    range   Display the first and the last key that start with the assigned fields, which must
+            be the first fields of the key.
+
+Options:
+    --schema FILE  The JSON schema of the key, or a JSON array of the schemas of several keys.
+    --key NAME     The key to use when the schema file has several keys.
+    --pretty       Display the decoded keys on several lines, like {:#?}.
+    -h, --help     Display this help.
+";
+
+/// An error of the command, which is displayed to the user.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Error(String);
+
+impl Error {
+    /// Create an error with its message.
+    pub fn new(message: String) -> Self {
+        Self(message)
+    }
+}
+
+impl Display for Error {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl std::error::Error for Error {}
+
+/// The command line options of the commands.
+#[derive(Debug, Default, PartialEq, Eq)]
+struct Options {
+    command: String,
+    schema: Option<String>,
+    key: Option<String>,
+    pretty: bool,
+    args: Vec<String>,
+}
+
+impl Options {
+    /// Parse the command line arguments, without the name of the program. The error is the
+    /// usage error, or `None` when the help was requested.
+    fn parse<I: IntoIterator<Item = String>>(args: I) -> Result<Self, Option<String>> {
+        let mut options = Options::default();
+        let mut args = args.into_iter();
+        while let Some(arg) = args.next() {
+            match arg.as_str() {
+                "-h" | "--help" | "help" => return Err(None),
+                "--schema" | "--key" => {
+                    let value = args.next()
+                        .ok_or_else(|| Some(format!("{} expects a value", arg)))?;
+                    if "--schema" == arg {
+                        options.schema = Some(value);
+                    }
+                    else {
+                        options.key = Some(value);
+                    }
+                }
+                "--pretty" => options.pretty = true,
+                option if option.starts_with("--") => {
+                    return Err(Some(format!("unknown option {}", option)));
+                }
+                _ if options.command.is_empty() => options.command = arg,
+                _ => options.args.push(arg),
+            }
+        }
+        match options.command.as_str() {
+            "" => Err(Some("missing command".to_string())),
+            "decode" | "encode" | "range" if options.schema.is_none() => {
+                Err(Some("missing --schema FILE".to_string()))
+            }
+            "encode" | "range" if options.args.is_empty() => {
+                Err(Some(format!("{} expects FIELD=VALUE assignments", options.command)))
+            }
+            "decode" | "encode" | "range" => Ok(options),
+            command => Err(Some(format!("unknown command {}", command))),
+        }
+    }
+}
+
+/// Run the command, and return whether all the keys were decoded.
+fn run(options: &Options) -> Result<bool, Error> {
+    let path = options.schema.as_deref().unwrap_or_default();
+    let json = std::fs::read_to_string(path)
+        .map_err(|error| Error::new(format!("failed to read {}: {}", path, error)))?;
+    let schema = KeySchema::from_json(&json, options.key.as_deref())?;
+    let stdout = std::io::stdout();
+    let mut out = stdout.lock();
+    let write_error = |error: std::io::Error| Error::new(format!("failed to write: {}", error));
+    match options.command.as_str() {
+        "decode" => {
+            if schema.custom_debug {
+                eprintln!("dbkey: warning: {} implements Debug itself, so its keys are displayed \
+                    like the generated Debug", schema.key);
+            }
+            let mut decode = |line: &str| -> Result<(), Error> {
+                let key = key::decode(&schema, line)?;
+                if options.pretty {
+                    writeln!(out, "{:#?}", key).map_err(write_error)
+                }
+                else {
+                    writeln!(out, "{:?}", key).map_err(write_error)
+                }
+            };
+            let mut success = true;
+            if options.args.is_empty() {
+                let stdin = std::io::stdin();
+                for (i, line) in stdin.lock().lines().enumerate() {
+                    let line = line.map_err(|error| {
+                        Error::new(format!("failed to read the standard input: {}", error))
+                    })?;
+                    if line.trim().is_empty() {
+                        continue;
+                    }
+                    if let Err(error) = decode(&line) {
+                        eprintln!("dbkey: line {}: {}", i + 1, error);
+                        success = false;
+                    }
+                }
+            }
+            for arg in options.args.iter() {
+                if let Err(error) = decode(arg) {
+                    eprintln!("dbkey: {}", error);
+                    success = false;
+                }
+            }
+            Ok(success)
+        }
+        "encode" => {
+            let (key, uncomputed) = key::encode(&schema, &options.args)?;
+            for name in uncomputed {
+                eprintln!("dbkey: warning: the computed {} field keeps its default value", name);
+            }
+            writeln!(out, "{}", to_hex(&key)).map_err(write_error)?;
+            Ok(true)
+        }
+        _ => {
+            let (start, end) = key::range(&schema, &options.args)?;
+            writeln!(out, "{}..={}", to_hex(&start), to_hex(&end)).map_err(write_error)?;
+            Ok(true)
+        }
+    }
+}
+
+fn main() {
+    let options = match Options::parse(std::env::args().skip(1)) {
+        Ok(options) => options,
+        Err(None) => {
+            print!("{}", USAGE);
+            return;
+        }
+        Err(Some(error)) => {
+            eprintln!("dbkey: {}\n\n{}", error, USAGE);
+            exit(2);
+        }
+    };
+    match run(&options) {
+        Ok(true) => {}
+        Ok(false) => exit(1),
+        Err(error) => {
+            eprintln!("dbkey: {}", error);
+            exit(1);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use db_key_samples::attrib::*;
+    use proptest::prelude::*;
+    use std::net::{Ipv4Addr, SocketAddrV4};
+    use std::num::{NonZeroI16, NonZeroI32, NonZeroU64};
+    use std::time::{Duration, UNIX_EPOCH};
+
+    fn load_schema(json: &str) -> KeySchema {
+        KeySchema::from_json(json, None).unwrap()
+    }
+
+    fn args(assignments: &[&str]) -> Vec<String> {
+        assignments.iter().map(|assignment| assignment.to_string()).collect()
+    }
+
+    /// Check that the keys decoded with the schema are displayed the same way as the `Debug` of
+    /// the key displays them.
+    macro_rules! assert_decode {
+        ($key:ident, $bytes:expr) => {{
+            let mut bytes = [0_u8; $key::KEY_LENGTH];
+            bytes.copy_from_slice(&$bytes[..$key::KEY_LENGTH]);
+            let schema = load_schema(&$key::schema_json());
+            let key = $key::from(bytes);
+            let decoded = key::decode(&schema, &to_hex(key.as_ref())).unwrap();
+            assert_eq!(format!("{:?}", decoded), format!("{:?}", key));
+            assert_eq!(format!("{:#?}", decoded), format!("{:#?}", key));
+        }};
+    }
+
+    proptest! {
+        #[test]
+        fn decode_tests(bytes in proptest::collection::vec(0..=u8::MAX, 64)) {
+            assert_decode!(BitsKey, bytes);
+            assert_decode!(OptionKey, bytes);
+            assert_decode!(FlowKey, bytes);
+            assert_decode!(RecordKey, bytes);
+            assert_decode!(WordKey, bytes);
+            assert_decode!(LoginKey, bytes);
+            assert_decode!(AmountKey, bytes);
+            assert_decode!(OrderKey, bytes);
+            assert_decode!(AdjustmentKey, bytes);
+            assert_decode!(ShardedKey, bytes);
+            assert_decode!(ReservedKey, bytes);
+            assert_decode!(ActivityKey, bytes);
+            assert_decode!(TaggedKey, bytes);
+            assert_decode!(JournalKey, bytes);
+        }
+
+        #[test]
+        fn encode_tests(
+            priority in 0..=15_u8,
+            shard in 0..=0xFFF_u16,
+            adjust in -4..=3_i8,
+            id in 0..=u32::MAX,
+            level in -512..=511_i16,
+            parent in proptest::option::of(0..=u64::MAX),
+            tag in proptest::option::of(proptest::array::uniform3(0..=u8::MAX)),
+        ) {
+            let schema = load_schema(&BitsKey::schema_json());
+            let assignments = args(&[&format!("priority={}", priority),
+                &format!("shard={:#x}", shard), &format!("adjust={}", adjust),
+                &format!("id={}", id), &format!("level={}", level)]);
+            let key = BitsKey::new(priority, shard, adjust, id, level);
+            assert_eq!(key::encode(&schema, &assignments), Ok((key.as_ref().to_vec(), vec![])));
+
+            let schema = load_schema(&OptionKey::schema_json());
+            let assignments = args(&[&format!("parent={:?}", parent), &format!("tag={:?}", tag)]);
+            let key = OptionKey::new(parent, None, tag, None, 0);
+            assert_eq!(key::encode(&schema, &assignments), Ok((key.as_ref().to_vec(), vec![])));
+        }
+    }
+
+    #[test]
+    fn decode_values() {
+        let key = EventKey::new(UNIX_EPOCH + Duration::new(1_700_000_000, 123),
+            UNIX_EPOCH + Duration::from_millis(1_700_000_000_456), Duration::from_secs(3600),
+            None);
+        assert_decode!(EventKey, key.as_ref());
+        let schema = load_schema(&EventKey::schema_json());
+        let decoded = key::decode(&schema, &to_hex(key.as_ref())).unwrap();
+        assert!(format!("{:?}", decoded).contains("ttl: 3600s, expires: None, raw: 0x"));
+
+        let key = AmountKey::new(-123_450, 250, 7);
+        let schema = load_schema(&AmountKey::schema_json());
+        let decoded = key::decode(&schema, "0x7ffffffffffe1dc6_0000fa_00000007").unwrap();
+        assert_eq!(format!("{:?}", decoded), format!("{:?}", key));
+        assert!(format!("{:?}", decoded).starts_with("AmountKey { amount: -12.3450, rate: 2.50,"));
+
+        let schema = load_schema(&BitsKey::schema_json());
+        assert_eq!(key::decode(&schema, "0x0102").map(|_| ()),
+            Err(Error::new("0x0102 has 2 bytes, but a BitsKey has 9".to_string())));
+        assert!(key::decode(&schema, "0x01020304050607080g").is_err());
+    }
+
+    #[test]
+    fn encode_values() {
+        let schema = load_schema(&FlowKey::schema_json());
+        let (bytes, _) = key::encode(&schema, &args(&["tenant=7", "source=10.1.2.3",
+            "client=127.0.0.1:8080", "gateway=Some(192.168.0.1)"])).unwrap();
+        let key = FlowKey::from(<[u8; FlowKey::KEY_LENGTH]>::try_from(bytes.as_slice()).unwrap());
+        assert_eq!(key.tenant(), 7);
+        assert_eq!(key.source(), Ipv4Addr::new(10, 1, 2, 3));
+        assert_eq!(key.client(), SocketAddrV4::new(Ipv4Addr::LOCALHOST, 8080));
+        assert_eq!(key.gateway(), Some(Ipv4Addr::new(192, 168, 0, 1)));
+
+        let schema = load_schema(&EventKey::schema_json());
+        let (bytes, _) = key::encode(&schema, &args(&["updated=1.5s", "ttl=90",
+            "expires=Some(2000)"])).unwrap();
+        let key = EventKey::from(<[u8; EventKey::KEY_LENGTH]>::try_from(bytes.as_slice()).unwrap());
+        assert_eq!(key.updated(), UNIX_EPOCH + Duration::from_millis(1500));
+        assert_eq!(key.ttl(), Duration::from_secs(90));
+        assert_eq!(key.expires(), Some(UNIX_EPOCH + Duration::from_secs(2000)));
+
+        let schema = load_schema(&OrderKey::schema_json());
+        let (bytes, _) = key::encode(&schema, &args(&["customer=5", "delta=-2"])).unwrap();
+        assert_eq!(bytes, OrderKey::new(NonZeroU64::new(5).unwrap(), None,
+            NonZeroI16::new(-2).unwrap()).as_ref());
+        assert!(key::encode(&schema, &args(&["customer=0"])).is_err());
+        assert!(key::encode(&schema, &args(&["customer=None"])).is_err());
+
+        let schema = load_schema(&AdjustmentKey::schema_json());
+        let (bytes, _) = key::encode(&schema, &args(&["account=3", "adjustment=-5",
+            "correction=None"])).unwrap();
+        assert_eq!(bytes, AdjustmentKey::new(3, NonZeroI32::new(-5), None, None).as_ref());
+        assert_eq!(&bytes[8..11], &[0; 3]);
+        assert!(key::encode(&schema, &args(&["adjustment=-2147483648"])).is_err());
+        assert!(key::encode(&schema, &args(&["step=-8"])).is_err());
+        let decoded = key::decode(&schema, &to_hex(&[0; AdjustmentKey::KEY_LENGTH])).unwrap();
+        assert!(format!("{:?}", decoded)
+            .starts_with("AdjustmentKey { account: 0, adjustment: None, correction: None, step: None,"));
+
+        let schema = load_schema(&LoginKey::schema_json());
+        let (bytes, _) = key::encode(&schema, &args(&["user=Alice", "domain=\" Exam \"",
+            "tag=[1, 2]"])).unwrap();
+        assert_eq!(bytes, LoginKey::new(*b"alice\0\0\0", Some(*b" Exam "), [1, 2]).as_ref());
+
+        let schema = load_schema(&WordKey::schema_json());
+        let (bytes, _) = key::encode(&schema, &args(&["initial='d'", "letters=\"og!\"",
+            "last=None"])).unwrap();
+        assert_eq!(bytes, WordKey::new('d', ['o', 'g', '!'], None).as_ref());
+
+        let schema = load_schema(&ShardedKey::schema_json());
+        let (_, uncomputed) = key::encode(&schema, &args(&["user=5"])).unwrap();
+        assert_eq!(uncomputed, ["shard", "check"]);
+        let schema = load_schema(&ReservedKey::schema_json());
+        assert!(key::encode(&schema, &args(&["_region=0x010203"])).is_err());
+        let schema = load_schema(&BitsKey::schema_json());
+        assert!(key::encode(&schema, &args(&["priority=16"])).is_err());
+        assert!(key::encode(&schema, &args(&["adjust=-5"])).is_err());
+        assert!(key::encode(&schema, &args(&["unknown=1"])).is_err());
+        assert!(key::encode(&schema, &args(&["priority"])).is_err());
+    }
+
+    #[test]
+    fn range_values() {
+        let schema = load_schema(&ActivityKey::schema_json());
+        let (start, end) = key::range(&schema, &args(&["user=5"])).unwrap();
+        assert_eq!(to_hex(&start), "0x020000000000000005000000000000000000000000000000000100");
+        assert_eq!(to_hex(&end), "0x020000000000000005ffffffff01ffffffffffffffffffffffffff");
+        assert!(start.as_slice() <= ActivityKey::new(5, [0; 4], None,
+            NonZeroU64::new(1).unwrap(), 0).as_ref());
+        assert!(end.as_slice() >= ActivityKey::new(5, [0xFF; 4], None,
+            NonZeroU64::new(u64::MAX).unwrap(), u8::MAX).as_ref());
+        assert!(key::range(&schema, &args(&["tag=ab"])).is_err());
+
+        let schema = load_schema(&ReservedKey::schema_json());
+        let (start, end) = key::range(&schema, &args(&["account=1", "entry=2"])).unwrap();
+        assert_eq!((to_hex(&start), to_hex(&end)),
+            ("0x0000000100000000020000".to_string(), "0x0000000100000000020000".to_string()));
+    }
+
+    #[test]
+    fn options() {
+        let parse = |args: &[&str]| Options::parse(args.iter().map(|arg| arg.to_string()));
+        assert_eq!(parse(&["decode", "--schema", "key.json", "--pretty", "0x01"]), Ok(Options {
+            command: "decode".to_string(),
+            schema: Some("key.json".to_string()),
+            key: None,
+            pretty: true,
+            args: vec!["0x01".to_string()],
+        }));
+        assert_eq!(parse(&["range", "--schema", "keys.json", "--key", "UserKey", "id=5"])
+            .map(|options| (options.key, options.args)),
+            Ok((Some("UserKey".to_string()), vec!["id=5".to_string()])));
+        assert!(parse(&["range", "--schema", "keys.json", "--prefix", "id=5"]).is_err());
+        assert_eq!(parse(&["--help"]), Err(None));
+        assert!(parse(&[]).is_err());
+        assert!(parse(&["decode"]).is_err());
+        assert!(parse(&["encode", "--schema", "key.json"]).is_err());
+        assert!(parse(&["decode", "--schema"]).is_err());
+        assert!(parse(&["list", "--schema", "key.json"]).is_err());
+
+        let json = format!("[{}, {}]", BitsKey::schema_json(), OrderKey::schema_json());
+        assert_eq!(KeySchema::from_json(&json, Some("OrderKey")).unwrap().key, "OrderKey");
+        assert!(KeySchema::from_json(&json, None).is_err());
+        assert!(KeySchema::from_json(&json, Some("WordKey")).is_err());
+    }
+}
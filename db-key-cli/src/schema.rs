@@ -0,0 +1,233 @@
+use serde::Deserialize;
+use crate::Error;
+
+/// The layout of a key read from the JSON returned by the `schema_json()` function of the key.
+#[derive(Clone, Debug, Deserialize)]
+pub struct KeySchema {
+    /// The name of the key.
+    pub key: String,
+    /// The length of the key in bytes.
+    pub length: usize,
+    /// The format of the raw key displayed by `Debug` (e.g. `compact`).
+    #[serde(default = "compact")]
+    pub raw_debug: String,
+    /// The key implements `Debug` itself, so its keys are displayed like the generated `Debug`.
+    #[serde(default)]
+    pub custom_debug: bool,
+    /// The fields in the order they are stored in the key.
+    pub fields: Vec<FieldSchema>,
+}
+
+/// The default format of the raw key.
+fn compact() -> String {
+    "compact".to_string()
+}
+
+/// The layout of a field of a key.
+#[derive(Clone, Debug, Deserialize)]
+pub struct FieldSchema {
+    pub name: String,
+    #[serde(rename = "type")]
+    pub rust_type: String,
+    pub encoding: String,
+    pub bit_offset: usize,
+    pub bits: usize,
+    pub sign_flip: bool,
+    pub option: Option<String>,
+    #[serde(default)]
+    pub descending: bool,
+    pub time_unit: Option<String>,
+    pub char_size: Option<usize>,
+    pub collation: Option<String>,
+    pub decimal_scale: Option<u32>,
+    #[serde(default)]
+    pub computed: bool,
+    #[serde(default)]
+    pub padding: bool,
+    #[serde(default)]
+    pub version: bool,
+    #[serde(default)]
+    pub hidden: bool,
+    pub default: String,
+    pub min: Option<String>,
+    pub max: Option<String>,
+}
+
+/// The formats of the raw key displayed by `Debug`.
+const RAW_DEBUG_FORMATS: [&str; 6] =
+    ["compact", "std", "lower_hex", "upper_hex", "pretty_lower_hex", "pretty_upper_hex"];
+
+/// The JSON of a schema file, which is either the schema of one key or an array of schemas.
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum SchemaFile {
+    One(KeySchema),
+    Many(Vec<KeySchema>),
+}
+
+impl KeySchema {
+    /// Read the schema of the key named `key` from the JSON of a schema file, which can be
+    /// omitted when the file only has one schema.
+    pub fn from_json(json: &str, key: Option<&str>) -> Result<Self, Error> {
+        let schemas = match serde_json::from_str(json) {
+            Ok(SchemaFile::One(schema)) => vec![schema],
+            Ok(SchemaFile::Many(schemas)) => schemas,
+            Err(error) => return Err(Error::new(format!("invalid schema: {}", error))),
+        };
+        let names = || schemas.iter().map(|schema| schema.key.as_str()).collect::<Vec<_>>()
+            .join(", ");
+        let schema = match key {
+            Some(key) => schemas.iter().find(|schema| key == schema.key).ok_or_else(|| {
+                Error::new(format!("the schema doesn't have the key {} (found {})", key, names()))
+            })?,
+            None if 1 == schemas.len() => &schemas[0],
+            None => {
+                return Err(Error::new(format!("the schema has several keys, select one with \
+                    --key (found {})", names())));
+            }
+        };
+        schema.check()?;
+        Ok(schema.clone())
+    }
+
+    /// Check that the fields are inside the key and that their values have the right size, so
+    /// the other functions don't have to.
+    fn check(&self) -> Result<(), Error> {
+        if !RAW_DEBUG_FORMATS.contains(&self.raw_debug.as_str()) {
+            return Err(Error::new(format!("unknown raw_debug format {} (expected one of {})",
+                self.raw_debug, RAW_DEBUG_FORMATS.join(", "))));
+        }
+        for field in self.fields.iter() {
+            if self.length * 8 < field.bit_offset + field.bits {
+                return Err(Error::new(format!("the {} field is outside the key", field.name)));
+            }
+            if field.descending {
+                return Err(Error::new(format!("the {} field is descending, which isn't \
+                    supported", field.name)));
+            }
+            let values = [Some(&field.default), field.min.as_ref(), field.max.as_ref()];
+            for value in values.iter().flatten() {
+                if parse_hex(value).map(|bytes| bytes.len()) != Ok((field.bits + 7) / 8) {
+                    return Err(Error::new(format!("invalid value {} of the {} field", value,
+                        field.name)));
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Return the field named `name`.
+    pub fn field(&self, name: &str) -> Result<&FieldSchema, Error> {
+        self.fields.iter().find(|field| name == field.name).ok_or_else(|| {
+            let names: Vec<&str> = self.fields.iter().map(|field| field.name.as_str()).collect();
+            Error::new(format!("the {} key doesn't have the {} field (expected one of {})",
+                self.key, name, names.join(", ")))
+        })
+    }
+
+    /// Return the default key, which is made of the default values of the fields.
+    pub fn default_key(&self) -> Vec<u8> {
+        let mut key = vec![0_u8; self.length];
+        for field in self.fields.iter() {
+            field.store(&mut key, &parse_hex(&field.default).unwrap_or_default());
+        }
+        key
+    }
+
+    /// Return the sizes in bits of the parts of the key displayed by the compact raw format of
+    /// `Debug`, which are the fields, the presence bytes of `Option` fields, and the unused bits
+    /// between the fields.
+    pub fn raw_sizes(&self) -> Vec<usize> {
+        let mut sizes = Vec::with_capacity(self.fields.len());
+        let mut bit = 0;
+        for field in self.fields.iter() {
+            if bit < field.bit_offset {
+                sizes.push(field.bit_offset - bit);
+            }
+            if field.has_presence_byte() {
+                sizes.push(8);
+                sizes.push(field.bits - 8);
+            }
+            else {
+                sizes.push(field.bits);
+            }
+            bit = field.bit_offset + field.bits;
+        }
+        if bit < self.length * 8 {
+            sizes.push(self.length * 8 - bit);
+        }
+        sizes
+    }
+}
+
+impl FieldSchema {
+    /// Return true if the field is an `Option` with a presence byte before the value.
+    pub fn has_presence_byte(&self) -> bool {
+        matches!(self.option.as_deref(), Some("none_first") | Some("none_last"))
+    }
+
+    /// Return the bits of the field in the key, right-aligned in the smallest number of bytes.
+    pub fn load(&self, key: &[u8]) -> Vec<u8> {
+        load_bits(key, self.bit_offset, self.bits)
+    }
+
+    /// Store the right-aligned bits of the field in the key.
+    pub fn store(&self, key: &mut [u8], bytes: &[u8]) {
+        store_bits(key, self.bit_offset, self.bits, bytes)
+    }
+}
+
+/// Return `bits` bits of the key starting at `bit_offset`, right-aligned in the smallest number
+/// of bytes.
+pub fn load_bits(key: &[u8], bit_offset: usize, bits: usize) -> Vec<u8> {
+    let size = (bits + 7) / 8;
+    let mut bytes = vec![0_u8; size];
+    for i in 0..bits {
+        let bit = bit_offset + i;
+        if 0 != key[bit / 8] & (0x80 >> (bit % 8)) {
+            let target = size * 8 - bits + i;
+            bytes[target / 8] |= 0x80 >> (target % 8);
+        }
+    }
+    bytes
+}
+
+/// Store the `bits` right-aligned bits of `bytes` in the key starting at `bit_offset`.
+pub fn store_bits(key: &mut [u8], bit_offset: usize, bits: usize, bytes: &[u8]) {
+    let size = bytes.len();
+    for i in 0..bits {
+        let source = size * 8 - bits + i;
+        let bit = bit_offset + i;
+        if 0 != bytes[source / 8] & (0x80 >> (source % 8)) {
+            key[bit / 8] |= 0x80 >> (bit % 8);
+        }
+        else {
+            key[bit / 8] &= !(0x80 >> (bit % 8));
+        }
+    }
+}
+
+/// Parse hexadecimal digits, which can start with `0x` and contain underscores (e.g. the raw key
+/// displayed by `Debug`).
+pub fn parse_hex(hex: &str) -> Result<Vec<u8>, Error> {
+    let hex = hex.trim();
+    let digits: Vec<u8> = hex.strip_prefix("0x").or_else(|| hex.strip_prefix("0X")).unwrap_or(hex)
+        .bytes().filter(|digit| b'_' != *digit).collect();
+    if 0 != digits.len() % 2 {
+        return Err(Error::new(format!("{} has an odd number of hexadecimal digits", hex)));
+    }
+    digits.chunks(2).map(|pair| {
+        std::str::from_utf8(pair).ok().and_then(|pair| u8::from_str_radix(pair, 16).ok())
+            .ok_or_else(|| Error::new(format!("{} isn't hexadecimal", hex)))
+    }).collect()
+}
+
+/// Return the bytes as `0x` followed by lowercase hexadecimal digits.
+pub fn to_hex(bytes: &[u8]) -> String {
+    let mut hex = String::with_capacity(2 + 2 * bytes.len());
+    hex.push_str("0x");
+    for byte in bytes.iter() {
+        hex.push_str(&format!("{:02x}", byte));
+    }
+    hex
+}
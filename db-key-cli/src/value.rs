@@ -0,0 +1,430 @@
+use db_key::Decimal;
+use std::fmt::{Debug, Formatter};
+use std::net::{Ipv4Addr, Ipv6Addr, SocketAddrV4, SocketAddrV6};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use crate::Error;
+use crate::schema::{FieldSchema, load_bits, parse_hex, store_bits, to_hex};
+
+/// The value of a field decoded from a key, which is displayed by `Debug` the same way as the
+/// value returned by the getter of the field is displayed by the `Debug` of the key.
+#[derive(Clone, PartialEq)]
+pub enum Value {
+    Unsigned(u128),
+    Signed(i128),
+    Decimal(Decimal),
+    Bytes(Vec<u8>),
+    Char(char),
+    Chars(Vec<char>),
+    Ipv4(Ipv4Addr),
+    Ipv6(Ipv6Addr),
+    SocketV4(SocketAddrV4),
+    SocketV6(SocketAddrV6),
+    Duration(Duration),
+    SystemTime(SystemTime),
+    /// A value that is displayed as it is, like UUIDs and the hexadecimal bytes of codec fields.
+    Text(String),
+    /// The bytes of a padding field.
+    Padding(Vec<u8>),
+    Some(Box<Value>),
+    None,
+}
+
+impl Debug for Value {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Value::Unsigned(value) => value.fmt(f),
+            Value::Signed(value) => value.fmt(f),
+            Value::Decimal(value) => value.fmt(f),
+            Value::Bytes(value) => value.fmt(f),
+            Value::Char(value) => value.fmt(f),
+            Value::Chars(value) => value.fmt(f),
+            Value::Ipv4(value) => value.fmt(f),
+            Value::Ipv6(value) => value.fmt(f),
+            Value::SocketV4(value) => value.fmt(f),
+            Value::SocketV6(value) => value.fmt(f),
+            Value::Duration(value) => value.fmt(f),
+            Value::SystemTime(value) => value.fmt(f),
+            Value::Text(value) => f.write_str(value),
+            Value::Padding(value) => write!(f, "padding {:?}", value),
+            Value::Some(value) => f.debug_tuple("Some").field(value).finish(),
+            Value::None => f.write_str("None"),
+        }
+    }
+}
+
+/// Return the number of nanoseconds of the time unit of a `duration` or `system_time` field.
+fn unit_nanos(field: &FieldSchema) -> u128 {
+    match field.time_unit.as_deref() {
+        Some("secs") => 1_000_000_000,
+        Some("millis") => 1_000_000,
+        Some("micros") => 1_000,
+        _ => 1,
+    }
+}
+
+/// Return the type of the value of the field without `Option`.
+fn value_type(field: &FieldSchema) -> &str {
+    let rust_type = field.rust_type.as_str();
+    rust_type.strip_prefix("Option<").and_then(|inner| inner.strip_suffix('>'))
+        .unwrap_or(rust_type)
+}
+
+/// Return true if the getter of the field returns an `Option` that is `None` for zero, which is
+/// the case of all non-zero integer fields.
+fn is_nonzero(field: &FieldSchema) -> bool {
+    Some("zero") == field.option.as_deref() || value_type(field).starts_with("NonZero")
+}
+
+/// Return the unsigned integer stored in the right-aligned bytes.
+fn to_u128(bytes: &[u8]) -> u128 {
+    bytes.iter().fold(0, |value, byte| (value << 8) | *byte as u128)
+}
+
+/// Return the `bits` low bits of the integer as right-aligned bytes.
+fn from_u128(value: u128, bits: usize) -> Vec<u8> {
+    let size = (bits + 7) / 8;
+    (0..size).map(|i| (value >> (8 * (size - 1 - i))) as u8).collect()
+}
+
+/// Return the mask of the `bits` low bits.
+fn mask(bits: usize) -> u128 {
+    if 128 <= bits { u128::MAX } else { (1 << bits) - 1 }
+}
+
+/// Decode the value of the field from the key.
+pub fn decode(field: &FieldSchema, key: &[u8]) -> Value {
+    if field.padding {
+        return Value::Padding(field.load(key));
+    }
+    if field.version {
+        return Value::Unsigned(to_u128(&field.load(key)));
+    }
+    if field.has_presence_byte() {
+        let presence = load_bits(key, field.bit_offset, 8)[0];
+        let some = if Some("none_last") == field.option.as_deref() { 0 } else { 1 };
+        if some != presence {
+            return Value::None;
+        }
+        let value_bits = field.bits - 8;
+        let bytes = load_bits(key, field.bit_offset + 8, value_bits);
+        return Value::Some(Box::new(decode_value(field, &bytes, value_bits)));
+    }
+    let bytes = field.load(key);
+    let value = decode_value(field, &bytes, field.bits);
+    if is_nonzero(field) {
+        // Zero bytes are `None`, which is the minimum value of signed types.
+        if Some("zero") == field.option.as_deref() && bytes.iter().all(|byte| 0 == *byte) {
+            return Value::None;
+        }
+        return match value {
+            Value::Unsigned(0) | Value::Signed(0) => Value::None,
+            value => Value::Some(Box::new(value)),
+        };
+    }
+    value
+}
+
+/// Decode the value stored in the `bits` right-aligned bits of the bytes.
+fn decode_value(field: &FieldSchema, bytes: &[u8], bits: usize) -> Value {
+    match field.encoding.as_str() {
+        "uint" | "int" => {
+            let stored = to_u128(bytes);
+            let value = if field.sign_flip {
+                let sign = 1_u128 << (bits - 1);
+                let value = stored ^ sign;
+                // Sign extend the value to 128 bits.
+                if 0 == value & sign { value as i128 } else { (value | !mask(bits)) as i128 }
+            }
+            else {
+                match field.decimal_scale {
+                    Some(_) => stored as i128,
+                    None => return Value::Unsigned(stored),
+                }
+            };
+            match field.decimal_scale {
+                Some(scale) => Value::Decimal(Decimal::new(value, scale)),
+                None => Value::Signed(value),
+            }
+        }
+        "char" => {
+            let size = field.char_size.unwrap_or(4);
+            let chars: Vec<char> = bytes.chunks(size).map(|bytes| {
+                char::from_u32(to_u128(bytes) as u32).unwrap_or(char::REPLACEMENT_CHARACTER)
+            }).collect();
+            match value_type(field) {
+                "char" => Value::Char(chars[0]),
+                _ => Value::Chars(chars),
+            }
+        }
+        "ipv4" => Value::Ipv4(ipv4(bytes)),
+        "ipv6" => Value::Ipv6(ipv6(bytes)),
+        "socket_v4" => Value::SocketV4(SocketAddrV4::new(ipv4(bytes),
+            to_u128(&bytes[4..6]) as u16)),
+        "socket_v6" => Value::SocketV6(SocketAddrV6::new(ipv6(bytes),
+            to_u128(&bytes[16..18]) as u16, to_u128(&bytes[18..22]) as u32,
+            to_u128(&bytes[22..26]) as u32)),
+        "uuid" => {
+            let hex = to_hex(bytes);
+            Value::Text(format!("{}-{}-{}-{}-{}", &hex[2..10], &hex[10..14], &hex[14..18],
+                &hex[18..22], &hex[22..]))
+        }
+        "duration" => Value::Duration(duration(field, bytes)),
+        "system_time" => match UNIX_EPOCH.checked_add(duration(field, bytes)) {
+            Some(time) => Value::SystemTime(time),
+            None => Value::Text(to_hex(bytes)),
+        },
+        "bytes" => Value::Bytes(bytes.to_vec()),
+        _ => Value::Text(to_hex(bytes)),
+    }
+}
+
+fn ipv4(bytes: &[u8]) -> Ipv4Addr {
+    Ipv4Addr::new(bytes[0], bytes[1], bytes[2], bytes[3])
+}
+
+fn ipv6(bytes: &[u8]) -> Ipv6Addr {
+    Ipv6Addr::from(to_u128(&bytes[..16]))
+}
+
+/// Return the duration of the count stored in a `duration` or `system_time` field, which
+/// saturates when it doesn't fit in a `Duration`.
+fn duration(field: &FieldSchema, bytes: &[u8]) -> Duration {
+    let count = to_u128(bytes);
+    let per_sec = 1_000_000_000 / unit_nanos(field);
+    let secs = count / per_sec;
+    let nanos = (count % per_sec * unit_nanos(field)) as u32;
+    if secs > u64::MAX as u128 {
+        return Duration::MAX;
+    }
+    Duration::new(secs as u64, nanos)
+}
+
+/// Encode the text of a value of the field into its stored bits, which are right-aligned in
+/// the smallest number of bytes.
+///
+/// The values are written the way `Debug` displays them, except that `Some()` and the quotes
+/// around characters and text can be omitted. Integers can also be written in hexadecimal, byte
+/// arrays as text or as hexadecimal digits, and times as a count with a unit (e.g. `1500ms`).
+pub fn encode(field: &FieldSchema, text: &str) -> Result<Vec<u8>, Error> {
+    let text = text.trim();
+    if field.padding || field.version {
+        return Err(Error::new(format!("the {} field can't be set", field.name)));
+    }
+    let error = |error: Error| {
+        Error::new(format!("invalid value {} of the {} field: {}", text, field.name, error))
+    };
+    let inner = text.strip_prefix("Some(").and_then(|inner| inner.strip_suffix(')'));
+    let size = (field.bits + 7) / 8;
+    if field.has_presence_byte() {
+        let none_last = Some("none_last") == field.option.as_deref();
+        let mut bytes = vec![0_u8; size];
+        let offset = size * 8 - field.bits;
+        if "None" == text {
+            store_bits(&mut bytes, offset, 8, &[none_last as u8]);
+        }
+        else {
+            let value_bits = field.bits - 8;
+            let value = encode_value(field, inner.unwrap_or(text), value_bits).map_err(error)?;
+            store_bits(&mut bytes, offset, 8, &[!none_last as u8]);
+            store_bits(&mut bytes, offset + 8, value_bits, &value);
+        }
+        return Ok(bytes);
+    }
+    if is_nonzero(field) {
+        if "None" == text {
+            if Some("zero") != field.option.as_deref() {
+                return Err(Error::new(format!("the {} field can't be None", field.name)));
+            }
+            return Ok(vec![0; size]);
+        }
+        let value = encode_value(field, inner.unwrap_or(text), field.bits).map_err(error)?;
+        let zero = encode_value(field, "0", field.bits).map_err(error)?;
+        if zero == value {
+            return Err(Error::new(format!("the {} field can't be zero", field.name)));
+        }
+        if Some("zero") == field.option.as_deref() && value.iter().all(|byte| 0 == *byte) {
+            return Err(Error::new(format!("the minimum value of the {} field is reserved for None",
+                field.name)));
+        }
+        return Ok(value);
+    }
+    encode_value(field, text, field.bits).map_err(error)
+}
+
+/// Encode the text of a value into `bits` right-aligned bits.
+fn encode_value(field: &FieldSchema, text: &str, bits: usize) -> Result<Vec<u8>, Error> {
+    let size = (bits + 7) / 8;
+    match field.encoding.as_str() {
+        "uint" | "int" => {
+            let value = match field.decimal_scale {
+                Some(scale) => Decimal::parse(text, scale)
+                    .map_err(|error| Error::new(error.to_string()))?.value(),
+                None => parse_integer(text)?,
+            };
+            let stored = if field.sign_flip {
+                let max = (mask(bits) >> 1) as i128;
+                if value < -max - 1 || max < value {
+                    return Err(Error::new(format!("the value is outside {}..={}", -max - 1,
+                        max)));
+                }
+                (value as u128 & mask(bits)) ^ (1 << (bits - 1))
+            }
+            else {
+                if value < 0 || mask(bits) < value as u128 {
+                    return Err(Error::new(format!("the value is outside 0..={}", mask(bits))));
+                }
+                value as u128
+            };
+            Ok(from_u128(stored, bits))
+        }
+        "bytes" => {
+            let mut bytes = parse_bytes(text)?;
+            if Some("ascii_ci_trim") == field.collation.as_deref() {
+                // The surrounding whitespace doesn't count in the size of the value.
+                bytes = collate(field, bytes);
+                bytes.truncate(bytes.iter().rposition(|byte| 0 != *byte).map_or(0, |end| end + 1));
+            }
+            if size < bytes.len() {
+                return Err(Error::new(format!("the value is longer than {} bytes", size)));
+            }
+            bytes.resize(size, 0);
+            Ok(collate(field, bytes))
+        }
+        "char" => {
+            let text = unquote(text, '\'').or_else(|| unquote(text, '"')).unwrap_or(text);
+            let char_size = field.char_size.unwrap_or(4);
+            let chars: Vec<char> = text.chars().collect();
+            if chars.len() * char_size != size {
+                return Err(Error::new(format!("expected {} characters", size / char_size)));
+            }
+            Ok(chars.iter().flat_map(|c| from_u128(*c as u128, 8 * char_size)).collect())
+        }
+        "ipv4" => Ok(parse::<Ipv4Addr>(text, "IPv4 address")?.octets().to_vec()),
+        "ipv6" => Ok(parse::<Ipv6Addr>(text, "IPv6 address")?.octets().to_vec()),
+        "socket_v4" => {
+            let socket = parse::<SocketAddrV4>(text, "IPv4 socket address")?;
+            let mut bytes = socket.ip().octets().to_vec();
+            bytes.extend_from_slice(&socket.port().to_be_bytes());
+            Ok(bytes)
+        }
+        "socket_v6" => {
+            let socket = parse::<SocketAddrV6>(text, "IPv6 socket address")?;
+            let mut bytes = socket.ip().octets().to_vec();
+            bytes.extend_from_slice(&socket.port().to_be_bytes());
+            bytes.extend_from_slice(&socket.flowinfo().to_be_bytes());
+            bytes.extend_from_slice(&socket.scope_id().to_be_bytes());
+            Ok(bytes)
+        }
+        "uuid" => {
+            let bytes = parse_hex(&text.replace('-', ""))?;
+            if 16 != bytes.len() {
+                return Err(Error::new("expected 32 hexadecimal digits".to_string()));
+            }
+            Ok(bytes)
+        }
+        "duration" | "system_time" => {
+            let count = parse_time(text, unit_nanos(field))?;
+            if mask(bits) < count {
+                return Err(Error::new(format!("the count of {} is more than {}",
+                    field.time_unit.as_deref().unwrap_or("nanos"), mask(bits))));
+            }
+            Ok(from_u128(count, bits))
+        }
+        _ => {
+            let bytes = parse_hex(text)?;
+            if size != bytes.len() {
+                return Err(Error::new(format!("expected {} bytes", size)));
+            }
+            Ok(bytes)
+        }
+    }
+}
+
+/// Return the text between the quotes, if it is quoted.
+fn unquote(text: &str, quote: char) -> Option<&str> {
+    if 2 <= text.len() {
+        text.strip_prefix(quote).and_then(|text| text.strip_suffix(quote))
+    }
+    else {
+        None
+    }
+}
+
+fn parse<T: std::str::FromStr>(text: &str, what: &str) -> Result<T, Error> {
+    text.parse().map_err(|_| Error::new(format!("expected an {}", what)))
+}
+
+/// Parse a decimal or hexadecimal integer, which can contain underscores.
+fn parse_integer(text: &str) -> Result<i128, Error> {
+    let digits = text.replace('_', "");
+    let (negative, digits) = match digits.strip_prefix('-') {
+        Some(digits) => (true, digits),
+        None => (false, digits.as_str()),
+    };
+    let magnitude = match digits.strip_prefix("0x").or_else(|| digits.strip_prefix("0X")) {
+        Some(hex) => u128::from_str_radix(hex, 16),
+        None => digits.parse(),
+    }.map_err(|_| Error::new("expected an integer".to_string()))?;
+    if negative {
+        // The cast wraps the magnitude of i128::MIN to itself.
+        if magnitude > i128::MAX as u128 + 1 {
+            return Err(Error::new("the value is too small".to_string()));
+        }
+        Ok((magnitude as i128).wrapping_neg())
+    }
+    else {
+        if magnitude > i128::MAX as u128 {
+            return Err(Error::new("the value is too large".to_string()));
+        }
+        Ok(magnitude as i128)
+    }
+}
+
+/// Parse the bytes of a byte array, which are written as a list (e.g. `[1, 2]`), hexadecimal
+/// digits starting with `0x`, or text.
+fn parse_bytes(text: &str) -> Result<Vec<u8>, Error> {
+    if let Some(list) = text.strip_prefix('[').and_then(|list| list.strip_suffix(']')) {
+        return list.split(',').map(str::trim).filter(|byte| !byte.is_empty()).map(|byte| {
+            parse_integer(byte).ok().filter(|byte| (0..=255).contains(byte)).map(|byte| byte as u8)
+                .ok_or_else(|| Error::new(format!("{} isn't a byte", byte)))
+        }).collect();
+    }
+    if text.starts_with("0x") || text.starts_with("0X") {
+        return parse_hex(text);
+    }
+    Ok(unquote(text, '"').unwrap_or(text).as_bytes().to_vec())
+}
+
+/// Normalize the bytes of a byte array field with the collation of the field.
+fn collate(field: &FieldSchema, mut bytes: Vec<u8>) -> Vec<u8> {
+    match field.collation.as_deref() {
+        Some("ascii_ci") => bytes.make_ascii_lowercase(),
+        Some("ascii_ci_trim") => {
+            let size = bytes.len();
+            let start = bytes.iter().position(|byte| !byte.is_ascii_whitespace()).unwrap_or(size);
+            let end = bytes.iter().rposition(|byte| !byte.is_ascii_whitespace() && 0 != *byte)
+                .map_or(start, |end| end + 1);
+            bytes = bytes[start..std::cmp::max(start, end)].to_ascii_lowercase();
+            bytes.resize(size, 0);
+        }
+        _ => {}
+    }
+    bytes
+}
+
+/// Parse a time as a count of the unit of the field, which is written as a count, or as a
+/// number followed by a unit of `s`, `ms`, `us`, or `ns` (e.g. `1.5s`, as `Debug` displays
+/// durations). The time is truncated to the unit of the field.
+fn parse_time(text: &str, unit_nanos: u128) -> Result<u128, Error> {
+    let units = [("ns", 1), ("us", 1_000), ("µs", 1_000), ("ms", 1_000_000),
+        ("s", 1_000_000_000)];
+    let (number, nanos) = units.iter().find_map(|(unit, nanos)| {
+        text.strip_suffix(unit).map(|number| (number, *nanos))
+    }).unwrap_or((text, unit_nanos));
+    let time = Decimal::parse(number, 9).ok().filter(|time| 0 <= time.value())
+        .ok_or_else(|| Error::new("expected a count or a time with a unit of s, ms, us, or ns"
+            .to_string()))?;
+    (time.value() as u128).checked_mul(nanos)
+        .map(|nanos| nanos / 1_000_000_000 / unit_nanos)
+        .ok_or_else(|| Error::new("the time is too large".to_string()))
+}
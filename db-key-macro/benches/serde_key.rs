@@ -19,8 +19,8 @@ pub trait SerializableKey: serde::Serialize + serde::de::DeserializeOwned {
         Ok(key)
     }
 
-    fn deserialize<'a>(raw_key: &'a [u8]) -> Result<Self, bincode::Error> {
-        Ok(key_serializer().deserialize_from(raw_key)?)
+    fn deserialize(raw_key: &[u8]) -> Result<Self, bincode::Error> {
+        key_serializer().deserialize_from(raw_key)
     }
 }
 
@@ -1,6 +1,8 @@
 use proc_macro2::{
     Ident,
     LexError,
+    Literal,
+    Span,
     TokenStream,
 };
 use quote::{quote, quote_spanned};
@@ -15,7 +17,9 @@ use syn::{
     Field,
     Lit,
     Meta,
+    Path,
     Result,
+    Visibility,
     spanned::Spanned,
 };
 use std::{
@@ -23,8 +27,8 @@ use std::{
 };
 use crate::{
     field::{
-        value::FieldValue,
-        r#type::{FieldSize, FieldType},
+        value::{FieldValue, ReferenceRng},
+        r#type::{Collation, FieldSize, FieldType, OptionOrder, TimeUnit},
     },
     parse::DBKeyStruct,
 };
@@ -36,12 +40,15 @@ pub mod value;
 #[derive(Debug, Default)]
 pub struct DBKeyFields {
     fields: Vec<DBKeyField>,
+    /// The number of unused bits after the last field to pad the key to a whole byte.
+    padding_after: usize,
 }
 
 macro_rules! impl_fields {
     ($(($fn_name: ident, $name: literal),)*) => {
         $(
         #[doc = concat!("Aggregate the ", $name, " data for all fields")]
+        #[allow(clippy::wrong_self_convention)]
         pub fn $fn_name(&self) -> Vec<TokenStream> {
             let mut streams = Vec::with_capacity(self.fields.len());
             for field in self.fields.iter() {
@@ -72,6 +79,7 @@ macro_rules! impl_fields_tests {
     ($(($fn_name:ident, $fn_verify:ident, $init_fn:ident, $verify_fn:ident, $key_name:ident, $docs:literal),)*) => {
         $(
         #[doc = $docs]
+        #[allow(clippy::wrong_self_convention)]
         pub fn $fn_name(
             &self,
             head: ::std::fmt::Arguments<'_>,
@@ -107,7 +115,6 @@ macro_rules! impl_fields_tests {
 impl DBKeyFields {
     impl_fields! {
         (consts, "constants"),
-        (sizes, "sizes"),
         (params, "new parameters"),
         (struct_fields, "new structure fields"),
         (defines, "code to initialize new()"),
@@ -117,25 +124,146 @@ impl DBKeyFields {
         (minimums, "code to implement MIN_KEY for the key structure"),
         (maximums, "code to implement MAX_KEY for the key structure"),
         (arg_defaults, "code to implement the Default for the arguments structure"),
+        (checks, "compile time checks"),
+        (computes, "code to store the computed fields"),
+        (validates, "code to validate the computed fields"),
+        (padding_checks, "code to check that the padding fields are zero"),
+        (version_names, "names of the fields of a versioned key"),
+        (missing_fields, "getters of the fields missing from the previous version"),
+        (migrates, "code to convert the previous version"),
+        (to_args, "code to convert the key to the arguments"),
+        (keeps, "code to keep the fields without a public setter in update()"),
+        (pattern_fields, "fields of the pattern structure"),
+        (pattern_args, "code to set the arguments to the values of a pattern"),
+        (pattern_matches, "code to match a key with a pattern"),
+        (pattern_fits, "code to check that the values of a pattern fit in the fields"),
+        (pattern_froms, "code to create a pattern from the arguments"),
+        (pattern_residuals, "code to set the residual pattern of a range"),
+        (field_schemas, "schemas of the fields for the SCHEMA constant"),
     }
     impl_fields_self! {
         (gets, "get functions"),
         (sets, "set functions"),
+        (migrate_checks, "compile time checks of the conversion from the previous version"),
+        (arbitraries, "proptest strategies of the argument fields"),
+        (arbitrary_fields, "arbitrary values of the argument fields"),
+        (pattern_bounds, "code to set the start and end of the range of a pattern"),
     }
     impl_fields_tests! {
-        (new_init_doc, verify_new_parts, new_init_doc, verify_parts, new_key, ""),
-        (new_init_partial, verify_new_partial, new_init_partial, verify_partial, partial_new_key, ""),
-        (from_init_doc, verify_from_parts, from_init_doc, verify_parts, from_key, ""),
-        (from_init_partial, verify_from_partial, from_init_partial, verify_partial, partial_from_key, ""),
+        (new_init_doc, verify_new_parts, new_init_doc, verify_parts, new_key, "Generate the new() doctest lines for all fields"),
+        (new_init_partial, verify_new_partial, new_init_partial, verify_partial, partial_new_key, "Generate the partial new() doctest lines for all fields"),
+        (from_init_doc, verify_from_parts, from_init_doc, verify_parts, from_key, "Generate the from() doctest lines for all fields"),
+        (from_init_partial, verify_from_partial, from_init_partial, verify_partial, partial_from_key, "Generate the partial from() doctest lines for all fields"),
+    }
+
+    /// Set the name of the crate used by the documentation examples of all fields.
+    pub fn set_crate_name(&mut self, crate_name: &str) {
+        for field in self.fields.iter_mut() {
+            field.set_crate_name(crate_name);
+        }
+    }
+
+    /// Return true if any field has a codec or a newtype, which may not be `Copy`.
+    pub fn has_custom_type(&self) -> bool {
+        self.fields.iter().any(|field| field.has_custom_type())
+    }
+
+    /// Remove the computed and padding fields from the argument structure when the macro defines
+    /// it.
+    pub fn set_define_args(&mut self, define_args: bool) {
+        for field in self.fields.iter_mut() {
+            if define_args && !field.is_input() {
+                field.in_args = false;
+            }
+        }
+    }
+
+    /// Add a padding field named `_reserved` with `size` bytes after the other fields for the
+    /// `reserve` attribute. The field isn't in the definition structure of the derive macro.
+    pub fn add_reserve(&mut self, size: usize) -> Result<()> {
+        let size = proc_macro2::Literal::usize_unsuffixed(size);
+        let field: Field = syn::parse_quote! {
+            #[padding]
+            _reserved: [u8; #size]
+        };
+        let mut next_bit = self.total_size() * 8;
+        let mut reserved = DBKeyField::try_new(&field, &mut next_bit)?;
+        reserved.in_args = false;
+        self.fields.push(reserved);
+        self.padding_after = 0;
+        Ok(())
+    }
+
+    /// Add the version field as the first byte of the key for the `version` attribute, which
+    /// isn't in the definition structure of the derive macro.
+    pub fn add_version(&mut self, version: u8) -> Result<()> {
+        let version = Literal::u8_unsuffixed(version);
+        let field: Field = syn::parse_quote! {
+            #[default = #version]
+            #[min = #version]
+            #[max = #version]
+            _version: u8
+        };
+        let mut next_bit = 0;
+        let mut version_field = DBKeyField::try_new(&field, &mut next_bit)?;
+        version_field.version = true;
+        version_field.in_args = false;
+        for field in self.fields.iter_mut() {
+            field.bit_offset += 8;
+            field.start_index += 1;
+            field.end_index += 1;
+        }
+        self.fields.insert(0, version_field);
+        Ok(())
+    }
+
+    /// Return true if any field has the `computed` attribute.
+    pub fn has_computed(&self) -> bool {
+        self.fields.iter().any(|field| field.is_computed())
+    }
+
+    /// Return true if any argument field doesn't have a public setter, because it has the
+    /// `readonly` or `hidden` attribute or a restricted visibility.
+    pub fn has_kept(&self) -> bool {
+        self.fields.iter().any(|field| field.is_kept())
+    }
+
+    /// Return the schema description of the key, with one line for each field in the order of the
+    /// fields in the key.
+    pub fn schema_description(&self) -> String {
+        self.fields.iter().map(|field| field.schema()).collect::<Vec<_>>().join("\n")
+    }
+
+    /// Return the field values of the argument structure for a reference key of the layout
+    /// snapshot test, which are generated from the seed.
+    pub fn references(&self, seed: u64) -> Vec<TokenStream> {
+        let mut rng = ReferenceRng::new(seed);
+        self.fields.iter().map(|field| field.reference(&mut rng)).collect()
     }
 
     /// Return the total size of the key in bytes.
     pub fn total_size(&self) -> usize {
-        let mut total = 0;
+        match self.fields.last() {
+            Some(field) => (field.bit_offset + field.bits() + self.padding_after) / 8,
+            None => 0,
+        }
+    }
+
+    /// Aggregate the sizes in bits of the fields and the padding between them.
+    pub fn sizes(&self) -> Vec<TokenStream> {
+        let mut streams = Vec::with_capacity(self.fields.len());
         for field in self.fields.iter() {
-            total += field.field_type.size();
+            if 0 < field.padding_before {
+                let padding = field.padding_before;
+                streams.push(quote! { #padding });
+            }
+            streams.push(field.sizes());
         }
-        total
+        if 0 < self.padding_after {
+            let padding = self.padding_after;
+            streams.push(quote! { #padding });
+        }
+        streams
     }
 }
 
@@ -148,12 +276,13 @@ impl TryFrom<&DeriveInput> for DBKeyFields {
                 match &data.fields {
                     Fields::Named(named_fields) => {
                         let mut fields = Vec::<DBKeyField>::with_capacity(named_fields.named.len());
-                        let mut size = 0;
+                        let mut bit_offset = 0;
                         for field in named_fields.named.iter() {
-                            fields.push(DBKeyField::try_new(field, &mut size)?);
+                            fields.push(DBKeyField::try_new(field, &mut bit_offset)?);
                         }
                         Ok(Self {
                             fields,
+                            padding_after: (8 - bit_offset % 8) % 8,
                         })
                     }
                     Fields::Unnamed(_) => {
@@ -178,6 +307,31 @@ impl TryFrom<&DeriveInput> for DBKeyFields {
     }
 }
 
+/// What to do when a value doesn't fit in an integer field stored in fewer bytes than its type.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub enum OverflowPolicy {
+    /// Panic if the value is out of range.
+    #[default]
+    Panic,
+    /// Only store the low bytes of the value.
+    Truncate,
+    /// Store the closest value that fits in the field.
+    Saturate,
+}
+
+impl TryFrom<&str> for OverflowPolicy {
+    type Error = ();
+
+    fn try_from(s: &str) -> std::result::Result<Self, Self::Error> {
+        match s {
+            "panic" => Ok(OverflowPolicy::Panic),
+            "truncate" => Ok(OverflowPolicy::Truncate),
+            "saturate" => Ok(OverflowPolicy::Saturate),
+            _ => Err(()),
+        }
+    }
+}
+
 #[derive(Debug)]
 struct FieldAttributes {
     docs: Vec<Attribute>,
@@ -185,17 +339,37 @@ struct FieldAttributes {
     default: TokenStream,
     minimum: TokenStream,
     maximum: TokenStream,
+    overflow: OverflowPolicy,
+    /// The number of fractional digits of a field with the `decimal` attribute.
+    scale: Option<u32>,
+    /// The function that computes the value of a field with the `computed` attribute from the
+    /// key.
+    computed: Option<Path>,
+    /// The field doesn't have a setter.
+    readonly: bool,
+    /// The field is zero-filled padding reserved for future fields.
+    padding: bool,
+    /// The visibility of the getters and setters of the field, which is private for fields with
+    /// the `hidden` attribute.
+    vis: Visibility,
 }
 
 impl FieldAttributes {
     /// Parse the attribute values for a field. It returns a tuple with a vector of the doc
     /// attributes and the proper name for the field.
-    pub fn try_new(field: &Field, field_type: &FieldType) -> Result<Self> {
+    pub fn try_new(field: &Field, field_type: &mut FieldType) -> Result<Self> {
         let mut docs = Vec::new();
         let mut name = field.ident.clone().unwrap().to_string();
-        let mut default = field_type.default_lit();
-        let mut minimum = field_type.minimum_lit();
-        let mut maximum = field_type.maximum_lit();
+        let mut default = None;
+        let mut minimum = None;
+        let mut maximum = None;
+        let mut overflow = None;
+        let mut scale = None;
+        let mut computed = None;
+        let mut readonly = false;
+        let mut padding = None;
+        let mut hidden = None;
+        let mut vis = None;
         for attr in field.attrs.iter() {
             if let Some(ident) = attr.path().get_ident() {
                 let s = ident.to_string();
@@ -233,7 +407,7 @@ impl FieldAttributes {
                     "default" => {
                         match &attr.meta {
                             Meta::NameValue(name_value) => {
-                                default = Self::parse_default_value(&name_value.value)?;
+                                default = Some(Self::parse_default_value(&name_value.value)?);
                             }
                             _ => {
                                 return Err(Error::new(ident.span(),
@@ -244,7 +418,7 @@ impl FieldAttributes {
                     "min" => {
                         match &attr.meta {
                             Meta::NameValue(name_value) => {
-                                minimum = Self::parse_default_value(&name_value.value)?;
+                                minimum = Some(Self::parse_default_value(&name_value.value)?);
                             }
                             _ => {
                                 return Err(Error::new(ident.span(),
@@ -255,7 +429,7 @@ impl FieldAttributes {
                     "max" => {
                         match &attr.meta {
                             Meta::NameValue(name_value) => {
-                                maximum = Self::parse_default_value(&name_value.value)?;
+                                maximum = Some(Self::parse_default_value(&name_value.value)?);
                             }
                             _ => {
                                 return Err(Error::new(ident.span(),
@@ -263,21 +437,311 @@ impl FieldAttributes {
                             }
                         }
                     }
+                    "bytes" => {
+                        match &attr.meta {
+                            Meta::NameValue(name_value) => {
+                                let width = Self::parse_usize(&name_value.value,
+                                    "The bytes attribute expects an integer literal.")?;
+                                field_type.set_width(width, name_value.value.span())?;
+                            }
+                            _ => {
+                                return Err(Error::new(ident.span(),
+                                    "The bytes attribute expects a value."));
+                            }
+                        }
+                    }
+                    "bits" => {
+                        match &attr.meta {
+                            Meta::NameValue(name_value) => {
+                                let bits = Self::parse_usize(&name_value.value,
+                                    "The bits attribute expects an integer literal.")?;
+                                field_type.set_bits(bits, name_value.value.span())?;
+                            }
+                            _ => {
+                                return Err(Error::new(ident.span(),
+                                    "The bits attribute expects a value."));
+                            }
+                        }
+                    }
+                    "overflow" => {
+                        match &attr.meta {
+                            Meta::NameValue(name_value) => {
+                                let policy = Self::parse_ident_or_str(&name_value.value)
+                                    .and_then(|s| OverflowPolicy::try_from(s.as_str()).ok());
+                                match policy {
+                                    Some(policy) => { overflow = Some((policy, ident.span())); }
+                                    None => {
+                                        return Err(Error::new(name_value.value.span(),
+                                            "Unknown overflow policy (expected panic, truncate, or saturate)."));
+                                    }
+                                }
+                            }
+                            _ => {
+                                return Err(Error::new(ident.span(),
+                                    "The overflow attribute expects a value."));
+                            }
+                        }
+                    }
+                    "time_unit" => {
+                        match &attr.meta {
+                            Meta::NameValue(name_value) => {
+                                let time_unit = Self::parse_ident_or_str(&name_value.value)
+                                    .and_then(|s| TimeUnit::try_from(s.as_str()).ok());
+                                match time_unit {
+                                    Some(time_unit) => {
+                                        field_type.set_time_unit(time_unit, ident.span())?;
+                                    }
+                                    None => {
+                                        return Err(Error::new(name_value.value.span(),
+                                            "Unknown time unit (expected s, ms, us, or ns)."));
+                                    }
+                                }
+                            }
+                            _ => {
+                                return Err(Error::new(ident.span(),
+                                    "The time_unit attribute expects a value."));
+                            }
+                        }
+                    }
+                    "collate" => {
+                        match &attr.meta {
+                            Meta::NameValue(name_value) => {
+                                let collation = Self::parse_ident_or_str(&name_value.value)
+                                    .and_then(|s| Collation::try_from(s.as_str()).ok());
+                                match collation {
+                                    Some(collation) => {
+                                        field_type.set_collation(collation, ident.span())?;
+                                    }
+                                    None => {
+                                        return Err(Error::new(name_value.value.span(),
+                                            "Unknown collation (expected binary, ascii_ci, or ascii_ci_trim)."));
+                                    }
+                                }
+                            }
+                            _ => {
+                                return Err(Error::new(ident.span(),
+                                    "The collate attribute expects a value."));
+                            }
+                        }
+                    }
+                    "none_last" => {
+                        match &attr.meta {
+                            Meta::Path(_) => {
+                                field_type.set_none_last(ident.span())?;
+                            }
+                            _ => {
+                                return Err(Error::new(ident.span(),
+                                    "The none_last attribute doesn't take a value."));
+                            }
+                        }
+                    }
+                    "decimal" => {
+                        let mut decimal_scale = None;
+                        attr.parse_nested_meta(|meta| {
+                            if meta.path.is_ident("scale") {
+                                let value: Expr = meta.value()?.parse()?;
+                                decimal_scale = Some(Self::parse_usize(&value,
+                                    "The decimal scale must be an integer.")?);
+                                Ok(())
+                            }
+                            else {
+                                Err(meta.error("Unknown decimal option (expected scale)."))
+                            }
+                        })?;
+                        match decimal_scale {
+                            Some(decimal_scale) => {
+                                scale = Some((decimal_scale, ident.span()));
+                            }
+                            None => {
+                                return Err(Error::new(ident.span(),
+                                    "The decimal attribute expects a scale (e.g. decimal(scale = 4))."));
+                            }
+                        }
+                    }
+                    "computed" => {
+                        match &attr.meta {
+                            Meta::NameValue(name_value) => {
+                                computed = Some(Self::parse_path(&name_value.value,
+                                    "The computed attribute expects the path of a function.")?);
+                            }
+                            _ => {
+                                return Err(Error::new(ident.span(),
+                                    "The computed attribute expects the path of a function."));
+                            }
+                        }
+                    }
+                    "readonly" => {
+                        match &attr.meta {
+                            Meta::Path(_) => {
+                                readonly = true;
+                            }
+                            _ => {
+                                return Err(Error::new(ident.span(),
+                                    "The readonly attribute doesn't take a value."));
+                            }
+                        }
+                    }
+                    "padding" => {
+                        match &attr.meta {
+                            Meta::Path(_) => {
+                                padding = Some(ident.span());
+                            }
+                            _ => {
+                                return Err(Error::new(ident.span(),
+                                    "The padding attribute doesn't take a value."));
+                            }
+                        }
+                    }
+                    "hidden" => {
+                        match &attr.meta {
+                            Meta::Path(_) => {
+                                hidden = Some(ident.span());
+                            }
+                            _ => {
+                                return Err(Error::new(ident.span(),
+                                    "The hidden attribute doesn't take a value."));
+                            }
+                        }
+                    }
+                    "vis" => {
+                        let error = "The vis attribute expects a visibility (e.g. vis(pub(crate)) or vis = \"pub(crate)\").";
+                        vis = Some(match &attr.meta {
+                            Meta::List(list) => list.parse_args::<Visibility>()
+                                .map_err(|err| Error::new(err.span(), error))?,
+                            Meta::NameValue(name_value) => match &name_value.value {
+                                Expr::Lit(expr_lit) => match &expr_lit.lit {
+                                    Lit::Str(lit_str) => lit_str.parse::<Visibility>()
+                                        .map_err(|err| Error::new(lit_str.span(), err))?,
+                                    lit => { return Err(Error::new(lit.span(), error)); }
+                                }
+                                value => { return Err(Error::new(value.span(), error)); }
+                            }
+                            Meta::Path(_) => { return Err(Error::new(ident.span(), error)); }
+                        });
+                    }
+                    "codec" | "via" => {
+                        // The codec and via attributes are parsed with the type of the field.
+                    }
                     unknown => {
                         println!("Unexpected attribute: {}", unknown);
                     }
                 }
             }
         }
+        if field_type.codec.is_some() && 0 == field_type.size() {
+            return Err(Error::new(field.ty.span(),
+                "Codec fields require the bytes attribute with the size of the encoded value."));
+        }
+        let overflow = match overflow {
+            Some((OverflowPolicy::Truncate, span)) if field_type.size.is_time() => {
+                return Err(Error::new(span,
+                    "Time fields only support the panic and saturate overflow policies."));
+            }
+            Some((_, span)) if !field_type.is_reduced() && !field_type.size.is_time() => {
+                return Err(Error::new(span,
+                    "The overflow attribute requires the bytes or bits attribute to narrow the field."));
+            }
+            Some((policy, _)) => policy,
+            None => OverflowPolicy::default(),
+        };
+        let scale = match scale {
+            Some((_, span)) if !field_type.size.is_integer() || field_type.is_option() ||
+                field_type.newtype.is_some() || field_type.nonzero.is_some() => {
+                return Err(Error::new(span,
+                    "The decimal attribute is only supported by integer fields."));
+            }
+            Some((_, span)) if matches!(field_type.size,
+                FieldSize::Signed128 | FieldSize::Unsigned128) => {
+                return Err(Error::new(span,
+                    "The decimal attribute doesn't support 128-bit integer fields."));
+            }
+            Some((scale, span)) if !(1..=18).contains(&scale) => {
+                return Err(Error::new(span, "The decimal scale must be from 1 to 18."));
+            }
+            Some((scale, _)) => Some(scale as u32),
+            None => None,
+        };
+        if let Some(span) = padding {
+            if !matches!(field_type.size, FieldSize::Array(_)) || field_type.is_option() ||
+                field_type.newtype.is_some() || Collation::Binary != field_type.collation {
+                return Err(Error::new(span,
+                    "The padding attribute is only supported by u8 array fields."));
+            }
+            if computed.is_some() || default.is_some() || minimum.is_some() ||
+                maximum.is_some() {
+                return Err(Error::new(span, concat!("The padding attribute can't be combined ",
+                    "with the computed, default, min, or max attributes.")));
+            }
+        }
+        let vis = match (hidden, vis) {
+            (Some(span), Some(_)) => {
+                return Err(Error::new(span, "The hidden and vis attributes can't be combined."));
+            }
+            (Some(_), None) => Visibility::Inherited,
+            (None, Some(vis)) => vis,
+            (None, None) => syn::parse_quote! { pub },
+        };
         Ok(Self {
             docs,
             name,
-            default,
-            minimum,
-            maximum,
+            default: default.unwrap_or_else(|| field_type.field_default_lit()),
+            minimum: minimum.unwrap_or_else(|| field_type.field_minimum_lit()),
+            // Padding fields are always zero.
+            maximum: maximum.unwrap_or_else(|| if padding.is_some() {
+                field_type.field_default_lit()
+            }
+            else {
+                field_type.field_maximum_lit()
+            }),
+            overflow,
+            scale,
+            computed,
+            readonly,
+            padding: padding.is_some(),
+            vis,
         })
     }
 
+    /// Parse an integer literal (or a string containing one) as a `usize`.
+    fn parse_usize(value: &Expr, error: &str) -> Result<usize> {
+        if let Expr::Lit(expr_lit) = value {
+            match &expr_lit.lit {
+                Lit::Int(lit_int) => { return lit_int.base10_parse(); }
+                Lit::Str(lit_str) => {
+                    if let Ok(value) = lit_str.value().trim().parse() {
+                        return Ok(value);
+                    }
+                }
+                _ => {}
+            }
+        }
+        Err(Error::new(value.span(), error))
+    }
+
+    /// Parse a path (or a string containing one).
+    fn parse_path(value: &Expr, error: &str) -> Result<Path> {
+        match value {
+            Expr::Path(expr_path) if expr_path.qself.is_none() => Ok(expr_path.path.clone()),
+            Expr::Lit(expr_lit) => match &expr_lit.lit {
+                Lit::Str(lit_str) => lit_str.parse(),
+                _ => Err(Error::new(value.span(), error)),
+            }
+            _ => Err(Error::new(value.span(), error)),
+        }
+    }
+
+    /// Parse a single identifier or a literal string into a `String`.
+    fn parse_ident_or_str(value: &Expr) -> Option<String> {
+        match value {
+            Expr::Path(expr_path) => expr_path.path.get_ident().map(|ident| ident.to_string()),
+            Expr::Lit(expr_lit) => match &expr_lit.lit {
+                Lit::Str(lit_str) => Some(lit_str.value()),
+                _ => None,
+            }
+            _ => None,
+        }
+    }
+
     fn parse_default_value(value: &Expr) -> Result<TokenStream> {
         match value {
             Expr::Lit(lit) => {
@@ -313,66 +777,17 @@ impl FieldAttributes {
     }
 }
 
+/// Return a `u128` literal.
+fn u128_lit(value: u128) -> TokenStream {
+    let lit = proc_macro2::Literal::u128_suffixed(value);
+    quote! { #lit }
+}
+
 macro_rules! impl_const_define {
     ($fn_ident:ident, $attr_ident:ident, $const_name:literal) => {
         #[doc = concat!("Define the code to initialize ", $const_name, " for this field.")]
         pub fn $fn_ident(&self) -> TokenStream {
-            let field_type = &self.field_type;
-            let xor_mask = &self.field_type.minimum_lit();
-            let value = &self.attr.$attr_ident;
-            match self.field_type.size {
-                FieldSize::Signed8 | FieldSize::Signed16 | FieldSize::Signed32 |
-                    FieldSize::Signed64 | FieldSize::Signed128 =>
-                {
-                    let size = field_type.size();
-                    quote! {
-                        let value: #field_type = #value ^ #xor_mask;
-                        if 0 == value {
-                            buf_i += #size;
-                        }
-                        else {
-                            let bytes = value.to_be_bytes();
-                            let mut i = 0;
-                            while i < bytes.len() {
-                                buf[buf_i] = bytes[i];
-                                buf_i += 1;
-                                i += 1;
-                            }
-                        }
-                    }
-                }
-                FieldSize::Unsigned8 | FieldSize::Unsigned16 | FieldSize::Unsigned32 |
-                    FieldSize::Unsigned64 | FieldSize::Unsigned128 =>
-                {
-                    let size = field_type.size();
-                    quote! {
-                        let value: #field_type = #value;
-                        if 0 == value {
-                            buf_i += #size;
-                        }
-                        else {
-                            let bytes = value.to_be_bytes();
-                            let mut i = 0;
-                            while i < bytes.len() {
-                                buf[buf_i] = bytes[i];
-                                buf_i += 1;
-                                i += 1;
-                            }
-                        }
-                    }
-                }
-                FieldSize::Array(_) => {
-                    quote! {
-                        let value: #field_type = #value;
-                        let mut i = 0;
-                        while i < value.len() {
-                            buf[buf_i] = value[i];
-                            buf_i += 1;
-                            i += 1;
-                        }
-                    }
-                }
-            }
+            self.const_define(&self.attr.$attr_ident, $const_name)
         }
     };
 }
@@ -389,13 +804,27 @@ pub struct DBKeyField {
     default_ident: Ident,
     min_ident: Ident,
     max_ident: Ident,
+    bits_ident: Ident,
+    bit_offset_ident: Ident,
+    /// The range of the bytes of the value, which excludes the presence byte of `Option` fields.
+    value_range_ident: Ident,
     field_type: FieldType,
     random: FieldValue,
     start_index: usize,
+    end_index: usize,
+    /// The offset of the first bit of the field from the start of the key.
+    bit_offset: usize,
+    /// The number of unused bits before the field to align it to a whole byte.
+    padding_before: usize,
+    /// The field is in the argument structure, which doesn't have the computed fields when it's
+    /// defined by the attribute macro.
+    in_args: bool,
+    /// The field is the version byte added by the `version` attribute.
+    version: bool,
 }
 
 impl DBKeyField {
-    pub fn try_new(field: &Field, start_byte: &mut usize) -> Result<Self> {
+    pub fn try_new(field: &Field, next_bit: &mut usize) -> Result<Self> {
         let ident = field.ident.clone().unwrap();
         let upper_str = ident.to_string().to_uppercase();
         let set_ident = Ident::new(&format!("set_{}", ident), ident.span());
@@ -406,11 +835,23 @@ impl DBKeyField {
         let default_ident = Ident::new(&format!("{}_DEFAULT", upper_str), ident.span());
         let min_ident = Ident::new(&format!("{}_MIN", upper_str), ident.span());
         let max_ident = Ident::new(&format!("{}_MAX", upper_str), ident.span());
-        let field_type = FieldType::try_from(field)?;
-        let attr = FieldAttributes::try_new(field, &field_type)?;
-        let random = FieldValue::random(field_type.size);
-        let start_index = *start_byte;
-        *start_byte = start_index + field_type.size();
+        let bits_ident = Ident::new(&format!("{}_BITS", upper_str), ident.span());
+        let bit_offset_ident = Ident::new(&format!("{}_BIT_OFFSET", upper_str), ident.span());
+        let mut field_type = FieldType::try_from(field)?;
+        let attr = FieldAttributes::try_new(field, &mut field_type)?;
+        let value_range_ident = if field_type.is_option() {
+            Ident::new(&format!("{}_VALUE_RANGE", upper_str), ident.span())
+        }
+        else {
+            range_ident.clone()
+        };
+        let random = FieldValue::random(&field_type);
+        // Bit fields are packed after the previous field, and all other fields start on a byte.
+        let padding_before = if field_type.is_bit_field() { 0 } else { (8 - *next_bit % 8) % 8 };
+        let bit_offset = *next_bit + padding_before;
+        *next_bit = bit_offset + field_type.key_bits();
+        let start_index = bit_offset / 8;
+        let end_index = (*next_bit + 7) / 8;
         Ok(Self {
             attr,
             ident,
@@ -422,223 +863,2222 @@ impl DBKeyField {
             default_ident,
             min_ident,
             max_ident,
+            bits_ident,
+            bit_offset_ident,
+            value_range_ident,
             field_type,
             random,
             start_index,
+            end_index,
+            bit_offset,
+            padding_before,
+            in_args: true,
+            version: false,
         })
     }
 
-    /// Define the constants for this field.
-    pub fn consts(&self) -> TokenStream {
-        let name = &self.attr.name;
-        let size_ident = &self.size_ident;
-        let start_ident = &self.start_ident;
-        let end_ident = &self.end_ident;
-        let range_ident = &self.range_ident;
-        let default_ident = &self.default_ident;
-        let min_ident = &self.min_ident;
-        let max_ident = &self.max_ident;
-        let size = &self.field_type.size();
-        let start = &self.start_index;
-        let default = &self.attr.default;
-        let field_type = &self.field_type;
-        let min = &self.attr.minimum;
-        let max = &self.attr.maximum;
-        quote! {
-            #[doc = concat!("The size of the ", #name, " field.")]
-            pub(crate) const #size_ident: usize = #size;
-            #[doc = concat!("The starting byte of the ", #name, " field in the key array.")]
-            pub(crate) const #start_ident: usize = #start;
-            #[doc = concat!("The byte after the last byte of the ", #name, " field in the key array.")]
-            pub(crate) const #end_ident: usize = Self::#start_ident + Self::#size_ident;
-            #[doc = concat!("The range of the bytes for the ", #name, " field in the key array.")]
-            pub(crate) const #range_ident: ::std::ops::Range<usize> = Self::#start_ident..Self::#end_ident;
-            #[doc = concat!("The default value of the ", #name, " field in the key array.")]
-            pub(crate) const #default_ident: #field_type = #default;
-            #[doc = concat!("The minimum value of the ", #name, " field in the key array.")]
-            pub(crate) const #min_ident: #field_type = #min;
-            #[doc = concat!("The maximum value of the ", #name, " field in the key array.")]
-            pub(crate) const #max_ident: #field_type = #max;
-        }
+    /// Set the name of the crate used by the documentation examples.
+    pub fn set_crate_name(&mut self, crate_name: &str) {
+        self.field_type.set_crate_name(crate_name);
+        self.random = FieldValue::random(&self.field_type);
     }
 
-    /// Define the sizes for this field.
-    pub fn sizes(&self) -> TokenStream {
-        let size_ident = &self.size_ident;
-        quote!{
-            Self::#size_ident
-        }
+    /// Return true if the field has a codec or a newtype, which may not be `Copy`.
+    pub fn has_custom_type(&self) -> bool {
+        self.field_type.is_borrowed()
     }
 
-    /// Define the new parameters for this field.
-    pub fn params(&self) -> TokenStream {
-        let ident = &self.ident;
-        let field_type = &self.field_type;
-        quote!{
-            #ident: #field_type,
+    /// Define the compile time checks for this field, which are outside of the key
+    /// implementation so that they are always evaluated.
+    pub fn checks(&self) -> TokenStream {
+        match &self.field_type.codec {
+            Some(codec) => {
+                let codec = codec.as_trait();
+                let size = self.field_type.size();
+                let message = format!(
+                    "The bytes attribute of the {} field doesn't match the SIZE of its codec.",
+                    self.attr.name);
+                quote! {
+                    const _: () = assert!(#size == #codec::SIZE, #message);
+                }
+            }
+            None => quote! {},
         }
     }
 
-    /// Define the new structure field for this field.
-    pub fn struct_fields(&self) -> TokenStream {
-        let ident = &self.ident;
-        let docs = &self.attr.docs;
-        let field_type = &self.field_type;
-        quote!{
-            #(#docs)*
-            pub #ident: #field_type,
-        }
+    /// Return true if the field has the `computed` attribute.
+    pub fn is_computed(&self) -> bool {
+        self.attr.computed.is_some()
     }
 
-    /// Define the field type as an array.
-    fn as_array(&self) -> TokenStream {
-        let ident = &self.ident;
-        match self.field_type.size {
-            FieldSize::Array(_) => quote! { #ident },
-            FieldSize::Unsigned8 | FieldSize::Unsigned16 | FieldSize::Unsigned32 |
-                FieldSize::Unsigned64 | FieldSize::Unsigned128
-                => quote! { #ident.to_be_bytes() },
-            FieldSize::Signed8 | FieldSize::Signed16 | FieldSize::Signed32 |
-                FieldSize::Signed64 | FieldSize::Signed128
-                => {
-                    let xor_mask = self.field_type.minimum_lit();
-                    quote! { (#ident ^ #xor_mask).to_be_bytes() }
-                }
-        }
+    /// Return true if the field has the `padding` attribute.
+    pub fn is_padding(&self) -> bool {
+        self.attr.padding
     }
 
-    /// Define the code to initialize new() for this field.
-    pub fn defines(&self) -> TokenStream {
-        let range_ident = &self.range_ident;
-        let as_array = &self.as_array();
-        quote!{
-            buf[Self::#range_ident].copy_from_slice(&#as_array);
+    /// Return true if the value of the field is set by `new()`, the arguments, and the setter,
+    /// which excludes computed, padding, and version fields.
+    fn is_input(&self) -> bool {
+        !self.is_computed() && !self.is_padding() && !self.version
+    }
+
+    /// Return true if the getters and setters of the field are public.
+    fn is_public(&self) -> bool {
+        matches!(self.attr.vis, Visibility::Public(_))
+    }
+
+    /// Return true if the field is in the arguments but doesn't have a public setter, so that
+    /// `update()` keeps its value.
+    fn is_kept(&self) -> bool {
+        self.in_args && self.is_input() && (self.attr.readonly || !self.is_public())
+    }
+
+    /// Return the visibility of the getters and setters of the field. The functions that aren't
+    /// public may be unused by the crate that defines the key.
+    fn fn_vis(&self) -> TokenStream {
+        let vis = &self.attr.vis;
+        if self.is_public() {
+            quote! { #vis }
+        }
+        else {
+            quote! { #[allow(dead_code)] #vis }
+        }
+    }
+
+    /// Return the examples section of the documentation of a getter or setter, which is omitted
+    /// when it isn't public because the doctests can't call it.
+    fn examples(&self, example_start: &str, example: &str) -> TokenStream {
+        if !self.is_public() {
+            return quote! {};
+        }
+        quote! {
+            ///
+            /// # Examples
+            ///
+            #[doc = #example_start]
+            #[doc = #example]
+            /// ```
+        }
+    }
+
+    /// Define the code that stores the value of a computed field, which is computed from the
+    /// other fields of the key.
+    pub fn computes(&self) -> TokenStream {
+        let path = match &self.attr.computed {
+            Some(path) => path,
+            None => { return quote! {}; }
+        };
+        let value_type = self.field_type.value_decl();
+        let store = if self.field_type.is_option() {
+            let start_ident = &self.start_ident;
+            let value_range_ident = &self.value_range_ident;
+            let some = self.field_type.some_marker();
+            let none = self.field_type.none_marker();
+            let store = self.value_store(quote! { self.0 }, quote! { value });
+            quote! {
+                match value {
+                    Some(value) => {
+                        self.0[Self::#start_ident] = #some;
+                        #store
+                    }
+                    None => {
+                        self.0[Self::#start_ident] = #none;
+                        self.0[Self::#value_range_ident].fill(0);
+                    }
+                }
+            }
+        }
+        else {
+            self.value_store(quote! { self.0 }, quote! { value })
+        };
+        quote! {
+            {
+                let value: #value_type = #path(self);
+                #store
+            }
+        }
+    }
+
+    /// Return the doctest code that initializes a key with the random value in this field, the
+    /// code of the random value, and the code of the random value as it's returned by the
+    /// getter. The value of a computed field can't be set, so the key is the default key and the
+    /// value is the one returned by the getter.
+    fn doc_init(&self, db_key: &DBKeyStruct, let_str: &str, random: &FieldValue)
+        -> (String, String, String)
+    {
+        if self.is_computed() {
+            let value = format!("key.{}()", &self.ident);
+            return (format!("let {} = {}::default();", let_str, &db_key.ident), value.clone(),
+                value);
+        }
+        (db_key.doc_init_key(let_str, &self.ident, random), random.to_string(),
+            random.assert_eq().to_string())
+    }
+
+    /// Define the code that checks the stored value of a computed field against the value
+    /// computed by `update_computed()` into the key `expected`.
+    pub fn validates(&self) -> TokenStream {
+        if !self.is_computed() {
+            return quote! {};
+        }
+        let ident = &self.ident;
+        let range_ident = &self.range_ident;
+        quote! {
+            if expected.0[Self::#range_ident] != self.0[Self::#range_ident] {
+                return Err(::db_key::ValidationError::Computed { field: stringify!(#ident) });
+            }
+        }
+    }
+
+    /// Define the code that checks that a padding field is zero for `validate()`.
+    pub fn padding_checks(&self) -> TokenStream {
+        if !self.is_padding() {
+            return quote! {};
+        }
+        let ident = &self.ident;
+        let range_ident = &self.range_ident;
+        quote! {
+            if self.0[Self::#range_ident].iter().any(|byte| 0 != *byte) {
+                return Err(::db_key::ValidationError::Padding { field: stringify!(#ident) });
+            }
+        }
+    }
+
+    /// Return the name of the field for the `VERSION_FIELDS` of a versioned key.
+    pub fn version_names(&self) -> TokenStream {
+        if !self.is_input() {
+            return quote! {};
+        }
+        let ident = &self.ident;
+        quote! {
+            stringify!(#ident),
+        }
+    }
+
+    /// Define the getter of the trait implemented by the previous version of a key, which is only
+    /// called when the previous version doesn't have the field.
+    pub fn missing_fields(&self) -> TokenStream {
+        if !self.is_input() {
+            return quote! {};
+        }
+        let ident = &self.ident;
+        let size_ident = &self.size_ident;
+        quote! {
+            const #size_ident: usize = 0;
+            fn #ident(&self) -> ::db_key::MissingField {
+                ::db_key::MissingField
+            }
+        }
+    }
+
+    /// Define the code that converts the field of the previous version of a key to the field of
+    /// the arguments, or the default value when the previous version doesn't have the field.
+    pub fn migrates(&self) -> TokenStream {
+        if !self.is_input() {
+            return quote! {};
+        }
+        let ident = &self.ident;
+        // The errors of the conversions that aren't lossless are reported at the field.
+        quote_spanned! { ident.span() =>
+            #ident: (&&&&::db_key::Migration::new(previous.#ident(), defaults.#ident)).migrate(),
+        }
+    }
+
+    /// Define the initializer of this field in the arguments converted from the variable `key`.
+    /// The padding fields keep the value of the variable `defaults`, like a non-zero field that
+    /// is zero in an invalid raw key.
+    pub fn to_args(&self) -> TokenStream {
+        if !self.in_args || self.is_padding() || self.version {
+            return quote! {};
+        }
+        let ident = &self.ident;
+        let field_type = &self.field_type;
+        match field_type.size {
+            // The getters of byte arrays return a slice.
+            FieldSize::Array(size) if !field_type.is_option() && field_type.newtype.is_none() => {
+                quote! {
+                    #ident: {
+                        let mut value = [0_u8; #size];
+                        value.copy_from_slice(key.#ident());
+                        value
+                    },
+                }
+            }
+            _ if field_type.is_nonzero_required() => quote! {
+                #ident: key.#ident().unwrap_or(defaults.#ident),
+            },
+            _ => quote! {
+                #ident: key.#ident(),
+            },
+        }
+    }
+
+    /// Define the code that restores the field of the variable `args` from the variable `kept`
+    /// when the field doesn't have a public setter.
+    pub fn keeps(&self) -> TokenStream {
+        if !self.is_kept() {
+            return quote! {};
+        }
+        let ident = &self.ident;
+        quote! {
+            args.#ident = kept.#ident;
+        }
+    }
+
+    /// Define the field of the pattern structure, which is `None` to match any value.
+    pub fn pattern_fields(&self) -> TokenStream {
+        if !self.in_args || !self.is_input() {
+            return quote! {};
+        }
+        let ident = &self.ident;
+        let docs = &self.attr.docs;
+        let field_type = self.field_type.declared();
+        quote! {
+            #(#docs)*
+            pub #ident: Option<#field_type>,
+        }
+    }
+
+    /// Return the expression of the value of this field in the variable `value`, which is a
+    /// reference to the value of the pattern.
+    fn pattern_value(&self) -> TokenStream {
+        if self.field_type.is_borrowed() {
+            quote! { value.clone() }
+        }
+        else {
+            quote! { *value }
+        }
+    }
+
+    /// Define the code that sets the value of this field in the variable `args` to the value of
+    /// the pattern.
+    pub fn pattern_args(&self) -> TokenStream {
+        if !self.in_args || !self.is_input() {
+            return quote! {};
+        }
+        let ident = &self.ident;
+        let value = self.pattern_value();
+        quote! {
+            if let Some(value) = &self.#ident {
+                args.#ident = #value;
+            }
+        }
+    }
+
+    /// Define the code that checks that the key stores the value of the pattern for this field,
+    /// which is the value of the field in the key `expected` created from the pattern.
+    pub fn pattern_matches(&self) -> TokenStream {
+        if !self.in_args || !self.is_input() {
+            return quote! {};
+        }
+        let ident = &self.ident;
+        quote! {
+            if self.#ident.is_some() && key.#ident() != expected.#ident() {
+                return false;
+            }
+        }
+    }
+
+    /// Define the code that returns false if the value of the pattern for this field doesn't fit
+    /// in the field, so the setter of the field would panic.
+    pub fn pattern_fits(&self) -> TokenStream {
+        if !self.in_args || !self.is_input() {
+            return quote! {};
+        }
+        let fits = match self.value_fits() {
+            Some(fits) => fits,
+            None => return quote! {},
+        };
+        let ident = &self.ident;
+        let value = self.pattern_value();
+        quote! {
+            if let Some(value) = &self.#ident {
+                let value = #value;
+                if !#fits {
+                    return false;
+                }
+            }
+        }
+    }
+
+    /// Define the initializer of this field in the pattern created from the variable `args`.
+    pub fn pattern_froms(&self) -> TokenStream {
+        if !self.in_args || !self.is_input() {
+            return quote! {};
+        }
+        let ident = &self.ident;
+        quote! {
+            #ident: Some(args.#ident),
+        }
+    }
+
+    /// Define the code that sets this field in the arguments of the `start` and `end` of the
+    /// range of a pattern. A `None` field ranges from its minimum to its maximum value, and the
+    /// bytes from a computed field stored before a `None` field are `unbounded`.
+    pub fn pattern_bounds(&self, db_key: &DBKeyStruct) -> TokenStream {
+        if self.is_computed() {
+            let start = self.bit_offset / 8;
+            return quote! {
+                if computed.is_none() {
+                    computed = Some(#start);
+                }
+            };
+        }
+        if !self.in_args || !self.is_input() {
+            return quote! {};
+        }
+        let ident = &self.ident;
+        let key = &db_key.ident;
+        let min_ident = &self.min_ident;
+        let max_ident = &self.max_ident;
+        let min = self.field_type.const_to_value(&quote! { #key::#min_ident });
+        let max = self.field_type.const_to_value(&quote! { #key::#max_ident });
+        let value = self.pattern_value();
+        let unbounded = if db_key.fields.has_computed() {
+            quote! {
+                if unbounded.is_none() {
+                    unbounded = computed;
+                }
+            }
+        }
+        else {
+            quote! {}
+        };
+        quote! {
+            match &self.#ident {
+                Some(value) => {
+                    start.#ident = #value;
+                    end.#ident = #value;
+                }
+                None => {
+                    start.#ident = #min;
+                    end.#ident = #max;
+                    #unbounded
+                }
+            }
+        }
+    }
+
+    /// Define the code that keeps the value of the pattern for this field in the `residual`
+    /// pattern, unless the `start` and the `end` of the range have the same bytes up to the end
+    /// of the field, so every key of the range stores the value.
+    pub fn pattern_residuals(&self) -> TokenStream {
+        if !self.in_args || !self.is_input() {
+            return quote! {};
+        }
+        let ident = &self.ident;
+        let end = (self.bit_offset + self.bits() + 7) / 8;
+        let value = self.pattern_value();
+        quote! {
+            if let Some(value) = &self.#ident {
+                if start.0[..#end] != end.0[..#end] {
+                    residual.#ident = Some(#value);
+                }
+            }
+        }
+    }
+
+    /// Define the compile time check that an array field isn't narrower than the field of the
+    /// previous version of a key, which would lose bytes.
+    pub fn migrate_checks(&self, db_key: &DBKeyStruct) -> TokenStream {
+        let previous = match &db_key.attr.previous {
+            Some(previous) if self.is_input() &&
+                matches!(self.field_type.size, FieldSize::Array(_)) => previous,
+            _ => { return quote! {}; }
+        };
+        let ident = &db_key.ident;
+        let size_ident = &self.size_ident;
+        let message = format!("The {} field is narrower than the field of the previous version.",
+            &self.attr.name);
+        quote_spanned! { self.ident.span() =>
+            const _: () = assert!(#previous::#size_ident <= #ident::#size_ident, #message);
+        }
+    }
+
+    /// Return the line of the field in the schema description of the key, which has the name of
+    /// the field, its encoding, its position and size in bits, and the options that change the
+    /// bytes stored (e.g. `amount: i64 @0+64 decimal=4`).
+    pub fn schema(&self) -> String {
+        let mut schema = format!("{}: {} @{}+{}", self.ident, self.field_type.schema(),
+            self.bit_offset, self.bits());
+        if let Some(scale) = self.attr.scale {
+            schema.push_str(&format!(" decimal={}", scale));
+        }
+        if let Some(computed) = &self.attr.computed {
+            schema.push_str(&format!(" computed={}", quote! { #computed }.to_string().replace(' ', "")));
+        }
+        if self.is_padding() {
+            schema.push_str(" padding");
+        }
+        if self.version {
+            schema.push_str(&format!(" version={}", self.attr.default));
+        }
+        schema
+    }
+
+    /// Return the field value of the argument structure for a reference key of the layout
+    /// snapshot test.
+    pub fn reference(&self, rng: &mut ReferenceRng) -> TokenStream {
+        if !self.is_input() {
+            return quote! {};
+        }
+        let ident = &self.ident;
+        let value = FieldValue::reference(&self.field_type, rng).to_string()
+            .parse::<TokenStream>().unwrap();
+        quote! {
+            #ident: #value,
+        }
+    }
+
+    /// Return the `db_key::FieldSchema` of the field for the `SCHEMA` of the key.
+    pub fn field_schemas(&self) -> TokenStream {
+        let name = self.ident.to_string();
+        let label = &self.attr.name;
+        let rust_type = self.field_type.schema_type();
+        let bit_offset = self.bit_offset;
+        let bits = self.bits();
+        let type_options = self.field_type.field_schema();
+        let decimal_scale = match self.attr.scale {
+            Some(scale) => quote! { Some(#scale) },
+            None => quote! { None },
+        };
+        let computed = self.is_computed();
+        let padding = self.is_padding();
+        let version = self.version;
+        let hidden = matches!(self.attr.vis, Visibility::Inherited);
+        quote! {
+            ::db_key::FieldSchema {
+                name: #name,
+                label: #label,
+                rust_type: #rust_type,
+                bit_offset: #bit_offset,
+                bits: #bits,
+                #type_options
+                descending: false,
+                decimal_scale: #decimal_scale,
+                computed: #computed,
+                padding: #padding,
+                version: #version,
+                hidden: #hidden,
+            },
+        }
+    }
+
+    /// Return the number of bits the field uses in the key.
+    fn bits(&self) -> usize {
+        self.field_type.key_bits()
+    }
+
+    /// Define the constants for this field.
+    pub fn consts(&self) -> TokenStream {
+        let name = &self.attr.name;
+        let size_ident = &self.size_ident;
+        let start_ident = &self.start_ident;
+        let end_ident = &self.end_ident;
+        let range_ident = &self.range_ident;
+        let default_ident = &self.default_ident;
+        let min_ident = &self.min_ident;
+        let max_ident = &self.max_ident;
+        let size = self.end_index - self.start_index;
+        let start = &self.start_index;
+        let default = &self.attr.default;
+        let field_type = self.field_type.const_type();
+        let min = &self.attr.minimum;
+        let max = &self.attr.maximum;
+        let bit_consts = if self.field_type.is_bit_field() {
+            let bits_ident = &self.bits_ident;
+            let bit_offset_ident = &self.bit_offset_ident;
+            let bits = self.bits() as u32;
+            let bit_offset = self.bit_offset;
+            quote! {
+                #[doc = concat!("The number of bits of the ", #name, " field.")]
+                pub(crate) const #bits_ident: u32 = #bits;
+                #[doc = concat!("The offset of the most significant bit of the ", #name,
+                    " field from the start of the key array.")]
+                pub(crate) const #bit_offset_ident: usize = #bit_offset;
+            }
+        }
+        else if self.field_type.is_option() {
+            let value_range_ident = &self.value_range_ident;
+            quote! {
+                #[doc = concat!("The range of the bytes for the value of the ", #name,
+                    " field in the key array, which follows the presence byte.")]
+                pub(crate) const #value_range_ident: ::std::ops::Range<usize> =
+                    (Self::#start_ident + 1)..Self::#end_ident;
+            }
+        }
+        else {
+            quote! {}
+        };
+        let scale_const = match self.attr.scale {
+            Some(scale) => {
+                let scale_ident = Ident::new(&format!("{}_SCALE", self.ident.to_string().to_uppercase()),
+                    self.ident.span());
+                quote! {
+                    #[doc = concat!("The number of fractional digits of the ", #name, " field.")]
+                    pub(crate) const #scale_ident: u32 = #scale;
+                }
+            }
+            None => quote! {},
+        };
+        quote! {
+            #[doc = concat!("The size of the ", #name, " field.")]
+            pub(crate) const #size_ident: usize = #size;
+            #[doc = concat!("The starting byte of the ", #name, " field in the key array.")]
+            pub(crate) const #start_ident: usize = #start;
+            #[doc = concat!("The byte after the last byte of the ", #name, " field in the key array.")]
+            pub(crate) const #end_ident: usize = Self::#start_ident + Self::#size_ident;
+            #[doc = concat!("The range of the bytes for the ", #name, " field in the key array.")]
+            pub(crate) const #range_ident: ::std::ops::Range<usize> = Self::#start_ident..Self::#end_ident;
+            #[doc = concat!("The default value of the ", #name, " field in the key array.")]
+            pub(crate) const #default_ident: #field_type = #default;
+            #[doc = concat!("The minimum value of the ", #name, " field in the key array.")]
+            pub(crate) const #min_ident: #field_type = #min;
+            #[doc = concat!("The maximum value of the ", #name, " field in the key array.")]
+            pub(crate) const #max_ident: #field_type = #max;
+            #bit_consts
+            #scale_const
+        }
+    }
+
+    /// Define the size in bits for this field.
+    pub fn sizes(&self) -> TokenStream {
+        if self.field_type.is_bit_field() {
+            let bits_ident = &self.bits_ident;
+            quote!{
+                Self::#bits_ident as usize
+            }
+        }
+        else if self.field_type.is_option() {
+            // The presence byte is shown separately from the value.
+            let size_ident = &self.size_ident;
+            // The expressions are in parentheses because clippy warns about a possibly missing
+            // comma when the attribute of the key is on several lines.
+            quote!{
+                8, (Self::#size_ident * 8 - 8)
+            }
+        }
+        else {
+            let size_ident = &self.size_ident;
+            quote!{
+                (Self::#size_ident * 8)
+            }
+        }
+    }
+
+    /// Define the new parameters for this field.
+    pub fn params(&self) -> TokenStream {
+        if !self.is_input() {
+            return quote! {};
+        }
+        let ident = &self.ident;
+        let field_type = self.field_type.declared();
+        quote!{
+            #ident: #field_type,
+        }
+    }
+
+    /// Define the new structure field for this field.
+    pub fn struct_fields(&self) -> TokenStream {
+        if !self.in_args {
+            return quote! {};
+        }
+        let ident = &self.ident;
+        let docs = &self.attr.docs;
+        let field_type = self.field_type.declared();
+        quote!{
+            #(#docs)*
+            pub #ident: #field_type,
+        }
+    }
+
+    /// Define the code to convert a value into the bytes of an integer type for a field stored in
+    /// fewer bytes than its type, applying the overflow policy of the field. Only the low bytes
+    /// (starting at `narrow_offset()`) are stored in the key.
+    fn narrow_array(&self, value: TokenStream) -> TokenStream {
+        let field_type = &self.field_type;
+        let signed = field_type.size.is_signed();
+        let check = self.overflow_check();
+        let bytes = if signed {
+            let xor_mask = field_type.xor_mask();
+            quote! { (value ^ #xor_mask).to_be_bytes() }
+        }
+        else {
+            quote! { value.to_be_bytes() }
+        };
+        quote! {
+            {
+                let value: #field_type = #value;
+                #check
+                #bytes
+            }
+        }
+    }
+
+    /// Define the code to apply the overflow policy to `value` for a field that is stored in fewer
+    /// bits than its type.
+    fn overflow_check(&self) -> TokenStream {
+        let field_type = &self.field_type;
+        let name = &self.attr.name;
+        let min = field_type.minimum_lit();
+        let max = field_type.maximum_lit();
+        let signed = field_type.size.is_signed();
+        match self.attr.overflow {
+            OverflowPolicy::Panic => {
+                let in_range = if signed {
+                    quote! { (#min..=#max).contains(&value) }
+                }
+                else {
+                    quote! { value <= #max }
+                };
+                quote! {
+                    assert!(#in_range, "{} doesn't fit in the {} field", value, #name);
+                }
+            }
+            OverflowPolicy::Saturate => {
+                if signed {
+                    quote! {
+                        let value = if value < #min { #min } else if #max < value { #max }
+                            else { value };
+                    }
+                }
+                else {
+                    quote! {
+                        let value = if #max < value { #max } else { value };
+                    }
+                }
+            }
+            OverflowPolicy::Truncate => quote! {},
+        }
+    }
+
+    /// Define the expression that is false if storing the variable `value` in this field panics,
+    /// because it doesn't fit and the overflow policy of the field doesn't saturate or truncate
+    /// it, or `None` if every value fits.
+    fn value_fits(&self) -> Option<TokenStream> {
+        let field_type = &self.field_type;
+        let mut checks = Vec::new();
+        if field_type.is_nonzero_signed_option() && OverflowPolicy::Panic == self.attr.overflow {
+            let none = field_type.minimum_lit();
+            checks.push(quote! { #none < value });
+        }
+        if let Some(fits) = self.nonzero_truncate_fits() {
+            checks.push(fits);
+        }
+        if field_type.size.is_time() {
+            if OverflowPolicy::Saturate != self.attr.overflow {
+                let count = field_type.time_unit.count(quote! { duration });
+                let max = u128_lit(field_type.max_count() as u128);
+                let duration = if FieldSize::SystemTime == field_type.size {
+                    quote! { value.duration_since(::std::time::UNIX_EPOCH) }
+                }
+                else {
+                    quote! { Ok::<_, ()>(value) }
+                };
+                checks.push(quote! {
+                    match #duration {
+                        Ok(duration) => #count <= #max,
+                        Err(_) => false,
+                    }
+                });
+            }
+        }
+        else if (field_type.is_narrow() || field_type.is_bit_field())
+            && OverflowPolicy::Panic == self.attr.overflow
+        {
+            let min = field_type.minimum_lit();
+            let max = field_type.maximum_lit();
+            if field_type.size.is_signed() {
+                checks.push(quote! { (#min..=#max).contains(&value) });
+            }
+            else {
+                checks.push(quote! { value <= #max });
+            }
+        }
+        if checks.is_empty() {
+            return None;
+        }
+        let fits = if self.is_checked_nonzero_option() {
+            quote! {
+                match value {
+                    Some(value) => {
+                        let value = value.get();
+                        #(#checks)&&*
+                    }
+                    None => true,
+                }
+            }
+        }
+        else {
+            let scalar = field_type.to_scalar(&quote! { value });
+            quote! {
+                {
+                    let value: #field_type = #scalar;
+                    #(#checks)&&*
+                }
+            }
+        };
+        if field_type.is_option() {
+            let value = if field_type.is_borrowed() { quote! { &value } } else { quote! { value } };
+            return Some(quote! {
+                match #value {
+                    Some(value) => #fits,
+                    None => true,
+                }
+            });
+        }
+        Some(fits)
+    }
+
+    /// Define the code to convert `value` to the bits of a bit field in the low bits of a `u128`.
+    fn bits_encode(&self) -> TokenStream {
+        let mask = u128_lit((1_u128 << self.bits()) - 1);
+        match self.field_type.size {
+            FieldSize::Unsigned128 => quote! { (value & #mask) },
+            FieldSize::Signed128 => {
+                let sign = u128_lit(1_u128 << (self.bits() - 1));
+                quote! { (((value as u128) & #mask) ^ #sign) }
+            }
+            size if size.is_signed() => {
+                let sign = u128_lit(1_u128 << (self.bits() - 1));
+                quote! { (((value as i128 as u128) & #mask) ^ #sign) }
+            }
+            _ => quote! { ((value as u128) & #mask) },
+        }
+    }
+
+    /// The number of bits after a bit field to the end of the last byte of the field.
+    fn bits_shift(&self) -> usize {
+        (8 - (self.bit_offset + self.bits()) % 8) % 8
+    }
+
+    /// Define the code to store `value` in a bit field of the `target` key array.
+    fn bits_store(&self, target: TokenStream, value: TokenStream) -> TokenStream {
+        let field_type = &self.field_type;
+        let range_ident = &self.range_ident;
+        let check = self.overflow_check();
+        let encode = self.bits_encode();
+        let mask = u128_lit((1_u128 << self.bits()) - 1);
+        let shift = self.bits_shift();
+        let (mask, bits) = if 0 == shift {
+            (mask, quote! { bits })
+        }
+        else {
+            (quote! { (#mask << #shift) }, quote! { (bits << #shift) })
+        };
+        quote! {
+            {
+                let value: #field_type = #value;
+                #check
+                let bits: u128 = #encode;
+                let mut window = 0_u128;
+                for byte in #target[Self::#range_ident].iter() {
+                    window = (window << 8) | *byte as u128;
+                }
+                window = (window & !#mask) | #bits;
+                for (i, byte) in #target[Self::#range_ident].iter_mut().rev().enumerate() {
+                    *byte = (window >> (i * 8)) as u8;
+                }
+            }
+        }
+    }
+
+    /// Define the code to initialize a constant for a bit field.
+    fn bits_const_define(&self, value: &TokenStream, const_name: &str) -> TokenStream {
+        let field_type = &self.field_type;
+        let min = field_type.minimum_lit();
+        let max = field_type.maximum_lit();
+        let message = format!("The {} of the {} field doesn't fit in the key.", const_name,
+            self.attr.name);
+        let check_min = if field_type.size.is_signed() {
+            quote! { assert!(#min <= value, #message); }
+        }
+        else {
+            quote! {}
+        };
+        let encode = self.bits_encode();
+        let shift = self.bits_shift();
+        let bits = if 0 == shift { quote! { #encode } } else { quote! { (#encode << #shift) } };
+        let start = self.start_index;
+        let end = self.end_index;
+        let span = end - start;
+        quote! {
+            let value: #field_type = #value;
+            #check_min
+            assert!(value <= #max, #message);
+            let bits: u128 = #bits;
+            let mut i = 0;
+            while i < #span {
+                buf[#start + i] |= (bits >> ((#span - 1 - i) * 8)) as u8;
+                i += 1;
+            }
+            buf_i = #end;
+        }
+    }
+
+    /// Define the code to initialize a constant for this field.
+    fn const_define(&self, value: &TokenStream, const_name: &str) -> TokenStream {
+        if self.field_type.is_option() {
+            let field_type = self.field_type.const_type();
+            let some = self.field_type.some_marker();
+            let none = self.field_type.none_marker();
+            let size = self.field_type.size();
+            let define = self.value_const_define(&quote! { value }, const_name);
+            return quote! {
+                let value: #field_type = #value;
+                match value {
+                    Some(value) => {
+                        buf[buf_i] = #some;
+                        buf_i += 1;
+                        #define
+                    }
+                    None => {
+                        buf[buf_i] = #none;
+                        buf_i += 1 + #size;
+                    }
+                }
+            };
         }
+        self.value_const_define(value, const_name)
     }
 
-    /// Define the code to initialize from(KeyArgs) for this field.
-    pub fn from_args(&self) -> TokenStream {
-        let range_ident = &self.range_ident;
+    /// Define the code to initialize a constant for the value of this field.
+    fn value_const_define(&self, value: &TokenStream, const_name: &str) -> TokenStream {
+        let field_type = &self.field_type;
+        let xor_mask = &self.field_type.xor_mask();
+        if field_type.size.is_time() {
+            return self.time_const_define(value, const_name);
+        }
+        if field_type.is_bit_field() {
+            return self.bits_const_define(value, const_name);
+        }
+        if field_type.is_narrow() {
+            return self.narrow_const_define(value, const_name);
+        }
+        match self.field_type.size {
+            FieldSize::Signed8 | FieldSize::Signed16 | FieldSize::Signed32 |
+                FieldSize::Signed64 | FieldSize::Signed128 =>
+            {
+                let size = field_type.size();
+                quote! {
+                    let value: #field_type = #value ^ #xor_mask;
+                    if 0 == value {
+                        buf_i += #size;
+                    }
+                    else {
+                        let bytes = value.to_be_bytes();
+                        let mut i = 0;
+                        while i < bytes.len() {
+                            buf[buf_i] = bytes[i];
+                            buf_i += 1;
+                            i += 1;
+                        }
+                    }
+                }
+            }
+            FieldSize::Unsigned8 | FieldSize::Unsigned16 | FieldSize::Unsigned32 |
+                FieldSize::Unsigned64 | FieldSize::Unsigned128 =>
+            {
+                let size = field_type.size();
+                quote! {
+                    let value: #field_type = #value;
+                    if 0 == value {
+                        buf_i += #size;
+                    }
+                    else {
+                        let bytes = value.to_be_bytes();
+                        let mut i = 0;
+                        while i < bytes.len() {
+                            buf[buf_i] = bytes[i];
+                            buf_i += 1;
+                            i += 1;
+                        }
+                    }
+                }
+            }
+            // Time fields are handled before matching the other types.
+            FieldSize::Duration | FieldSize::SystemTime => unreachable!(),
+            FieldSize::Ipv4 | FieldSize::Ipv6 | FieldSize::SocketV4 | FieldSize::SocketV6 |
+                FieldSize::Uuid =>
+            {
+                let copies = self.byte_parts().into_iter().map(|(bytes, _size)| quote! {
+                    let bytes = #bytes;
+                    let mut i = 0;
+                    while i < bytes.len() {
+                        buf[buf_i] = bytes[i];
+                        buf_i += 1;
+                        i += 1;
+                    }
+                });
+                quote! {
+                    let value: #field_type = #value;
+                    #(#copies)*
+                }
+            }
+            FieldSize::Array(size) => {
+                let collation = self.field_type.collation;
+                let binding = if Collation::Binary == collation {
+                    quote! { let value: #field_type = #value; }
+                }
+                else {
+                    let normalize = collation.normalize(size);
+                    quote! {
+                        let mut value: #field_type = #value;
+                        #normalize
+                    }
+                };
+                quote! {
+                    #binding
+                    let mut i = 0;
+                    while i < value.len() {
+                        buf[buf_i] = value[i];
+                        buf_i += 1;
+                        i += 1;
+                    }
+                }
+            }
+            FieldSize::Char => {
+                let offset = 4 - field_type.char_width();
+                quote! {
+                    let value: char = #value;
+                    let bytes = (value as u32).to_be_bytes();
+                    let mut i = #offset;
+                    while i < bytes.len() {
+                        buf[buf_i] = bytes[i];
+                        buf_i += 1;
+                        i += 1;
+                    }
+                }
+            }
+            FieldSize::CharArray(_) => {
+                let offset = 4 - field_type.char_width();
+                quote! {
+                    let value: #field_type = #value;
+                    let mut c = 0;
+                    while c < value.len() {
+                        let bytes = (value[c] as u32).to_be_bytes();
+                        let mut i = #offset;
+                        while i < bytes.len() {
+                            buf[buf_i] = bytes[i];
+                            buf_i += 1;
+                            i += 1;
+                        }
+                        c += 1;
+                    }
+                }
+            }
+            FieldSize::Codec(size) => {
+                let message = format!("The {} of the {} field isn't {} bytes.", const_name,
+                    self.attr.name, size);
+                quote! {
+                    let value: &'static [u8] = #value;
+                    assert!(#size == value.len(), #message);
+                    let mut i = 0;
+                    while i < value.len() {
+                        buf[buf_i] = value[i];
+                        buf_i += 1;
+                        i += 1;
+                    }
+                }
+            }
+        }
+    }
+
+    /// Define the code to initialize a constant for a time field from a `Duration`.
+    fn time_const_define(&self, value: &TokenStream, const_name: &str) -> TokenStream {
+        let field_type = &self.field_type;
+        let count = field_type.time_unit.count(quote! { value });
+        let max = u128_lit(field_type.max_count() as u128);
+        let offset = 16 - field_type.size();
+        let message = format!("The {} of the {} field doesn't fit in the key.", const_name,
+            self.attr.name);
+        quote! {
+            let value: ::std::time::Duration = #value;
+            let count: u128 = #count;
+            assert!(count <= #max, #message);
+            let bytes = count.to_be_bytes();
+            let mut i = #offset;
+            while i < bytes.len() {
+                buf[buf_i] = bytes[i];
+                buf_i += 1;
+                i += 1;
+            }
+        }
+    }
+
+    /// Define the code to convert a `Duration` or `SystemTime` value to the bytes of the `u128`
+    /// count of the time unit, applying the overflow policy of the field. Only the low bytes
+    /// (starting at `narrow_offset()`) are stored in the key.
+    fn time_array(&self, value: TokenStream) -> TokenStream {
+        let field_type = &self.field_type;
+        let name = &self.attr.name;
+        let saturate = OverflowPolicy::Saturate == self.attr.overflow;
+        let count = field_type.time_unit.count(quote! { duration });
+        let duration = if FieldSize::SystemTime == field_type.size {
+            let before_epoch = if saturate {
+                quote! { ::std::time::Duration::ZERO }
+            }
+            else {
+                quote! { panic!("{:?} is before the Unix epoch for the {} field", value, #name) }
+            };
+            quote! {
+                match value.duration_since(::std::time::UNIX_EPOCH) {
+                    Ok(duration) => duration,
+                    Err(_) => #before_epoch,
+                }
+            }
+        }
+        else {
+            quote! { value }
+        };
+        let max = u128_lit(field_type.max_count() as u128);
+        let check = if saturate {
+            quote! { let count = ::std::cmp::min(count, #max); }
+        }
+        else {
+            quote! {
+                assert!(count <= #max, "{:?} doesn't fit in the {} field", value, #name);
+            }
+        };
+        quote! {
+            {
+                let value: #field_type = #value;
+                let duration: ::std::time::Duration = #duration;
+                let count: u128 = #count;
+                #check
+                count.to_be_bytes()
+            }
+        }
+    }
+
+    /// The index of the first byte of a narrowed integer type that is stored in the key.
+    fn narrow_offset(&self) -> usize {
+        if self.field_type.size.is_time() {
+            return 16 - self.field_type.size();
+        }
+        self.field_type.type_size() - self.field_type.size()
+    }
+
+    /// Define the code to initialize a constant for a field stored in fewer bytes than its type.
+    fn narrow_const_define(&self, value: &TokenStream, const_name: &str) -> TokenStream {
+        let field_type = &self.field_type;
+        let min = field_type.minimum_lit();
+        let max = field_type.maximum_lit();
+        let offset = self.narrow_offset();
+        let message = format!("The {} of the {} field doesn't fit in the key.", const_name,
+            self.attr.name);
+        let (check_min, bytes) = if field_type.size.is_signed() {
+            let xor_mask = field_type.xor_mask();
+            (quote! { assert!(#min <= value, #message); },
+                quote! { (value ^ #xor_mask).to_be_bytes() })
+        }
+        else {
+            (quote! {}, quote! { value.to_be_bytes() })
+        };
+        quote! {
+            let value: #field_type = #value;
+            #check_min
+            assert!(value <= #max, #message);
+            let bytes = #bytes;
+            let mut i = #offset;
+            while i < bytes.len() {
+                buf[buf_i] = bytes[i];
+                buf_i += 1;
+                i += 1;
+            }
+        }
+    }
+
+    /// Return true if the `Some` values of an `Option` of a non-zero integer are checked before
+    /// they're stored: the minimum value of signed fields is reserved for `None`, and truncated
+    /// values may be zero.
+    fn is_checked_nonzero_option(&self) -> bool {
+        self.field_type.is_nonzero_signed_option() || (self.field_type.is_nonzero_option()
+            && OverflowPolicy::Truncate == self.attr.overflow)
+    }
+
+    /// Define the expression that is true if the non-zero `value` of a field with the `truncate`
+    /// overflow policy is still a non-zero value, which isn't the `None` of a signed `Option`,
+    /// after it is truncated to the bits of the field, or `None` if it's always true.
+    fn nonzero_truncate_fits(&self) -> Option<TokenStream> {
+        let field_type = &self.field_type;
+        if field_type.nonzero.is_none() || OverflowPolicy::Truncate != self.attr.overflow {
+            return None;
+        }
+        let none = field_type.minimum_lit();
+        if !field_type.is_reduced() {
+            return if field_type.is_nonzero_signed_option() {
+                Some(quote! { #none != value })
+            }
+            else {
+                None
+            };
+        }
+        // Shifting the stored bits to the top and back sign extends signed values.
+        let shift = field_type.type_size() * 8 - field_type.value_bits();
+        let truncated = quote! { ((value << #shift) >> #shift) };
+        if field_type.is_nonzero_signed_option() {
+            Some(quote! { (0 != #truncated && #none != #truncated) })
+        }
+        else {
+            Some(quote! { 0 != #truncated })
+        }
+    }
+
+    /// Define the code to convert `value` of an `Option` of a non-zero integer to the primitive
+    /// value. `None` is the minimum value of the field, so it's stored as zero bytes and sorts
+    /// first, and `Some` values that would be stored as zero bytes panic, or saturate to the
+    /// smallest `Some` value.
+    fn nonzero_option_scalar(&self, value: &TokenStream) -> TokenStream {
+        let name = &self.attr.name;
+        let none = self.field_type.minimum_lit();
+        let some = match self.attr.overflow {
+            OverflowPolicy::Saturate => quote! {
+                if value <= #none { #none + 1 } else { value }
+            },
+            OverflowPolicy::Panic => quote! {
+                {
+                    assert!(#none < value, "{} doesn't fit in the {} field", value, #name);
+                    value
+                }
+            },
+            OverflowPolicy::Truncate => {
+                let fits = self.nonzero_truncate_fits();
+                quote! {
+                    {
+                        assert!(#fits, "{} doesn't fit in the {} field", value, #name);
+                        value
+                    }
+                }
+            }
+        };
+        quote! {
+            match #value {
+                Some(value) => {
+                    let value = value.get();
+                    #some
+                }
+                None => #none,
+            }
+        }
+    }
+
+    /// Define the code to convert `value` of a required non-zero integer to the primitive value,
+    /// which panics if the value is truncated to zero.
+    fn nonzero_required_scalar(&self, value: &TokenStream) -> TokenStream {
+        let scalar = self.field_type.to_scalar(value);
+        let fits = match self.nonzero_truncate_fits() {
+            Some(fits) => fits,
+            None => return scalar,
+        };
+        let name = &self.attr.name;
+        let field_type = &self.field_type;
+        quote! {
+            {
+                let value: #field_type = #scalar;
+                assert!(#fits, "{} doesn't fit in the {} field", value, #name);
+                value
+            }
+        }
+    }
+
+    /// Define the code to store `value` in the bytes of the value of this field in the `target`
+    /// key array.
+    fn value_store(&self, target: TokenStream, value: TokenStream) -> TokenStream {
+        let value_range_ident = &self.value_range_ident;
+        let value = if self.is_checked_nonzero_option() {
+            self.nonzero_option_scalar(&value)
+        }
+        else if self.field_type.is_nonzero_required() {
+            self.nonzero_required_scalar(&value)
+        }
+        else {
+            self.field_type.to_scalar(&value)
+        };
+        if self.field_type.is_bit_field() {
+            return self.bits_store(target, value);
+        }
+        if self.field_type.size.is_time() {
+            let offset = self.narrow_offset();
+            let bytes = self.time_array(value);
+            return quote! {
+                #target[Self::#value_range_ident].copy_from_slice(&#bytes[#offset..]);
+            };
+        }
+        if self.field_type.is_narrow() {
+            let offset = self.narrow_offset();
+            let bytes = self.narrow_array(value);
+            return quote! {
+                #target[Self::#value_range_ident].copy_from_slice(&#bytes[#offset..]);
+            };
+        }
         match self.field_type.size {
             FieldSize::Signed8 | FieldSize::Signed16 | FieldSize::Signed32 |
                 FieldSize::Signed64 | FieldSize::Signed128
             => {
-                let ident = &self.ident;
-                let xor_mask = self.field_type.minimum_lit();
+                let xor_mask = self.field_type.xor_mask();
+                quote! {
+                    #target[Self::#value_range_ident]
+                        .copy_from_slice(&(#value ^ #xor_mask).to_be_bytes());
+                }
+            }
+            FieldSize::Unsigned8 | FieldSize::Unsigned16 | FieldSize::Unsigned32 |
+                FieldSize::Unsigned64 | FieldSize::Unsigned128
+            => {
+                quote! {
+                    #target[Self::#value_range_ident].copy_from_slice(&#value.to_be_bytes());
+                }
+            }
+            FieldSize::Array(size) => {
+                let collation = self.field_type.collation;
+                if Collation::Binary == collation {
+                    quote! {
+                        #target[Self::#value_range_ident].copy_from_slice(&#value);
+                    }
+                }
+                else {
+                    let normalize = collation.normalize(size);
+                    quote! {
+                        {
+                            let mut value: [u8; #size] = #value;
+                            #normalize
+                            #target[Self::#value_range_ident].copy_from_slice(&value);
+                        }
+                    }
+                }
+            }
+            FieldSize::Char => {
+                let field_type = &self.field_type;
+                let offset = 4 - field_type.char_width();
+                quote! {
+                    {
+                        let value: #field_type = #value;
+                        #target[Self::#value_range_ident]
+                            .copy_from_slice(&(value as u32).to_be_bytes()[#offset..]);
+                    }
+                }
+            }
+            FieldSize::CharArray(_) => {
+                let field_type = &self.field_type;
+                let width = field_type.char_width();
+                let offset = 4 - width;
+                quote! {
+                    {
+                        let value: #field_type = #value;
+                        let chunks = #target[Self::#value_range_ident].chunks_mut(#width);
+                        for (c, bytes) in value.iter().zip(chunks) {
+                            bytes.copy_from_slice(&(*c as u32).to_be_bytes()[#offset..]);
+                        }
+                    }
+                }
+            }
+            FieldSize::Codec(_) => {
+                // The value is encoded by reference, so it isn't moved out of the arguments.
+                let codec = self.field_type.codec.as_ref().unwrap().as_trait();
+                quote! {
+                    #codec::encode(&#value, &mut #target[Self::#value_range_ident]);
+                }
+            }
+            // Time fields are handled before matching the other types.
+            FieldSize::Duration | FieldSize::SystemTime => unreachable!(),
+            FieldSize::Ipv4 | FieldSize::Ipv6 | FieldSize::SocketV4 | FieldSize::SocketV6 |
+                FieldSize::Uuid =>
+            {
+                let field_type = &self.field_type;
+                let mut offset = 0;
+                let copies = self.byte_parts().into_iter().map(|(bytes, size)| {
+                    let start = offset;
+                    offset += size;
+                    quote! { bytes[#start..#offset].copy_from_slice(&#bytes); }
+                }).collect::<Vec<_>>();
+                quote! {
+                    {
+                        let value: #field_type = #value;
+                        let bytes = &mut #target[Self::#value_range_ident];
+                        #(#copies)*
+                    }
+                }
+            }
+        }
+    }
+
+    /// Return the expressions for the big-endian bytes of the parts of a `std::net` address or
+    /// `Uuid` `value` in key order with their sizes. The expressions can be used in a constant.
+    fn byte_parts(&self) -> Vec<(TokenStream, usize)> {
+        match self.field_type.size {
+            FieldSize::Uuid => vec![(quote! { *value.as_bytes() }, 16)],
+            FieldSize::Ipv4 => vec![(quote! { value.octets() }, 4)],
+            FieldSize::Ipv6 => vec![(quote! { value.octets() }, 16)],
+            FieldSize::SocketV4 => vec![
+                (quote! { value.ip().octets() }, 4),
+                (quote! { value.port().to_be_bytes() }, 2),
+            ],
+            FieldSize::SocketV6 => vec![
+                (quote! { value.ip().octets() }, 16),
+                (quote! { value.port().to_be_bytes() }, 2),
+                (quote! { value.flowinfo().to_be_bytes() }, 4),
+                (quote! { value.scope_id().to_be_bytes() }, 4),
+            ],
+            _ => Vec::new(),
+        }
+    }
+
+    /// Define the code to store the `Option` `value` in the `target` key array. The value bytes
+    /// of `None` are left unchanged, so they must already be zero.
+    fn option_store(&self, target: TokenStream, value: TokenStream) -> TokenStream {
+        let start_ident = &self.start_ident;
+        let some = self.field_type.some_marker();
+        let none = self.field_type.none_marker();
+        let store = self.value_store(target.clone(), quote! { value });
+        // Codec and newtype values are only borrowed, because they may not be `Copy`.
+        let value = if self.field_type.is_borrowed() { quote! { &#value } } else { value };
+        quote! {
+            match #value {
+                Some(value) => {
+                    #target[Self::#start_ident] = #some;
+                    #store
+                }
+                None => {
+                    #target[Self::#start_ident] = #none;
+                }
+            }
+        }
+    }
+
+    /// Define the code that stores the version in the version byte.
+    fn version_store(&self) -> TokenStream {
+        let start_ident = &self.start_ident;
+        quote! {
+            buf[Self::#start_ident] = Self::VERSION;
+        }
+    }
+
+    /// Define the code to initialize new() for this field.
+    pub fn defines(&self) -> TokenStream {
+        if self.version {
+            return self.version_store();
+        }
+        if !self.is_input() {
+            return quote! {};
+        }
+        let ident = &self.ident;
+        if self.field_type.is_option() {
+            return self.option_store(quote! { buf }, quote! { #ident });
+        }
+        self.value_store(quote! { buf }, quote! { #ident })
+    }
+
+    /// Define the code to initialize from(KeyArgs) for this field.
+    #[allow(clippy::wrong_self_convention)]
+    pub fn from_args(&self) -> TokenStream {
+        if self.version {
+            return self.version_store();
+        }
+        if !self.is_input() {
+            return quote! {};
+        }
+        let ident = &self.ident;
+        if self.field_type.is_option() {
+            return self.option_store(quote! { buf }, quote! { args.#ident });
+        }
+        self.value_store(quote! { buf }, quote! { args.#ident })
+    }
+
+    /// Define the code to initialize from(KeyArgs) for this field.
+    pub fn debug(&self) -> TokenStream {
+        let ident = &self.ident;
+        // Hidden fields are only displayed in the raw key.
+        if matches!(self.attr.vis, Visibility::Inherited) {
+            return quote!{};
+        }
+        if self.version {
+            let start_ident = &self.start_ident;
+            return quote!{
+                .field(stringify!(#ident), &self.0[Self::#start_ident])
+            };
+        }
+        if self.is_padding() {
+            let range_ident = &self.range_ident;
+            return quote!{
+                .field(stringify!(#ident),
+                    &format_args!("padding {:?}", &self.0[Self::#range_ident]))
+            };
+        }
+        if self.attr.scale.is_some() {
+            let decimal_ident = Ident::new(&format!("{}_decimal", ident), ident.span());
+            return quote!{
+                .field(stringify!(#ident), &self.#decimal_ident())
+            };
+        }
+        quote!{
+            .field(stringify!(#ident), &self.#ident())
+        }
+    }
+
+    impl_const_define!{defaults, default, "Default"}
+    impl_const_define!{maximums, maximum, "maximum value"}
+    impl_const_define!{minimums, minimum, "minimum value"}
+    /// Define the code to initialize from(KeyArgs) for this field.
+    pub fn arg_defaults(&self) -> TokenStream {
+        if !self.in_args {
+            return quote! {};
+        }
+        let ident = &self.ident;
+        let default = self.field_type.const_to_value(&self.attr.default);
+        quote! {
+            #ident: #default,
+        }
+    }
+
+    /// Define the code that replaces the value of this field in the proptest strategy of the
+    /// arguments with a value between the minimum and maximum values of the field.
+    pub fn arbitraries(&self, db_key: &DBKeyStruct) -> TokenStream {
+        if !self.in_args || !self.is_input() {
+            return quote! {};
+        }
+        let ident = &self.ident;
+        let key = &db_key.ident;
+        let min_ident = &self.min_ident;
+        let max_ident = &self.max_ident;
+        let values = self.arbitrary_values();
+        let (strategy, value) = match self.field_type.option {
+            Some(order) => {
+                let minimum = self.field_type.minimum_lit();
+                let maximum = self.field_type.maximum_lit();
+                // `None` is in the range when it's the minimum or maximum that sorts first or
+                // last, and the `Some` values aren't when `None` is both.
+                let (none, some) = match order {
+                    OptionOrder::NoneFirst => (quote! { #key::#min_ident.is_none() },
+                        quote! { #key::#max_ident.is_some() }),
+                    OptionOrder::NoneLast => (quote! { #key::#max_ident.is_none() },
+                        quote! { #key::#min_ident.is_some() }),
+                };
+                let value = if self.field_type.codec.is_some() {
+                    quote! { value.as_deref() }
+                }
+                else {
+                    quote! { value }
+                };
+                (quote! {
+                    {
+                        let min = match #key::#min_ident { Some(min) => min, None => #minimum };
+                        let max = match #key::#max_ident { Some(max) => max, None => #maximum };
+                        let values = #values;
+                        match (#none, #some) {
+                            (true, true) => ::proptest::option::of(values).boxed(),
+                            (false, true) => values.prop_map(Some).boxed(),
+                            (_, false) => ::proptest::strategy::Just(None).boxed(),
+                        }
+                    }
+                }, value)
+            }
+            None => {
+                let value = if self.field_type.codec.is_some() {
+                    quote! { &value[..] }
+                }
+                else {
+                    quote! { value }
+                };
+                (quote! {
+                    {
+                        let (min, max) = (#key::#min_ident, #key::#max_ident);
+                        #values
+                    }
+                }, value)
+            }
+        };
+        let value = self.field_type.const_to_value(&value);
+        quote! {
+            let strategy = (strategy, #strategy)
+                .prop_map(|(mut args, value)| {
+                    args.#ident = #value;
+                    args
+                })
+                .boxed();
+        }
+    }
+
+    /// Return the proptest strategy of the values of the field between the variables `min` and
+    /// `max`, which have the type of the constants of the field (without the `Option`). The values
+    /// of collated fields are normalized and the values of time fields are whole counts of the
+    /// time unit, so that different values are different keys, and the values of codec fields are
+    /// the encoded bytes.
+    fn arbitrary_values(&self) -> TokenStream {
+        let field_type = &self.field_type;
+        match field_type.size {
+            FieldSize::Array(size) => {
+                let normalize = field_type.collation.normalize(size);
                 quote! {
-                    buf[Self::#range_ident]
-                        .copy_from_slice(&(args.#ident ^ #xor_mask).to_be_bytes());
+                    ::proptest::collection::vec(::proptest::arbitrary::any::<u8>(), #size)
+                        .prop_map(move |bytes| {
+                            let mut value = [0_u8; #size];
+                            value.copy_from_slice(&bytes);
+                            #normalize
+                            ::std::cmp::Ord::clamp(value, min, max)
+                        })
                 }
             }
-            _ => {
-                let as_array = &self.as_array();
-                quote!{
-                    buf[Self::#range_ident].copy_from_slice(&args.#as_array);
+            FieldSize::CharArray(count) => quote! {
+                ::proptest::collection::vec(::proptest::arbitrary::any::<char>(), #count)
+                    .prop_map(move |chars| {
+                        let mut value = ['\0'; #count];
+                        value.copy_from_slice(&chars);
+                        ::std::cmp::Ord::clamp(value, min, max)
+                    })
+            },
+            FieldSize::Char => quote! { ::proptest::char::range(min, max) },
+            FieldSize::Ipv4 => quote! {
+                (u32::from(min)..=u32::from(max)).prop_map(::std::net::Ipv4Addr::from)
+            },
+            FieldSize::Ipv6 => quote! {
+                (u128::from(min)..=u128::from(max)).prop_map(::std::net::Ipv6Addr::from)
+            },
+            FieldSize::Uuid => quote! {
+                (min.as_u128()..=max.as_u128()).prop_map(::uuid::Uuid::from_u128)
+            },
+            FieldSize::SocketV4 => quote! {
+                ::proptest::arbitrary::any::<::std::net::SocketAddrV4>()
+                    .prop_map(move |value| ::std::cmp::Ord::clamp(value, min, max))
+            },
+            FieldSize::SocketV6 => quote! {
+                ::proptest::arbitrary::any::<::std::net::SocketAddrV6>()
+                    .prop_map(move |value| ::std::cmp::Ord::clamp(value, min, max))
+            },
+            FieldSize::Duration | FieldSize::SystemTime => {
+                let min_count = field_type.time_unit.count(quote! { min });
+                let max_count = field_type.time_unit.count(quote! { max });
+                let from = Ident::new(&format!("from_{}", field_type.time_unit.name()),
+                    Span::call_site());
+                quote! {
+                    ((#min_count as u64)..=(#max_count as u64))
+                        .prop_map(::std::time::Duration::#from)
                 }
             }
+            FieldSize::Codec(size) => quote! {
+                ::proptest::collection::vec(::proptest::arbitrary::any::<u8>(), #size)
+                    .prop_map(move |bytes| ::std::cmp::Ord::clamp(&bytes[..], min, max).to_vec())
+            },
+            _ if field_type.is_nonzero_required() => quote! {
+                (min..=max).prop_filter("non-zero", |value| 0 != *value)
+            },
+            _ => quote! { min..=max },
         }
     }
 
-    /// Define the code to initialize from(KeyArgs) for this field.
-    pub fn debug(&self) -> TokenStream {
-        let ident = &self.ident;
-        quote!{
-            .field(stringify!(#ident), &self.#ident())
+    /// Define the statement that replaces the value of this field in the variable `args` by an
+    /// `arbitrary::Arbitrary` value from the variable `u`, which is between the minimum and
+    /// maximum values of the field like the values of the proptest strategy.
+    pub fn arbitrary_fields(&self, db_key: &DBKeyStruct) -> TokenStream {
+        if !self.in_args || !self.is_input() {
+            return quote! {};
         }
-    }
-
-    impl_const_define!{defaults, default, "Default"}
-    impl_const_define!{maximums, maximum, "maximum value"}
-    impl_const_define!{minimums, minimum, "minimum value"}
-    /// Define the code to initialize from(KeyArgs) for this field.
-    pub fn arg_defaults(&self) -> TokenStream {
         let ident = &self.ident;
-        let default = &self.attr.default;
+        let key = &db_key.ident;
+        let min_ident = &self.min_ident;
+        let max_ident = &self.max_ident;
+        let value = self.arbitrary_value();
+        let value = match self.field_type.option {
+            Some(order) => {
+                let minimum = self.field_type.minimum_lit();
+                let maximum = self.field_type.maximum_lit();
+                let (none, some) = match order {
+                    OptionOrder::NoneFirst => (quote! { #key::#min_ident.is_none() },
+                        quote! { #key::#max_ident.is_some() }),
+                    OptionOrder::NoneLast => (quote! { #key::#max_ident.is_none() },
+                        quote! { #key::#min_ident.is_some() }),
+                };
+                let some_value = if self.field_type.codec.is_some() {
+                    quote! { value.as_deref() }
+                }
+                else {
+                    quote! { value }
+                };
+                let some_value = self.field_type.const_to_value(&some_value);
+                quote! {
+                    let min = match #key::#min_ident { Some(min) => min, None => #minimum };
+                    let max = match #key::#max_ident { Some(max) => max, None => #maximum };
+                    let value = if #some && !(#none && u.arbitrary::<bool>()?) {
+                        Some(#value)
+                    }
+                    else {
+                        None
+                    };
+                    #some_value
+                }
+            }
+            None => {
+                let some_value = if self.field_type.codec.is_some() {
+                    quote! { &value[..] }
+                }
+                else {
+                    quote! { value }
+                };
+                let some_value = self.field_type.const_to_value(&some_value);
+                quote! {
+                    let (min, max) = (#key::#min_ident, #key::#max_ident);
+                    let value = #value;
+                    #some_value
+                }
+            }
+        };
         quote! {
-            #ident: #default,
+            args.#ident = {
+                #value
+            };
         }
     }
 
-    /// Define the code to extract the value for this field.
-    fn get_code(&self) -> TokenStream {
-        let ident = &self.ident;
-        match self.field_type.size {
-            FieldSize::Signed8 => {
-                let start_ident = &self.start_ident;
+    /// Return the expression of an `arbitrary::Arbitrary` value of the field from the variable
+    /// `u` between the variables `min` and `max`, with the same values as `arbitrary_values()`.
+    fn arbitrary_value(&self) -> TokenStream {
+        let field_type = &self.field_type;
+        match field_type.size {
+            FieldSize::Array(size) => {
+                let normalize = field_type.collation.normalize(size);
                 quote! {
-                    pub const fn #ident(&self) -> i8 {
-                        (self.0[Self::#start_ident] as i8) ^ i8::MIN
+                    {
+                        let mut value = [0_u8; #size];
+                        u.fill_buffer(&mut value)?;
+                        #normalize
+                        ::std::cmp::Ord::clamp(value, min, max)
                     }
                 }
             }
-            FieldSize::Unsigned8 => {
-                let start_ident = &self.start_ident;
+            FieldSize::CharArray(count) => quote! {
+                {
+                    let mut value = ['\0'; #count];
+                    for c in value.iter_mut() {
+                        *c = u.arbitrary()?;
+                    }
+                    ::std::cmp::Ord::clamp(value, min, max)
+                }
+            },
+            FieldSize::Char => quote! {
+                char::from_u32(u.int_in_range(u32::from(min)..=u32::from(max))?).unwrap_or(min)
+            },
+            FieldSize::Ipv4 => quote! {
+                ::std::net::Ipv4Addr::from(u.int_in_range(u32::from(min)..=u32::from(max))?)
+            },
+            FieldSize::Ipv6 => quote! {
+                ::std::net::Ipv6Addr::from(u.int_in_range(u128::from(min)..=u128::from(max))?)
+            },
+            FieldSize::Uuid => quote! {
+                ::uuid::Uuid::from_u128(u.int_in_range(min.as_u128()..=max.as_u128())?)
+            },
+            FieldSize::SocketV4 => quote! {
+                ::std::cmp::Ord::clamp(u.arbitrary::<::std::net::SocketAddrV4>()?, min, max)
+            },
+            FieldSize::SocketV6 => quote! {
+                ::std::cmp::Ord::clamp(u.arbitrary::<::std::net::SocketAddrV6>()?, min, max)
+            },
+            FieldSize::Duration | FieldSize::SystemTime => {
+                let min_count = field_type.time_unit.count(quote! { min });
+                let max_count = field_type.time_unit.count(quote! { max });
+                let from = Ident::new(&format!("from_{}", field_type.time_unit.name()),
+                    Span::call_site());
                 quote! {
-                    pub const fn #ident(&self) -> u8 {
-                        self.0[Self::#start_ident]
+                    ::std::time::Duration::#from(
+                        u.int_in_range((#min_count as u64)..=(#max_count as u64))?)
+                }
+            }
+            FieldSize::Codec(size) => quote! {
+                {
+                    let mut value = [0_u8; #size];
+                    u.fill_buffer(&mut value)?;
+                    ::std::cmp::Ord::clamp(&value[..], min, max).to_vec()
+                }
+            },
+            // Zero takes the maximum value, or the minimum value when the maximum is zero.
+            _ if field_type.is_nonzero_required() => quote! {
+                match u.int_in_range(min..=max)? {
+                    0 if 0 != max => max,
+                    0 => min,
+                    value => value,
+                }
+            },
+            _ => quote! { u.int_in_range(min..=max)? },
+        }
+    }
+
+    /// Define the expression to extract the value of this field from the key. It returns whether
+    /// the expression can be used in a const function and the expression.
+    fn value_get(&self) -> (bool, TokenStream) {
+        let value_range_ident = &self.value_range_ident;
+        let field_type = &self.field_type;
+        let start_ident = &self.start_ident;
+        let value_start = if field_type.is_option() {
+            quote! { Self::#start_ident + 1 }
+        }
+        else {
+            quote! { Self::#start_ident }
+        };
+        if field_type.is_bit_field() {
+            let mask = u128_lit((1_u128 << self.bits()) - 1);
+            let shift = self.bits_shift();
+            let bits = if 0 == shift {
+                quote! { window & #mask }
+            }
+            else {
+                quote! { (window >> #shift) & #mask }
+            };
+            let value = match field_type.size {
+                FieldSize::Unsigned128 => quote! { bits },
+                size if size.is_signed() => {
+                    // Toggle the sign bit back and then sign extend the value.
+                    let sign = u128_lit(1_u128 << (self.bits() - 1));
+                    let extend = (128 - self.bits()) as u32;
+                    let value = quote! { ((((bits ^ #sign) << #extend) as i128) >> #extend) };
+                    if FieldSize::Signed128 == size { value } else { quote! { #value as #field_type } }
+                }
+                _ => quote! { bits as #field_type },
+            };
+            return (false, quote! {
+                {
+                    let mut window = 0_u128;
+                    for byte in self.0[Self::#value_range_ident].iter() {
+                        window = (window << 8) | *byte as u128;
                     }
+                    let bits = #bits;
+                    #value
                 }
+            });
+        }
+        if field_type.size.is_time() {
+            let offset = 8 - field_type.size();
+            let duration = field_type.time_unit.duration(quote! { u64::from_be_bytes(buf) });
+            let value = if FieldSize::SystemTime == field_type.size {
+                quote! { ::std::time::UNIX_EPOCH + #duration }
+            }
+            else {
+                duration
+            };
+            return (false, quote! {
+                {
+                    let mut buf = [0_u8; 8];
+                    buf[#offset..].copy_from_slice(&self.0[Self::#value_range_ident]);
+                    #value
+                }
+            });
+        }
+        if field_type.is_narrow() {
+            let type_size = field_type.type_size();
+            let offset = self.narrow_offset();
+            let value = if field_type.size.is_signed() {
+                // Toggle the sign bit back and then sign extend the value.
+                let xor_mask = field_type.xor_mask();
+                let shift = (offset * 8) as u32;
+                quote! { ((#field_type::from_be_bytes(buf) ^ #xor_mask) << #shift) >> #shift }
             }
+            else {
+                quote! { #field_type::from_be_bytes(buf) }
+            };
+            return (false, quote! {
+                {
+                    let mut buf = [0_u8; #type_size];
+                    buf[#offset..].copy_from_slice(&self.0[Self::#value_range_ident]);
+                    #value
+                }
+            });
+        }
+        match field_type.size {
+            FieldSize::Signed8 => (true, quote! { (self.0[#value_start] as i8) ^ i8::MIN }),
+            FieldSize::Unsigned8 => (true, quote! { self.0[#value_start] }),
             FieldSize::Signed16 | FieldSize::Signed32 | FieldSize::Signed64 |
                 FieldSize::Signed128 =>
             {
-                let size_ident = &self.size_ident;
-                let range_ident = &self.range_ident;
-                let field_type = &self.field_type;
-                let xor_mask = &self.field_type.minimum_lit();
-                quote! {
-                    pub fn #ident(&self) -> #field_type {
-                        let mut buf = [0_u8; Self::#size_ident];
-                        buf.copy_from_slice(&self.0[Self::#range_ident]);
+                let size = field_type.size();
+                let xor_mask = field_type.xor_mask();
+                (false, quote! {
+                    {
+                        let mut buf = [0_u8; #size];
+                        buf.copy_from_slice(&self.0[Self::#value_range_ident]);
                         #field_type::from_be_bytes(buf) ^ #xor_mask
                     }
-                }
+                })
             }
             FieldSize::Unsigned16 | FieldSize::Unsigned32 | FieldSize::Unsigned64 |
                 FieldSize::Unsigned128 =>
             {
-                let size_ident = &self.size_ident;
-                let range_ident = &self.range_ident;
-                let field_type = &self.field_type;
-                quote! {
-                    pub fn #ident(&self) -> #field_type {
-                        let mut buf = [0_u8; Self::#size_ident];
-                        buf.copy_from_slice(&self.0[Self::#range_ident]);
+                let size = field_type.size();
+                (false, quote! {
+                    {
+                        let mut buf = [0_u8; #size];
+                        buf.copy_from_slice(&self.0[Self::#value_range_ident]);
                         #field_type::from_be_bytes(buf)
                     }
+                })
+            }
+            FieldSize::Duration | FieldSize::SystemTime => unreachable!(),
+            FieldSize::Ipv4 | FieldSize::Ipv6 => {
+                let size = field_type.size();
+                let ip_type = if FieldSize::Ipv4 == field_type.size {
+                    quote! { ::std::net::Ipv4Addr }
                 }
+                else {
+                    quote! { ::std::net::Ipv6Addr }
+                };
+                (false, quote! {
+                    {
+                        let mut octets = [0_u8; #size];
+                        octets.copy_from_slice(&self.0[Self::#value_range_ident]);
+                        #ip_type::from(octets)
+                    }
+                })
             }
-            FieldSize::Array(_size) => {
-                let range_ident = &self.range_ident;
-                quote! {
-                    pub fn #ident(&self) -> &[u8] {
-                        &self.0[Self::#range_ident]
+            FieldSize::Uuid => (false, quote! {
+                {
+                    let mut bytes = [0_u8; 16];
+                    bytes.copy_from_slice(&self.0[Self::#value_range_ident]);
+                    ::uuid::Uuid::from_bytes(bytes)
+                }
+            }),
+            FieldSize::SocketV4 => (false, quote! {
+                {
+                    let bytes = &self.0[Self::#value_range_ident];
+                    let mut octets = [0_u8; 4];
+                    octets.copy_from_slice(&bytes[..4]);
+                    ::std::net::SocketAddrV4::new(::std::net::Ipv4Addr::from(octets),
+                        u16::from_be_bytes([bytes[4], bytes[5]]))
+                }
+            }),
+            FieldSize::SocketV6 => (false, quote! {
+                {
+                    let bytes = &self.0[Self::#value_range_ident];
+                    let mut octets = [0_u8; 16];
+                    octets.copy_from_slice(&bytes[..16]);
+                    ::std::net::SocketAddrV6::new(::std::net::Ipv6Addr::from(octets),
+                        u16::from_be_bytes([bytes[16], bytes[17]]),
+                        u32::from_be_bytes([bytes[18], bytes[19], bytes[20], bytes[21]]),
+                        u32::from_be_bytes([bytes[22], bytes[23], bytes[24], bytes[25]]))
+                }
+            }),
+            FieldSize::Codec(_) => {
+                let codec = field_type.codec.as_ref().unwrap().as_trait();
+                (false, quote! { #codec::decode(&self.0[Self::#value_range_ident]) })
+            }
+            FieldSize::Char | FieldSize::CharArray(_) => (false, self.char_decode(false)),
+            FieldSize::Array(size) => {
+                if field_type.is_option() || field_type.newtype.is_some() {
+                    (false, quote! {
+                        {
+                            let mut value = [0_u8; #size];
+                            value.copy_from_slice(&self.0[Self::#value_range_ident]);
+                            value
+                        }
+                    })
+                }
+                else {
+                    (false, quote! { &self.0[Self::#value_range_ident] })
+                }
+            }
+        }
+    }
+
+    /// Define the expression to decode the characters of a `char` field. Invalid scalar values
+    /// return an error with `?` if the decoding is fallible, and are replaced by
+    /// `char::REPLACEMENT_CHARACTER` otherwise.
+    fn char_decode(&self, fallible: bool) -> TokenStream {
+        let value_range_ident = &self.value_range_ident;
+        let width = self.field_type.char_width();
+        let offset = 4 - width;
+        let decode = if fallible {
+            quote! { <char as ::std::convert::TryFrom<u32>>::try_from(u32::from_be_bytes(buf))? }
+        }
+        else {
+            quote! {
+                ::std::char::from_u32(u32::from_be_bytes(buf))
+                    .unwrap_or(::std::char::REPLACEMENT_CHARACTER)
+            }
+        };
+        match self.field_type.size {
+            FieldSize::CharArray(count) => quote! {
+                {
+                    let mut chars = ['\0'; #count];
+                    let chunks = self.0[Self::#value_range_ident].chunks(#width);
+                    for (c, bytes) in chars.iter_mut().zip(chunks) {
+                        let mut buf = [0_u8; 4];
+                        buf[#offset..].copy_from_slice(bytes);
+                        *c = #decode;
+                    }
+                    chars
+                }
+            },
+            _ => quote! {
+                {
+                    let mut buf = [0_u8; 4];
+                    buf[#offset..].copy_from_slice(&self.0[Self::#value_range_ident]);
+                    #decode
+                }
+            },
+        }
+    }
+
+    /// Define the function that extracts the value of a `char` field, and returns an error if
+    /// the key contains an invalid scalar value (e.g. a key read from a database).
+    fn try_get(&self, db_key: &DBKeyStruct) -> TokenStream {
+        let vis = self.fn_vis();
+        let field_type = &self.field_type;
+        if !field_type.size.is_char() {
+            return quote! {};
+        }
+        let ident = &self.ident;
+        let try_ident = Ident::new(&format!("try_{}", ident), ident.span());
+        let struct_ident = &db_key.ident;
+        let example_start = db_key.example_start();
+        let value = field_type.scalar_to_value(&self.char_decode(true));
+        let value_type = field_type.value_decl();
+        let try_doc = format!(concat!("Get the {} value from the `{}`, or an error if the key ",
+            "contains an invalid Unicode scalar value."), &self.attr.name, struct_ident);
+        let random = FieldValue::random(field_type);
+        let (init_key, _, expected) = self.doc_init(db_key, "key", &random);
+        let mut try_example = format!(r#"
+{0}
+
+assert_eq!(key.{1}(), Ok({2}));"#,
+            init_key,  // 0
+            &try_ident, // 1
+            expected, // 2
+        );
+        if !field_type.is_option() {
+            let _ = write!(try_example, r#"
+
+let invalid = {0}::from([0xFF_u8; {0}::KEY_LENGTH]);
+assert!(invalid.{1}().is_err());"#,
+                struct_ident, &try_ident);
+            if field_type.newtype.is_none() {
+                let replacement = match field_type.size {
+                    FieldSize::CharArray(count) => format!("[char::REPLACEMENT_CHARACTER; {}]",
+                        count),
+                    _ => "char::REPLACEMENT_CHARACTER".to_string(),
+                };
+                let _ = write!(try_example, "\nassert_eq!(invalid.{}(), {});", ident,
+                    replacement);
+            }
+        }
+        let (return_type, body) = if field_type.is_option() {
+            let start_ident = &self.start_ident;
+            let some = field_type.some_marker();
+            (quote! { Option<#value_type> }, quote! {
+                if #some == self.0[Self::#start_ident] {
+                    Ok(Some(#value))
+                }
+                else {
+                    Ok(None)
+                }
+            })
+        }
+        else {
+            (quote! { #value_type }, quote! { Ok(#value) })
+        };
+        let try_example_docs = self.examples(&example_start, &try_example);
+        quote! {
+            #[doc = #try_doc]
+            #try_example_docs
+            #vis fn #try_ident(&self)
+                -> ::std::result::Result<#return_type, ::std::char::CharTryFromError>
+            {
+                #body
+            }
+        }
+    }
+
+    /// Define the functions that get the value of a field with the `decimal` attribute as a
+    /// decimal number.
+    fn decimal_gets(&self, db_key: &DBKeyStruct) -> TokenStream {
+        let scale = match self.attr.scale {
+            Some(scale) => scale,
+            None => { return quote! {}; }
+        };
+        let ident = &self.ident;
+        let vis = self.fn_vis();
+        let decimal_ident = Ident::new(&format!("{}_decimal", ident), ident.span());
+        let f64_ident = Ident::new(&format!("{}_f64", ident), ident.span());
+        let string_ident = Ident::new(&format!("{}_string", ident), ident.span());
+        let struct_ident = &db_key.ident;
+        let example_start = db_key.example_start();
+        let name = &self.attr.name;
+        let decimal_doc = format!("Get the {} value from the `{}` as a decimal number with {} \
+            fractional digits.", name, struct_ident, scale);
+        let f64_doc = format!("Get the {} value from the `{}` as the closest `f64`.", name,
+            struct_ident);
+        let string_doc = format!("Get the {} value from the `{}` as a decimal string with {} \
+            fractional digits.", name, struct_ident, scale);
+        let random = FieldValue::random(&self.field_type);
+        let (init_key, random, _) = self.doc_init(db_key, "key", &random);
+        let decimal_example = format!(r#"
+{0}
+let decimal = key.{1}();
+
+assert_eq!(decimal.value(), {2} as i128);
+assert_eq!(decimal.scale(), {3});"#,
+            init_key, &decimal_ident, random, scale);
+        let f64_example = format!(r#"
+{0}
+
+assert_eq!(key.{1}(), {2} as f64 / 10_f64.powi({3}));"#,
+            init_key, &f64_ident, random, scale);
+        let string_example = format!(r#"
+{0}
+
+assert_eq!(key.{1}(), db_key::Decimal::new({2} as i128, {3}).to_string());"#,
+            init_key, &string_ident, random, scale);
+        let decimal_example_docs = self.examples(&example_start, &decimal_example);
+        let f64_example_docs = self.examples(&example_start, &f64_example);
+        let string_example_docs = self.examples(&example_start, &string_example);
+        quote! {
+            #[doc = #decimal_doc]
+            #decimal_example_docs
+            #vis fn #decimal_ident(&self) -> ::db_key::Decimal {
+                ::db_key::Decimal::new(self.#ident() as i128, #scale)
+            }
+
+            #[doc = #f64_doc]
+            #f64_example_docs
+            #vis fn #f64_ident(&self) -> f64 {
+                self.#decimal_ident().to_f64()
+            }
+
+            #[doc = #string_doc]
+            #string_example_docs
+            #vis fn #string_ident(&self) -> String {
+                self.#decimal_ident().to_string()
+            }
+        }
+    }
+
+    /// Define the functions that set the value of a field with the `decimal` attribute from a
+    /// decimal number.
+    fn decimal_sets(&self, db_key: &DBKeyStruct) -> TokenStream {
+        let scale = match self.attr.scale {
+            Some(scale) if !self.is_computed() => scale,
+            _ => { return quote! {}; }
+        };
+        let ident = &self.ident;
+        let vis = self.fn_vis();
+        let set_ident = &self.set_ident;
+        let f64_ident = Ident::new(&format!("set_{}_f64", ident), ident.span());
+        let str_ident = Ident::new(&format!("set_{}_str", ident), ident.span());
+        let field_type = &self.field_type;
+        let struct_ident = &db_key.ident;
+        let example_start = db_key.example_start();
+        let name = &self.attr.name;
+        let min_ident = &self.min_ident;
+        let max_ident = &self.max_ident;
+        let f64_doc = format!("Set the {} in the `{}` to the `f64` value rounded to {} fractional \
+            digits. Values that are out of the range of the field saturate to its minimum and \
+            maximum values, and NaN is zero.", name, struct_ident, scale);
+        let str_doc = format!("Set the {} in the `{}` from a decimal string with up to {} \
+            fractional digits (e.g. `-12.34`).", name, struct_ident, scale);
+        let random1 = FieldValue::random(field_type);
+        let random2 = FieldValue::random(field_type);
+        let f64_example = format!(r#"
+{0}
+
+key.{1}(0.0);
+assert_eq!(key.{2}(), 0);"#,
+            db_key.doc_init_key("mut key", ident, &random1), &f64_ident, ident);
+        let str_example = format!(r#"
+{0}
+
+key.{1}(&db_key::Decimal::new({3} as i128, {4}).to_string()).unwrap();
+assert_eq!(key.{2}(), {3});
+assert!(key.{1}("1.2.3").is_err());
+assert_eq!(key.{2}(), {3});"#,
+            db_key.doc_init_key("mut key", ident, &random1), &str_ident, ident, random2, scale);
+        let f64_example_docs = self.examples(&example_start, &f64_example);
+        let str_example_docs = self.examples(&example_start, &str_example);
+        quote! {
+            #[doc = #f64_doc]
+            ///
+            /// The rounded value is stored like the value of the setter of the field.
+            #f64_example_docs
+            #vis fn #f64_ident(&mut self, value: f64) {
+                let value = ::db_key::Decimal::from_f64(value, #scale).value()
+                    .clamp(Self::#min_ident as i128, Self::#max_ident as i128);
+                self.#set_ident(value as #field_type);
+            }
+
+            #[doc = #str_doc]
+            ///
+            /// It returns an error without changing the key if the string isn't a decimal number
+            /// or the value is out of the range between the minimum and maximum values of the field.
+            #str_example_docs
+            #vis fn #str_ident(&mut self, value: &str)
+                -> ::std::result::Result<(), ::db_key::ParseDecimalError>
+            {
+                let value = ::db_key::Decimal::parse(value, #scale)?.value();
+                if !(Self::#min_ident as i128..=Self::#max_ident as i128).contains(&value) {
+                    return Err(::db_key::ParseDecimalError::OutOfRange);
+                }
+                self.#set_ident(value as #field_type);
+                Ok(())
+            }
+        }
+    }
+
+    /// Define the code to extract the value for this field.
+    fn get_code(&self) -> TokenStream {
+        let vis = self.fn_vis();
+        let ident = &self.ident;
+        let field_type = &self.field_type;
+        let (is_const, value) = self.value_get();
+        // The conversion of newtypes isn't a const function.
+        let is_const = is_const && field_type.newtype.is_none();
+        let value = field_type.scalar_to_value(&value);
+        let value_type = field_type.get_decl();
+        let constness = if is_const { quote! { const } } else { quote! {} };
+        if field_type.is_option() {
+            let start_ident = &self.start_ident;
+            let some = field_type.some_marker();
+            return quote! {
+                #vis #constness fn #ident(&self) -> Option<#value_type> {
+                    if #some == self.0[Self::#start_ident] {
+                        Some(#value)
+                    }
+                    else {
+                        None
                     }
                 }
+            };
+        }
+        let return_type = match field_type.size {
+            FieldSize::Array(_) if field_type.newtype.is_none() => quote! { &[u8] },
+            _ => value_type,
+        };
+        quote! {
+            #vis #constness fn #ident(&self) -> #return_type {
+                #value
             }
         }
     }
 
     /// Define the code to extract the value for this field.
     pub fn gets(&self, db_key: &DBKeyStruct) -> TokenStream {
+        if self.is_padding() || self.version {
+            return quote! {};
+        }
         let example_start = db_key.example_start();
         let struct_ident = &db_key.ident;
         let get_doc = format!("Get the {} value from the `{}`.", &self.attr.name, struct_ident);
-        let random = FieldValue::random(self.field_type.size);
+        let random = FieldValue::random(&self.field_type);
         let min_lines = if db_key.attr.min_key {
             [ format!("\nlet min_key = {0}::MIN_KEY;", struct_ident),
-            format!("\nassert_eq!(min_key.{0}(), {1});", &self.ident, &self.attr.minimum) ]
+            format!("\nassert_eq!(min_key.{0}(), {1});", &self.ident, self.doc_get(&self.attr.minimum)) ]
         }
         else {
             [String::new(), String::new()]
         };
         let max_lines = if db_key.attr.max_key {
             [ format!("\nlet max_key = {0}::MAX_KEY;", struct_ident),
-            format!("\nassert_eq!(max_key.{0}(), {1});", &self.ident, &self.attr.maximum) ]
+            format!("\nassert_eq!(max_key.{0}(), {1});", &self.ident, self.doc_get(&self.attr.maximum)) ]
         }
         else {
             [String::new(), String::new()]
         };
+        let (init_key, _, value) = self.doc_init(db_key, "key", &random);
+        // The default value of a computed field is replaced by the computed value.
+        let default = if self.is_computed() { format!("default_key.{}()", &self.ident) }
+            else { self.doc_get(&self.attr.default) };
         let get_example = format!(r#"
 let default_key = {0}::default();{5}{6}
 {1}
@@ -646,79 +3086,207 @@ let default_key = {0}::default();{5}{6}
 assert_eq!(default_key.{2}(), {3});{7}{8}
 assert_eq!(key.{2}(), {4});"#,
             struct_ident, // 0
-            db_key.doc_init_key("key", &self.ident, &random),   // 1
+            init_key,   // 1
             &self.ident, // 2
-            &self.attr.default, // 3
-            random.assert_eq(), // 4
+            default, // 3
+            value, // 4
             min_lines[0], // 5
             max_lines[0], // 6
             min_lines[1], // 7
             max_lines[1], // 8
         );
         let docs = &self.attr.docs;
+        let collation_doc = self.collation_doc("The value is returned in the normalized form");
+        let computed_doc = match &self.attr.computed {
+            Some(path) => {
+                let doc = format!("The value is computed by `{}` from the other fields of the \
+                    key, and checked by `validate()`.", quote! { #path }.to_string().replace(' ', ""));
+                quote! {
+                    ///
+                    #[doc = #doc]
+                }
+            }
+            None => quote! {},
+        };
         let get_code = self.get_code();
+        let prefix_range = self.prefix_range(db_key);
+        let try_get = self.try_get(db_key);
+        let decimal_gets = self.decimal_gets(db_key);
+        let get_example_docs = self.examples(&example_start, &get_example);
         quote! {
             #[doc = #get_doc]
             ///
             #(#docs)*
+            #collation_doc
+            #computed_doc
+            #get_example_docs
+            #get_code
+            #try_get
+            #decimal_gets
+            #prefix_range
+        }
+    }
+
+    /// Return the documentation of the collation of a byte array field.
+    fn collation_doc(&self, summary: &str) -> TokenStream {
+        let collation = self.field_type.collation;
+        if Collation::Binary == collation {
+            return quote! {};
+        }
+        let doc = format!("{} of the `{}` collation, so different values can return the same \
+            bytes.", summary, collation.name());
+        quote! {
             ///
-            /// # Examples
+            #[doc = #doc]
+        }
+    }
+
+    /// Define the function that returns the range of keys with an IP address in a prefix (CIDR
+    /// block) for `std::net` address fields.
+    fn prefix_range(&self, db_key: &DBKeyStruct) -> TokenStream {
+        let ip_size = match self.field_type.size.ip_size() {
+            Some(ip_size) if !self.field_type.is_option() => ip_size,
+            _ => { return quote! {}; }
+        };
+        let ident = &self.ident;
+        let vis = self.fn_vis();
+        let prefix_ident = Ident::new(&format!("{}_prefix_range", ident), ident.span());
+        let start_ident = &self.start_ident;
+        let example_start = db_key.example_start();
+        let max_bits = (ip_size * 8) as u8;
+        let prefix_doc = format!(concat!("Return the range of keys that have the same fields ",
+            "before the {} as this key, and a {} with an IP address in the first `prefix_len` ",
+            "bits of the IP address of this key (a CIDR block)."), &self.attr.name,
+            &self.attr.name);
+        let random = FieldValue::random(&self.field_type);
+        let ip = match self.field_type.size {
+            FieldSize::SocketV4 | FieldSize::SocketV6 => ".ip()",
+            _ => "",
+        };
+        let prefix_example = format!(r#"
+{0}
+let range = key.{1}(8);
+
+assert!(range.contains(&key));
+assert_eq!(range.start().{2}(){3}.octets()[0], key.{2}(){3}.octets()[0]);
+assert_eq!(range.start().{2}(){3}.octets()[1], 0x00);
+assert_eq!(range.end().{2}(){3}.octets()[1], 0xFF);"#,
+            self.doc_init(db_key, "key", &random).0,  // 0
+            &prefix_ident, // 1
+            ident, // 2
+            ip, // 3
+        );
+        let panic_doc = format!("Panics if `prefix_len` is more than {}.", max_bits);
+        let prefix_example_docs = self.examples(&example_start, &prefix_example);
+        quote! {
+            #[doc = #prefix_doc]
             ///
-            #[doc = #example_start]
-            #[doc = #get_example]
-            /// ```
-            #get_code
+            /// # Panics
+            ///
+            #[doc = #panic_doc]
+            #prefix_example_docs
+            #vis fn #prefix_ident(&self, prefix_len: u8) -> ::std::ops::RangeInclusive<Self> {
+                assert!(prefix_len <= #max_bits, "The prefix length {} is more than {} bits",
+                    prefix_len, #max_bits);
+                let mut start = self.0;
+                let mut end = self.0;
+                for i in 0..#ip_size {
+                    let bits = prefix_len.saturating_sub((i * 8) as u8).min(8);
+                    let mask = (0xFF00_u16 >> bits) as u8;
+                    start[Self::#start_ident + i] &= mask;
+                    end[Self::#start_ident + i] |= !mask;
+                }
+                start[Self::#start_ident + #ip_size..].fill(u8::MIN);
+                end[Self::#start_ident + #ip_size..].fill(u8::MAX);
+                Self(start)..=Self(end)
+            }
         }
     }
 
-    /// Define the code to extract the value for this field.
-    fn set_code(&self) -> TokenStream {
+    /// Define the code to insert the value for this field into the key array, followed by the
+    /// `update` code.
+    fn set_code(&self, update: &TokenStream) -> TokenStream {
+        let vis = self.fn_vis();
         let set_ident = &self.set_ident;
-        match self.field_type.size {
-            FieldSize::Signed8 => {
+        let field_type = &self.field_type;
+        if field_type.is_option() {
+            let start_ident = &self.start_ident;
+            let value_range_ident = &self.value_range_ident;
+            let some = field_type.some_marker();
+            let none = field_type.none_marker();
+            let store = self.value_store(quote! { self.0 }, quote! { value });
+            let value_type = field_type.value_decl();
+            return quote! {
+                #vis fn #set_ident(&mut self, value: Option<#value_type>) {
+                    match value {
+                        Some(value) => {
+                            self.0[Self::#start_ident] = #some;
+                            #store
+                        }
+                        None => {
+                            self.0[Self::#start_ident] = #none;
+                            self.0[Self::#value_range_ident].fill(0);
+                        }
+                    }
+                    #update
+                }
+            };
+        }
+        if field_type.newtype.is_some() || field_type.nonzero.is_some() {
+            let store = self.value_store(quote! { self.0 }, quote! { value });
+            let value_type = field_type.value_decl();
+            return quote! {
+                #vis fn #set_ident(&mut self, value: #value_type) {
+                    #store
+                    #update
+                }
+            };
+        }
+        match field_type.size {
+            FieldSize::Signed8 if !field_type.is_reduced() => {
                 let start_ident = &self.start_ident;
                 quote! {
-                    pub fn #set_ident(&mut self, value: i8) {
+                    #vis fn #set_ident(&mut self, value: i8) {
                         self.0[Self::#start_ident] = (value ^ i8::MIN) as u8;
+                        #update
                     }
                 }
             }
-            FieldSize::Unsigned8 => {
+            FieldSize::Unsigned8 if !field_type.is_reduced() => {
                 let start_ident = &self.start_ident;
                 quote! {
-                    pub fn #set_ident(&mut self, value: u8) {
+                    #vis fn #set_ident(&mut self, value: u8) {
                         self.0[Self::#start_ident] = value;
+                        #update
                     }
                 }
             }
-            FieldSize::Signed16 | FieldSize::Signed32 | FieldSize::Signed64 |
-                FieldSize::Signed128 =>
-            {
-                let range_ident = &self.range_ident;
-                let field_type = &self.field_type;
-                let xor_mask = self.field_type.minimum_lit();
+            FieldSize::Array(size) if Collation::Binary != field_type.collation => {
+                let store = self.value_store(quote! { self.0 }, quote! { bytes });
                 quote! {
-                    pub fn #set_ident(&mut self, value: #field_type) {
-                        self.0[Self::#range_ident].copy_from_slice(&(value ^ #xor_mask).to_be_bytes());
+                    #vis fn #set_ident<V: std::convert::AsRef<[u8]>>(&mut self, value: V) {
+                        let mut bytes = [0_u8; #size];
+                        bytes.copy_from_slice(value.as_ref());
+                        #store
+                        #update
                     }
                 }
             }
-            FieldSize::Unsigned16 | FieldSize::Unsigned32 | FieldSize::Unsigned64 |
-                FieldSize::Unsigned128 =>
-            {
+            FieldSize::Array(_) => {
                 let range_ident = &self.range_ident;
-                let field_type = &self.field_type;
                 quote! {
-                    pub fn #set_ident(&mut self, value: #field_type) {
-                        self.0[Self::#range_ident].copy_from_slice(&value.to_be_bytes());
+                    #vis fn #set_ident<V: std::convert::AsRef<[u8]>>(&mut self, value: V) {
+                        self.0[Self::#range_ident].copy_from_slice(value.as_ref());
+                        #update
                     }
                 }
             }
-            FieldSize::Array(_) => {
-                let range_ident = &self.range_ident;
+            _ => {
+                let store = self.value_store(quote! { self.0 }, quote! { value });
                 quote! {
-                    pub fn #set_ident<V: std::convert::AsRef<[u8]>>(&mut self, value: V) {
-                        self.0[Self::#range_ident].copy_from_slice(value.as_ref());
+                    #vis fn #set_ident(&mut self, value: #field_type) {
+                        #store
+                        #update
                     }
                 }
             }
@@ -727,14 +3295,19 @@ assert_eq!(key.{2}(), {4});"#,
 
     /// Define the code to insert the value for this field into the key array.
     pub fn sets(&self, db_key: &DBKeyStruct) -> TokenStream {
+        // Computed fields are only set by `update_computed()`, and padding fields are always
+        // zero.
+        if !self.is_input() || self.attr.readonly {
+            return quote! {};
+        }
         let example_start = db_key.example_start();
         let struct_ident = &db_key.ident;
         let set_doc = format!("Set the {} in the `{}`.", &self.attr.name, struct_ident);
-        let random1 = FieldValue::random(self.field_type.size);
+        let random1 = FieldValue::random(&self.field_type);
         let random2 = {
-            let mut random = FieldValue::random(self.field_type.size);
+            let mut random = FieldValue::random(&self.field_type);
             while random == random1 {
-                random = FieldValue::random(self.field_type.size);
+                random = FieldValue::random(&self.field_type);
             }
             random
         };
@@ -743,51 +3316,89 @@ assert_eq!(key.{2}(), {4});"#,
 
 assert_eq!(key.{1}(), {2});
 key.{3}({4});
-assert_eq!(key.{1}(), {4});"#,
+assert_eq!(key.{1}(), {5});"#,
             db_key.doc_init_key("mut key", &self.ident, &random1),  // 0
             &self.ident,    // 1
-            random1,    // 2
+            random1.assert_eq(),    // 2
             &self.set_ident,    // 3
             random2,    // 4
+            random2.assert_eq(),    // 5
         );
         let docs = &self.attr.docs;
-        let set_code = self.set_code();
+        let collation_doc = self.collation_doc("The value is normalized before it is stored");
+        let update = if db_key.fields.has_computed() {
+            quote! { self.update_computed(); }
+        }
+        else {
+            quote! {}
+        };
+        let set_code = self.set_code(&update);
+        let decimal_sets = self.decimal_sets(db_key);
+        let set_example_docs = self.examples(&example_start, &set_example);
         quote! {
             #[doc = #set_doc]
             ///
             #(#docs)*
-            ///
-            /// # Examples
-            ///
-            #[doc = #example_start]
-            #[doc = #set_example]
-            /// ```
+            #collation_doc
+            #set_example_docs
             #set_code
+            #decimal_sets
         }
     }
 
+    /// Return the code of a default, minimum, or maximum value of this field for the doctests.
+    fn doc_value(&self, value: &TokenStream) -> String {
+        self.field_type.doc_value(value)
+    }
+
+    /// Return the code of a default, minimum, or maximum value of this field as it's returned by
+    /// the getter for the doctests.
+    fn doc_get(&self, value: &TokenStream) -> String {
+        self.field_type.doc_get(value)
+    }
+
     /// Define the doctest for the new() function for this field.
     pub fn new_init_doc(&self, output: &mut String, _key_name: &str, _index: usize) {
+        if !self.is_input() {
+            return;
+        }
         let _ = output.write_fmt(format_args!("\t{},\n", self.random));
     }
 
     /// Define the doctest for the new() function for this field.
+    #[allow(clippy::wrong_self_convention)]
     pub fn from_init_doc(&self, output: &mut String, _key_name: &str, _index: usize) {
+        if !self.in_args {
+            return;
+        }
+        if !self.is_input() {
+            // The value of a computed or padding field in the definition structure is ignored.
+            let _ = output.write_fmt(format_args!("\t{}: {},\n", self.ident,
+                self.doc_value(&self.attr.default)));
+            return;
+        }
         let _ = output.write_fmt(format_args!("\t{}: {},\n", self.ident, self.random));
     }
 
     /// Define the doctest for the partial new() function for this field.
     pub fn new_init_partial(&self, output: &mut String, _key_name: &str, index: usize) {
+        if !self.is_input() {
+            return;
+        }
         if 0 == (1 & index) {
             let _ = output.write_fmt(format_args!("\t{},\n", self.random));
         }
         else {
-            let _ = output.write_fmt(format_args!("\t{},\n", self.attr.default));
+            let _ = output.write_fmt(format_args!("\t{},\n", self.doc_value(&self.attr.default)));
         }
     }
 
     /// Define the doctest for the partial new() function for this field.
+    #[allow(clippy::wrong_self_convention)]
     pub fn from_init_partial(&self, output: &mut String, _key_name: &str, index: usize) {
+        if !self.is_input() {
+            return;
+        }
         if 0 == (1 & index) {
             let _ = output.write_fmt(format_args!("\t{}: {},\n", self.ident, self.random));
         }
@@ -795,19 +3406,25 @@ assert_eq!(key.{1}(), {4});"#,
 
     /// Define the doctest for the new() function for this field.
     pub fn verify_parts(&self, output: &mut String, key_name: &str, _index: usize) {
+        if !self.is_input() || !self.is_public() {
+            return;
+        }
         let _ = output.write_fmt(format_args!("\nassert_eq!({}.{}(), {});", key_name, &self.ident,
-            &self.random));
+            self.random.assert_eq()));
     }
 
     /// Define the doctest for the new() function for this field.
     pub fn verify_partial(&self, output: &mut String, key_name: &str, index: usize) {
+        if !self.is_input() || !self.is_public() {
+            return;
+        }
         if 0 == (1 & index) {
             let _ = output.write_fmt(format_args!("\nassert_eq!({}.{}(), {});", key_name,
-                &self.ident, &self.random));
+                &self.ident, self.random.assert_eq()));
         }
         else {
             let _ = output.write_fmt(format_args!("\nassert_eq!({}.{}(), {});", key_name,
-                &self.ident, &self.attr.default));
+                &self.ident, self.doc_get(&self.attr.default)));
         }
     }
 }
@@ -1,5 +1,10 @@
 use proc_macro2::{
+    Group,
+    Ident,
+    Literal,
+    Span,
     TokenStream,
+    TokenTree,
 };
 use quote::{quote, ToTokens};
 use syn::{
@@ -7,7 +12,11 @@ use syn::{
     Error,
     Expr,
     Field,
+    GenericArgument,
     Lit,
+    Meta,
+    Path,
+    PathArguments,
     Result,
     spanned::Spanned,
     Type,
@@ -29,6 +38,29 @@ pub enum FieldSize {
     Unsigned64,
     Unsigned128,
     Array(usize),
+    /// `std::net::Ipv4Addr` stored as its 4 octets.
+    Ipv4,
+    /// `std::net::Ipv6Addr` stored as its 16 octets.
+    Ipv6,
+    /// `std::net::SocketAddrV4` stored as the IP address followed by the port.
+    SocketV4,
+    /// `std::net::SocketAddrV6` stored as the IP address followed by the port, flow info, and
+    /// scope ID.
+    SocketV6,
+    /// `uuid::Uuid` stored as its 16 bytes.
+    Uuid,
+    /// `std::time::Duration` stored as an unsigned count of the time unit of the field.
+    Duration,
+    /// `std::time::SystemTime` stored as an unsigned count of the time unit of the field since
+    /// the Unix epoch.
+    SystemTime,
+    /// A type with a `db_key::KeyFieldCodec` stored in the number of bytes of the `bytes`
+    /// attribute.
+    Codec(usize),
+    /// `char` stored as the big-endian scalar value.
+    Char,
+    /// An array of `char` stored as the big-endian scalar values.
+    CharArray(usize),
 }
 
 impl FieldSize {
@@ -46,6 +78,15 @@ impl FieldSize {
             FieldSize::Unsigned64 => 8,
             FieldSize::Unsigned128 => 16,
             FieldSize::Array(size) => *size,
+            FieldSize::Ipv4 => 4,
+            FieldSize::Ipv6 => 16,
+            FieldSize::SocketV4 => 6,
+            FieldSize::SocketV6 => 26,
+            FieldSize::Uuid => 16,
+            FieldSize::Duration | FieldSize::SystemTime => 8,
+            FieldSize::Codec(size) => *size,
+            FieldSize::Char => 4,
+            FieldSize::CharArray(count) => 4 * count,
         }
     }
 }
@@ -63,56 +104,491 @@ impl std::fmt::Display for FieldSize {
             FieldSize::Unsigned32 => f.write_str("4"),
             FieldSize::Unsigned64 => f.write_str("8"),
             FieldSize::Unsigned128 => f.write_str("16"),
-            FieldSize::Array(size) => write!(f, "{}", size),
+            FieldSize::Array(size) | FieldSize::Codec(size) => write!(f, "{}", size),
+            FieldSize::Ipv4 | FieldSize::Ipv6 | FieldSize::SocketV4 | FieldSize::SocketV6 |
+                FieldSize::Uuid | FieldSize::Duration | FieldSize::SystemTime | FieldSize::Char |
+                FieldSize::CharArray(_) => write!(f, "{}", self.size()),
+        }
+    }
+}
+
+impl FieldSize {
+    /// Return the name of how the values of the type are stored for the JSON schema of the key.
+    pub fn encoding(&self) -> &'static str {
+        match self {
+            FieldSize::Signed8 | FieldSize::Signed16 | FieldSize::Signed32 | FieldSize::Signed64 |
+                FieldSize::Signed128 => "int",
+            FieldSize::Unsigned8 | FieldSize::Unsigned16 | FieldSize::Unsigned32 |
+                FieldSize::Unsigned64 | FieldSize::Unsigned128 => "uint",
+            FieldSize::Array(_) => "bytes",
+            FieldSize::Ipv4 => "ipv4",
+            FieldSize::Ipv6 => "ipv6",
+            FieldSize::SocketV4 => "socket_v4",
+            FieldSize::SocketV6 => "socket_v6",
+            FieldSize::Uuid => "uuid",
+            FieldSize::Duration => "duration",
+            FieldSize::SystemTime => "system_time",
+            FieldSize::Codec(_) => "codec",
+            FieldSize::Char | FieldSize::CharArray(_) => "char",
+        }
+    }
+
+    /// Return true if the field is a signed integer.
+    pub fn is_signed(&self) -> bool {
+        matches!(self, FieldSize::Signed8 | FieldSize::Signed16 | FieldSize::Signed32 |
+            FieldSize::Signed64 | FieldSize::Signed128)
+    }
+
+    /// Return true if the field is an integer.
+    pub fn is_integer(&self) -> bool {
+        !matches!(self, FieldSize::Array(_) | FieldSize::Ipv4 | FieldSize::Ipv6 |
+            FieldSize::SocketV4 | FieldSize::SocketV6 | FieldSize::Uuid | FieldSize::Duration |
+            FieldSize::SystemTime | FieldSize::Codec(_) | FieldSize::Char |
+            FieldSize::CharArray(_))
+    }
+
+    /// Return true if the field is a `char` or an array of `char`.
+    pub fn is_char(&self) -> bool {
+        matches!(self, FieldSize::Char | FieldSize::CharArray(_))
+    }
+
+    /// Return the number of characters of `char` fields.
+    pub fn char_count(&self) -> usize {
+        match self {
+            FieldSize::CharArray(count) => *count,
+            _ => 1,
+        }
+    }
+
+    /// Return true if the field is a `Duration` or a `SystemTime`.
+    pub fn is_time(&self) -> bool {
+        matches!(self, FieldSize::Duration | FieldSize::SystemTime)
+    }
+
+    /// Return the number of bytes of the IP address at the start of `std::net` address fields.
+    pub fn ip_size(&self) -> Option<usize> {
+        match self {
+            FieldSize::Ipv4 | FieldSize::SocketV4 => Some(4),
+            FieldSize::Ipv6 | FieldSize::SocketV6 => Some(16),
+            _ => None,
+        }
+    }
+}
+
+/// Where `None` is sorted for `Option` fields.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum OptionOrder {
+    /// `None` sorts before all `Some` values.
+    NoneFirst,
+    /// `None` sorts after all `Some` values.
+    NoneLast,
+}
+
+/// The unit of the count stored for `Duration` and `SystemTime` fields.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub enum TimeUnit {
+    Seconds,
+    Millis,
+    Micros,
+    #[default]
+    Nanos,
+}
+
+impl TryFrom<&str> for TimeUnit {
+    type Error = ();
+
+    fn try_from(s: &str) -> std::result::Result<Self, Self::Error> {
+        match s {
+            "s" => Ok(TimeUnit::Seconds),
+            "ms" => Ok(TimeUnit::Millis),
+            "us" => Ok(TimeUnit::Micros),
+            "ns" => Ok(TimeUnit::Nanos),
+            _ => Err(()),
+        }
+    }
+}
+
+impl TimeUnit {
+    /// Return the name of the `Duration` functions for the unit (e.g. `millis` for `as_millis`
+    /// and `from_millis`).
+    pub fn name(&self) -> &'static str {
+        match self {
+            TimeUnit::Seconds => "secs",
+            TimeUnit::Millis => "millis",
+            TimeUnit::Micros => "micros",
+            TimeUnit::Nanos => "nanos",
+        }
+    }
+
+    /// Return the expression for the `u128` count of the unit in the `Duration` `value`, which
+    /// can be used in a constant.
+    pub fn count(&self, value: TokenStream) -> TokenStream {
+        match self {
+            TimeUnit::Seconds => quote! { (#value.as_secs() as u128) },
+            TimeUnit::Millis => quote! { #value.as_millis() },
+            TimeUnit::Micros => quote! { #value.as_micros() },
+            TimeUnit::Nanos => quote! { #value.as_nanos() },
+        }
+    }
+
+    /// Return the expression for the `Duration` of the `u64` `count` of the unit.
+    pub fn duration(&self, count: TokenStream) -> TokenStream {
+        let from = Ident::new(&format!("from_{}", self.name()), Span::call_site());
+        quote! { ::std::time::Duration::#from(#count) }
+    }
+}
+
+/// The codec of a field with the `codec` attribute.
+#[derive(Debug)]
+pub struct Codec {
+    /// The path of the type that implements `db_key::KeyFieldCodec`.
+    path: Path,
+}
+
+impl Codec {
+    /// Return the codec as a `db_key::KeyFieldCodec` for calling the functions and constants of
+    /// the trait.
+    pub fn as_trait(&self) -> TokenStream {
+        let path = &self.path;
+        quote! { <#path as ::db_key::KeyFieldCodec> }
+    }
+}
+
+/// Replace the `crate` identifiers in the code with the name of the crate, because the
+/// documentation examples are compiled outside of the crate.
+fn replace_crate(stream: TokenStream, crate_name: &str) -> TokenStream {
+    stream.into_iter().map(|tree| match tree {
+        TokenTree::Ident(ident) if "crate" == ident.to_string().as_str() => {
+            TokenTree::Ident(Ident::new(crate_name, ident.span()))
+        }
+        TokenTree::Group(group) => {
+            let mut replaced = Group::new(group.delimiter(),
+                replace_crate(group.stream(), crate_name));
+            replaced.set_span(group.span());
+            TokenTree::Group(replaced)
+        }
+        tree => tree,
+    }).collect()
+}
+
+/// The `std::num` non-zero integer type of a field, which is stored as its primitive type.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct NonZero {
+    /// The name of the type in `std::num` (e.g. `NonZeroU64`).
+    pub ident: Ident,
+    /// The field is an `Option` of the type, which stores `None` as zero bytes (the minimum value
+    /// of the field) instead of using a presence byte.
+    pub optional: bool,
+}
+
+impl NonZero {
+    /// Return the full path of the type.
+    pub fn path(&self) -> TokenStream {
+        let ident = &self.ident;
+        quote! { ::std::num::#ident }
+    }
+}
+
+/// The normalization of the bytes of byte array fields before they are stored in the key.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub enum Collation {
+    /// Store the bytes unchanged.
+    #[default]
+    Binary,
+    /// Store ASCII letters in lowercase, so the case of the letters is ignored.
+    AsciiCi,
+    /// Remove the leading and trailing ASCII whitespace and store ASCII letters in lowercase. The
+    /// remaining bytes start the field and are followed by zeros.
+    AsciiCiTrim,
+}
+
+impl TryFrom<&str> for Collation {
+    type Error = ();
+
+    fn try_from(s: &str) -> std::result::Result<Self, Self::Error> {
+        match s {
+            "binary" => Ok(Collation::Binary),
+            "ascii_ci" => Ok(Collation::AsciiCi),
+            "ascii_ci_trim" => Ok(Collation::AsciiCiTrim),
+            _ => Err(()),
+        }
+    }
+}
+
+impl Collation {
+    /// Return the name of the collation used by the `collate` attribute.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Collation::Binary => "binary",
+            Collation::AsciiCi => "ascii_ci",
+            Collation::AsciiCiTrim => "ascii_ci_trim",
+        }
+    }
+
+    /// Return the code that normalizes the mutable `[u8; size]` array `value`, which can be used
+    /// in a constant.
+    pub fn normalize(&self, size: usize) -> TokenStream {
+        match self {
+            Collation::Binary => quote! {},
+            Collation::AsciiCi => quote! {
+                let mut i = 0;
+                while i < #size {
+                    value[i] = value[i].to_ascii_lowercase();
+                    i += 1;
+                }
+            },
+            Collation::AsciiCiTrim => quote! {
+                let mut start = 0;
+                while start < #size && value[start].is_ascii_whitespace() {
+                    start += 1;
+                }
+                let mut end = #size;
+                while start < end && (value[end - 1].is_ascii_whitespace() || 0 == value[end - 1]) {
+                    end -= 1;
+                }
+                let mut trimmed = [0_u8; #size];
+                let mut i = 0;
+                while start + i < end {
+                    trimmed[i] = value[start + i].to_ascii_lowercase();
+                    i += 1;
+                }
+                value = trimmed;
+            },
         }
     }
 }
 
 #[derive(Debug)]
 pub struct FieldType {
+    /// The type of the field as it was declared.
     pub field_type: Type,
+    /// The type of the value stored in the field. This is only different from the declared type
+    /// for `Option` fields.
+    pub value_type: Type,
     pub string: String,
     pub size: FieldSize,
+    /// The number of bytes the field uses in the key. This is only different from the size of
+    /// the type for integers with the `bytes` attribute.
+    pub width: usize,
+    /// The number of bits the field uses in the key for integers with the `bits` attribute.
+    pub bits: Option<usize>,
+    /// The order of `None` for `Option` fields, which are stored as a presence byte followed by
+    /// the value.
+    pub option: Option<OptionOrder>,
+    /// The unit of the count stored for `Duration` and `SystemTime` fields.
+    pub time_unit: TimeUnit,
+    /// The codec of fields with the `codec` attribute.
+    pub codec: Option<Codec>,
+    /// The name of the crate used in place of `crate` in the documentation examples.
+    pub doc_crate: String,
+    /// The newtype of fields with the `via` attribute, which is converted to and from the
+    /// supported type of the value.
+    pub newtype: Option<Type>,
+    /// The normalization of byte array fields with the `collate` attribute.
+    pub collation: Collation,
+    /// The non-zero integer type of `NonZero*` fields, which is converted to and from the
+    /// primitive type of the value.
+    pub nonzero: Option<NonZero>,
 }
 
 impl TryFrom<&Field> for FieldType {
     type Error = Error;
 
     fn try_from(field: &Field) -> Result<Self> {
-        const ERROR_STR: &'static str = "Unsupported field type for db_key";
-        const ERROR_ZERO: &'static str = "Unsupported array size for db_key";
-        let field_type = field.ty.clone();  // We always need a clone of this on success.
+        let codec = codec_path(field)?;
+        let via = via_type(field)?;
+        if let (Some(_), Some(via)) = (&codec, &via) {
+            return Err(Error::new(via.span(), "The codec and via attributes can't both be used."));
+        }
+        match option_inner_type(&field.ty) {
+            Some(inner) => {
+                if option_inner_type(inner).is_some() {
+                    return Err(Error::new(inner.span(), "Nested Option fields are not supported"));
+                }
+                let mut field_type = FieldType::try_from_value(inner, codec, via)?;
+                field_type.field_type = field.ty.clone();
+                match &mut field_type.nonzero {
+                    // `None` is stored as zero bytes, which is zero for unsigned types and the
+                    // minimum value of the field for signed types.
+                    Some(nonzero) => { nonzero.optional = true; }
+                    None => { field_type.option = Some(OptionOrder::NoneFirst); }
+                }
+                Ok(field_type)
+            }
+            None => FieldType::try_from_value(&field.ty, codec, via),
+        }
+    }
+}
+
+/// Return the supported type of the `via` attribute of a field.
+fn via_type(field: &Field) -> Result<Option<Type>> {
+    for attr in field.attrs.iter() {
+        if !attr.path().is_ident("via") {
+            continue;
+        }
+        let value = match &attr.meta {
+            Meta::NameValue(name_value) => &name_value.value,
+            _ => {
+                return Err(Error::new(attr.span(), "The via attribute expects a type."));
+            }
+        };
+        return match value {
+            Expr::Lit(expr_lit) => match &expr_lit.lit {
+                Lit::Str(lit_str) => lit_str.parse().map(Some),
+                lit => Err(Error::new(lit.span(), "The via attribute expects a type.")),
+            }
+            _ => syn::parse2(value.to_token_stream()).map(Some)
+                .map_err(|_| Error::new(value.span(), "The via attribute expects a type.")),
+        };
+    }
+    Ok(None)
+}
+
+/// Return the path of the `codec` attribute of a field.
+fn codec_path(field: &Field) -> Result<Option<Path>> {
+    for attr in field.attrs.iter() {
+        if !attr.path().is_ident("codec") {
+            continue;
+        }
+        let value = match &attr.meta {
+            Meta::NameValue(name_value) => &name_value.value,
+            _ => {
+                return Err(Error::new(attr.span(), "The codec attribute expects a value."));
+            }
+        };
+        return match value {
+            Expr::Path(expr_path) if expr_path.qself.is_none() => Ok(Some(expr_path.path.clone())),
+            Expr::Lit(expr_lit) => match &expr_lit.lit {
+                Lit::Str(lit_str) => lit_str.parse().map(Some),
+                lit => Err(Error::new(lit.span(), "The codec attribute expects a path.")),
+            }
+            _ => Err(Error::new(value.span(), "The codec attribute expects a path.")),
+        };
+    }
+    Ok(None)
+}
+
+/// Return the `T` of an `Option<T>` type.
+fn option_inner_type(ty: &Type) -> Option<&Type> {
+    let segment = match ty {
+        Type::Path(path) => path.path.segments.last()?,
+        _ => return None,
+    };
+    if "Option" != segment.ident.to_string().as_str() {
+        return None;
+    }
+    match &segment.arguments {
+        PathArguments::AngleBracketed(args) if 1 == args.args.len() => match args.args.first() {
+            Some(GenericArgument::Type(inner)) => Some(inner),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+impl FieldType {
+    /// Parse the type of the value of a field, which can be any type for fields with a codec,
+    /// and is stored as the supported type of the `via` attribute for newtypes.
+    fn try_from_value(ty: &Type, codec: Option<Path>, via: Option<Type>) -> Result<Self> {
+        if let Some(via) = via {
+            let mut field_type = FieldType::try_from(&via)?;
+            if field_type.nonzero.is_some() {
+                return Err(Error::new(via.span(),
+                    "The via attribute doesn't support non-zero integer types."));
+            }
+            field_type.field_type = ty.clone();
+            field_type.newtype = Some(ty.clone());
+            return Ok(field_type);
+        }
+        match codec {
+            Some(path) => Ok(Self {
+                field_type: ty.clone(),
+                value_type: ty.clone(),
+                string: ty.to_token_stream().to_string(),
+                // The size is set by the required bytes attribute.
+                size: FieldSize::Codec(0),
+                width: 0,
+                bits: None,
+                option: None,
+                time_unit: TimeUnit::default(),
+                codec: Some(Codec { path }),
+                doc_crate: "crate".to_string(),
+                newtype: None,
+                collation: Collation::default(),
+                nonzero: None,
+            }),
+            None => FieldType::try_from(ty),
+        }
+    }
+}
+
+impl TryFrom<&Type> for FieldType {
+    type Error = Error;
+
+    fn try_from(ty: &Type) -> Result<Self> {
+        const ERROR_STR: &str = "Unsupported field type for db_key";
+        const ERROR_ZERO: &str = "Unsupported array size for db_key";
+        let field_type = ty.clone();  // We always need a clone of this on success.
         match &field_type {
             Type::Path(path) => {
-                let ident = match path.path.get_ident() {
-                    Some(ident) => ident.clone(),
-                    None => { return Err(Error::new(field_type.span(), ERROR_STR)); }
+                // The std::net types can be used with their full path.
+                let ident = match path.path.segments.last() {
+                    Some(segment) if segment.arguments.is_empty() => segment.ident.clone(),
+                    _ => { return Err(Error::new(field_type.span(), ERROR_STR)); }
                 };
                 let string = ident.to_string();
+                let is_ident = path.qself.is_none() && path.path.get_ident().is_some();
                 let size = match string.as_str() {
-                    "i8" => FieldSize::Signed8,
-                    "i16" => FieldSize::Signed16,
-                    "i32" => FieldSize::Signed32,
-                    "i64" => FieldSize::Signed64,
-                    "i128" => FieldSize::Signed128,
-                    "u8" => FieldSize::Unsigned8,
-                    "u16" => FieldSize::Unsigned16,
-                    "u32" => FieldSize::Unsigned32,
-                    "u64" => FieldSize::Unsigned64,
-                    "u128" => FieldSize::Unsigned128,
+                    "i8" if is_ident => FieldSize::Signed8,
+                    "i16" if is_ident => FieldSize::Signed16,
+                    "i32" if is_ident => FieldSize::Signed32,
+                    "i64" if is_ident => FieldSize::Signed64,
+                    "i128" if is_ident => FieldSize::Signed128,
+                    "u8" if is_ident => FieldSize::Unsigned8,
+                    "u16" if is_ident => FieldSize::Unsigned16,
+                    "u32" if is_ident => FieldSize::Unsigned32,
+                    "u64" if is_ident => FieldSize::Unsigned64,
+                    "u128" if is_ident => FieldSize::Unsigned128,
+                    "char" if is_ident => FieldSize::Char,
+                    "Ipv4Addr" => FieldSize::Ipv4,
+                    "Ipv6Addr" => FieldSize::Ipv6,
+                    "SocketAddrV4" => FieldSize::SocketV4,
+                    "SocketAddrV6" => FieldSize::SocketV6,
+                    "Uuid" if cfg!(feature = "uuid") => FieldSize::Uuid,
+                    "Uuid" => {
+                        return Err(Error::new(field_type.span(),
+                            "Uuid fields require the uuid feature of db-key-macro"));
+                    }
+                    "Duration" => FieldSize::Duration,
+                    "SystemTime" => FieldSize::SystemTime,
+                    nonzero if nonzero.starts_with("NonZero") => {
+                        return Self::try_nonzero(&field_type, &ident);
+                    }
                     _ => { return Err(Error::new(field_type.span(), ERROR_STR)); }
                 };
                 Ok(Self {
+                    value_type: field_type.clone(),
                     field_type,
                     string,
+                    width: size.size(),
+                    bits: None,
+                    option: None,
+                    time_unit: TimeUnit::default(),
+                    codec: None,
+                    doc_crate: "crate".to_string(),
+                    newtype: None,
+                    collation: Collation::default(),
+                    nonzero: None,
                     size,
                 })
             }
             Type::Array(array) => {
-                match &*array.elem {
+                let is_char = match &*array.elem {
                     Type::Path(path) => match path.path.get_ident() {
-                        Some(ident) => if "u8" != &format!("{}", ident) {
-                            return Err(Error::new(ident.span(), ERROR_STR));
+                        Some(ident) => match ident.to_string().as_str() {
+                            "u8" => false,
+                            "char" => true,
+                            _ => { return Err(Error::new(ident.span(), ERROR_STR)); }
                         }
                         None => { return Err(Error::new(path.span(), ERROR_STR)); }
                     }
@@ -120,12 +596,13 @@ impl TryFrom<&Field> for FieldType {
                         return Err(Error::new(array.elem.span(), ERROR_STR));
                     }
 
-                }
+                };
                 let size = match &array.len {
                     Expr::Lit(expr_lit) => match &expr_lit.lit {
                         Lit::Int(lit_int) => {
                             match usize::from_str(lit_int.base10_digits()) {
                                 Ok(0) => { return Err(Error::new(lit_int.span(), ERROR_ZERO)); },
+                                Ok(count) if is_char => FieldSize::CharArray(count),
                                 Ok(size) => FieldSize::Array(size),
                                 Err(_) => { return Err(Error::new(lit_int.span(), ERROR_STR)); }
                             }
@@ -134,10 +611,23 @@ impl TryFrom<&Field> for FieldType {
                     }
                     _ => { return Err(Error::new(array.len.span(), ERROR_STR)); }
                 };
-                let string = format!("[u8; {}]", size);
+                let string = match size {
+                    FieldSize::CharArray(count) => format!("[char; {}]", count),
+                    _ => format!("[u8; {}]", size),
+                };
                 Ok(Self {
+                    value_type: field_type.clone(),
                     field_type,
                     string,
+                    width: size.size(),
+                    bits: None,
+                    option: None,
+                    time_unit: TimeUnit::default(),
+                    codec: None,
+                    doc_crate: "crate".to_string(),
+                    newtype: None,
+                    collation: Collation::default(),
+                    nonzero: None,
                     size,
                 })
             }
@@ -146,23 +636,92 @@ impl TryFrom<&Field> for FieldType {
     }
 }
 
+impl FieldType {
+    /// Parse a `std::num` non-zero integer type, which is stored as its primitive type.
+    fn try_nonzero(field_type: &Type, ident: &Ident) -> Result<Self> {
+        let (size, primitive) = match ident.to_string().as_str() {
+            "NonZeroI8" => (FieldSize::Signed8, "i8"),
+            "NonZeroI16" => (FieldSize::Signed16, "i16"),
+            "NonZeroI32" => (FieldSize::Signed32, "i32"),
+            "NonZeroI64" => (FieldSize::Signed64, "i64"),
+            "NonZeroI128" => (FieldSize::Signed128, "i128"),
+            "NonZeroU8" => (FieldSize::Unsigned8, "u8"),
+            "NonZeroU16" => (FieldSize::Unsigned16, "u16"),
+            "NonZeroU32" => (FieldSize::Unsigned32, "u32"),
+            "NonZeroU64" => (FieldSize::Unsigned64, "u64"),
+            "NonZeroU128" => (FieldSize::Unsigned128, "u128"),
+            _ => { return Err(Error::new(field_type.span(), "Unsupported field type for db_key")); }
+        };
+        let value_type = Type::Verbatim(Ident::new(primitive, ident.span()).into_token_stream());
+        Ok(Self {
+            field_type: field_type.clone(),
+            value_type,
+            string: primitive.to_string(),
+            width: size.size(),
+            bits: None,
+            option: None,
+            time_unit: TimeUnit::default(),
+            codec: None,
+            doc_crate: "crate".to_string(),
+            newtype: None,
+            collation: Collation::default(),
+            nonzero: Some(NonZero { ident: ident.clone(), optional: false }),
+            size,
+        })
+    }
+}
+
 impl ToTokens for FieldType {
     fn to_tokens(&self, tokens: &mut TokenStream) {
-        self.field_type.to_tokens(tokens);
+        self.value_type.to_tokens(tokens);
     }
 }
 
 impl FieldType {
     /// Return default value for a specified integer type.
     pub fn default_lit(&self) -> TokenStream {
+        if self.is_nonzero_required() {
+            return self.int_lit(1);
+        }
+        if self.is_nonzero_signed_option() {
+            // The minimum value is `None`.
+            return self.minimum_lit();
+        }
         match self.size {
             FieldSize::Array(size) => quote! { [0_u8; #size] },
+            FieldSize::Char | FieldSize::CharArray(_) => self.minimum_lit(),
+            FieldSize::Ipv4 | FieldSize::Ipv6 | FieldSize::SocketV4 | FieldSize::SocketV6 |
+                FieldSize::Uuid | FieldSize::Duration | FieldSize::SystemTime |
+                FieldSize::Codec(_) => self.minimum_lit(),
             _ => quote! { 0 },
         }
     }
 
     /// Return minimum value for a specified integer type.
     pub fn minimum_lit(&self) -> TokenStream {
+        if let Some(codec) = &self.codec {
+            let codec = codec.as_trait();
+            return quote! { #codec::MIN };
+        }
+        match self.size {
+            FieldSize::Char => { return quote! { '\0' }; }
+            FieldSize::CharArray(count) => { return quote! { ['\0'; #count] }; }
+            _ => {}
+        }
+        if self.size.is_time() {
+            return quote! { ::std::time::Duration::ZERO };
+        }
+        if self.is_nonzero_required() && !self.size.is_signed() {
+            return self.int_lit(1);
+        }
+        if self.is_reduced() {
+            return if self.size.is_signed() {
+                self.int_lit(-(1_i128 << (self.value_bits() - 1)))
+            }
+            else {
+                self.int_lit(0)
+            };
+        }
         match self.size {
             FieldSize::Signed8 => quote! { i8::MIN },
             FieldSize::Signed16 => quote! { i16::MIN },
@@ -175,11 +734,44 @@ impl FieldType {
             FieldSize::Unsigned64 => quote! { u64::MIN },
             FieldSize::Unsigned128 => quote! { u128::MIN },
             FieldSize::Array(size) => quote! { [u8::MIN; #size] },
+            FieldSize::Ipv4 => quote! { ::std::net::Ipv4Addr::UNSPECIFIED },
+            FieldSize::Ipv6 => quote! { ::std::net::Ipv6Addr::UNSPECIFIED },
+            FieldSize::SocketV4 => quote! {
+                ::std::net::SocketAddrV4::new(::std::net::Ipv4Addr::UNSPECIFIED, 0)
+            },
+            FieldSize::SocketV6 => quote! {
+                ::std::net::SocketAddrV6::new(::std::net::Ipv6Addr::UNSPECIFIED, 0, 0, 0)
+            },
+            FieldSize::Uuid => quote! { ::uuid::Uuid::nil() },
+            // Time, codec, and char fields are handled before matching the other types.
+            FieldSize::Duration | FieldSize::SystemTime | FieldSize::Codec(_) | FieldSize::Char |
+                FieldSize::CharArray(_) => unreachable!(),
         }
     }
 
     /// Return maximum value for a specified integer type.
     pub fn maximum_lit(&self) -> TokenStream {
+        if let Some(codec) = &self.codec {
+            let codec = codec.as_trait();
+            return quote! { #codec::MAX };
+        }
+        match self.size {
+            FieldSize::Char => { return quote! { char::MAX }; }
+            FieldSize::CharArray(count) => { return quote! { [char::MAX; #count] }; }
+            _ => {}
+        }
+        if self.size.is_time() {
+            let count = Literal::u64_suffixed(self.max_count());
+            return self.time_unit.duration(quote! { #count });
+        }
+        if self.is_reduced() {
+            return if self.size.is_signed() {
+                self.int_lit((1_i128 << (self.value_bits() - 1)) - 1)
+            }
+            else {
+                self.uint_lit((1_u128 << self.value_bits()) - 1)
+            };
+        }
         match self.size {
             FieldSize::Signed8 => quote! { i8::MAX },
             FieldSize::Signed16 => quote! { i16::MAX },
@@ -192,25 +784,563 @@ impl FieldType {
             FieldSize::Unsigned64 => quote! { u64::MAX },
             FieldSize::Unsigned128 => quote! { u128::MAX },
             FieldSize::Array(size) => quote! { [u8::MAX; #size] },
+            FieldSize::Ipv4 => quote! { ::std::net::Ipv4Addr::BROADCAST },
+            FieldSize::Ipv6 => quote! {
+                ::std::net::Ipv6Addr::new(u16::MAX, u16::MAX, u16::MAX, u16::MAX, u16::MAX,
+                    u16::MAX, u16::MAX, u16::MAX)
+            },
+            FieldSize::SocketV4 => quote! {
+                ::std::net::SocketAddrV4::new(::std::net::Ipv4Addr::BROADCAST, u16::MAX)
+            },
+            FieldSize::SocketV6 => quote! {
+                ::std::net::SocketAddrV6::new(::std::net::Ipv6Addr::new(u16::MAX, u16::MAX,
+                    u16::MAX, u16::MAX, u16::MAX, u16::MAX, u16::MAX, u16::MAX), u16::MAX,
+                    u32::MAX, u32::MAX)
+            },
+            FieldSize::Uuid => quote! { ::uuid::Uuid::from_bytes([u8::MAX; 16]) },
+            // Time, codec, and char fields are handled before matching the other types.
+            FieldSize::Duration | FieldSize::SystemTime | FieldSize::Codec(_) | FieldSize::Char |
+                FieldSize::CharArray(_) => unreachable!(),
         }
     }
 
-    /// Return the size of the field type in bytes.
+    /// Return the value XORed with signed integers to toggle the sign bit so that the key order
+    /// is preserved.
+    pub fn xor_mask(&self) -> TokenStream {
+        if self.is_reduced() {
+            self.uint_lit(1_u128 << (self.value_bits() - 1))
+        }
+        else {
+            self.minimum_lit()
+        }
+    }
+
+    /// Return the declared default value of the field, which is `None` for `Option` fields.
+    pub fn field_default_lit(&self) -> TokenStream {
+        match self.option {
+            Some(_) => quote! { None },
+            None => self.default_lit(),
+        }
+    }
+
+    /// Return the declared minimum value of the field, which depends on where `None` is sorted
+    /// for `Option` fields.
+    pub fn field_minimum_lit(&self) -> TokenStream {
+        let minimum = self.minimum_lit();
+        match self.option {
+            Some(OptionOrder::NoneFirst) => quote! { None },
+            Some(OptionOrder::NoneLast) => quote! { Some(#minimum) },
+            None => minimum,
+        }
+    }
+
+    /// Return the declared maximum value of the field, which depends on where `None` is sorted
+    /// for `Option` fields.
+    pub fn field_maximum_lit(&self) -> TokenStream {
+        let maximum = self.maximum_lit();
+        match self.option {
+            Some(OptionOrder::NoneFirst) => quote! { Some(#maximum) },
+            Some(OptionOrder::NoneLast) => quote! { None },
+            None => maximum,
+        }
+    }
+
+    /// Return the type the field is stored as for the schema of the key (e.g. `Option<u64>`),
+    /// which ignores the `via` newtype.
+    pub fn schema_type(&self) -> String {
+        let schema_type = match &self.nonzero {
+            Some(nonzero) if nonzero.optional => format!("Option<{}>", nonzero.ident),
+            Some(nonzero) => nonzero.ident.to_string(),
+            None => self.string.replace(' ', ""),
+        };
+        if self.option.is_some() {
+            format!("Option<{}>", schema_type)
+        }
+        else {
+            schema_type
+        }
+    }
+
+    /// Return the description of how the field is encoded for the schema description of the key
+    /// (e.g. `Option<u64> none_last`), which doesn't depend on how the type was written.
+    pub fn schema(&self) -> String {
+        let mut schema = self.schema_type();
+        if let Some(codec) = &self.codec {
+            let path = &codec.path;
+            schema.push_str(&format!(" codec={}", quote! { #path }.to_string().replace(' ', "")));
+        }
+        if Some(OptionOrder::NoneLast) == self.option {
+            schema.push_str(" none_last");
+        }
+        if self.size.is_time() {
+            schema.push_str(&format!(" time_unit={}", self.time_unit.name()));
+        }
+        if Collation::Binary != self.collation {
+            schema.push_str(&format!(" collate={}", self.collation.name()));
+        }
+        schema
+    }
+
+    /// Return the code of the options of the `db_key::FieldSchema` of the field that depend on
+    /// its type.
+    pub fn field_schema(&self) -> TokenStream {
+        let encoding = self.size.encoding();
+        let sign_flip = self.size.is_signed();
+        let option = match (self.option, &self.nonzero) {
+            (Some(OptionOrder::NoneFirst), _) => quote! { Some("none_first") },
+            (Some(OptionOrder::NoneLast), _) => quote! { Some("none_last") },
+            (None, Some(nonzero)) if nonzero.optional => quote! { Some("zero") },
+            (None, _) => quote! { None },
+        };
+        let time_unit = if self.size.is_time() {
+            let name = self.time_unit.name();
+            quote! { Some(#name) }
+        }
+        else {
+            quote! { None }
+        };
+        let char_size = if self.size.is_char() {
+            let char_size = self.char_width();
+            quote! { Some(#char_size) }
+        }
+        else {
+            quote! { None }
+        };
+        let collation = if Collation::Binary != self.collation {
+            let name = self.collation.name();
+            quote! { Some(#name) }
+        }
+        else {
+            quote! { None }
+        };
+        let codec = match &self.codec {
+            Some(codec) => {
+                let path = &codec.path;
+                let path = quote! { #path }.to_string().replace(' ', "");
+                quote! { Some(#path) }
+            }
+            None => quote! { None },
+        };
+        quote! {
+            encoding: #encoding,
+            sign_flip: #sign_flip,
+            option: #option,
+            time_unit: #time_unit,
+            char_size: #char_size,
+            collation: #collation,
+            codec: #codec,
+        }
+    }
+
+    /// Return the type of the field as it was declared.
+    #[inline]
+    pub fn declared(&self) -> &Type {
+        &self.field_type
+    }
+
+    /// Return the type of the default, minimum, and maximum constants of the field. It's the
+    /// declared type, except `SystemTime` can't be created in a constant, so it's replaced by the
+    /// `Duration` since the Unix epoch, and the values of codec fields are their encoded bytes.
+    pub fn const_type(&self) -> TokenStream {
+        match (self.size, self.option) {
+            (FieldSize::SystemTime, Some(_)) => quote! { Option<::std::time::Duration> },
+            (FieldSize::SystemTime, None) => quote! { ::std::time::Duration },
+            (FieldSize::Codec(_), Some(_)) => quote! { Option<&'static [u8]> },
+            (FieldSize::Codec(_), None) => quote! { &'static [u8] },
+            // The constants of newtype fields are the supported type, because the conversion
+            // can't be done in a constant.
+            (_, Some(_)) if self.newtype.is_some() => {
+                let value_type = &self.value_type;
+                quote! { Option<#value_type> }
+            }
+            _ => {
+                let field_type = if self.newtype.is_some() || self.nonzero.is_some() {
+                    &self.value_type
+                }
+                else {
+                    &self.field_type
+                };
+                quote! { #field_type }
+            }
+        }
+    }
+
+    /// Convert the expression of a constant of the `const_type()` of the field to the declared
+    /// type of the field.
+    pub fn const_to_value(&self, value: &TokenStream) -> TokenStream {
+        if let Some(nonzero) = &self.nonzero {
+            let path = nonzero.path();
+            return if nonzero.optional {
+                self.nonzero_option_value(value)
+            }
+            else {
+                quote! { #path::new(#value).unwrap() }
+            };
+        }
+        let value = self.scalar_const_to_value(value);
+        match (&self.newtype, self.option) {
+            (Some(_), Some(_)) => {
+                let from = self.scalar_to_value_fn();
+                quote! { (#value).map(#from) }
+            }
+            (Some(_), None) => self.scalar_to_value(&value),
+            (None, _) => value,
+        }
+    }
+
+    /// Return the function that converts the supported type of a newtype field to the newtype.
+    /// The newtype isn't named, so that the documentation examples don't depend on its path.
+    fn scalar_to_value_fn(&self) -> TokenStream {
+        let value_type = &self.value_type;
+        quote! { ::std::convert::From::<#value_type>::from }
+    }
+
+    /// Convert the expression of a constant of the `const_type()` of the field to the type
+    /// returned by the getter, which is an `Option` for non-zero integer fields.
+    pub fn const_to_get(&self, value: &TokenStream) -> TokenStream {
+        match &self.nonzero {
+            Some(_) => self.scalar_to_value(value),
+            None => self.const_to_value(value),
+        }
+    }
+
+    /// Convert the expression of the supported type of a newtype field to the newtype, or the
+    /// primitive value of a non-zero integer field to an `Option` of the non-zero type.
+    pub fn scalar_to_value(&self, value: &TokenStream) -> TokenStream {
+        if let Some(nonzero) = &self.nonzero {
+            let path = nonzero.path();
+            return if nonzero.optional {
+                self.nonzero_option_value(value)
+            }
+            else {
+                quote! { #path::new(#value) }
+            };
+        }
+        match &self.newtype {
+            Some(_) => {
+                let from = self.scalar_to_value_fn();
+                quote! { #from(#value) }
+            }
+            None => value.clone(),
+        }
+    }
+
+    /// Convert the expression of the primitive value of an `Option` of a non-zero integer to the
+    /// `Option`. `None` is stored as the minimum value of the field, whose bytes are all zero, so
+    /// it's zero for unsigned types, and the minimum value isn't a valid value for signed types.
+    fn nonzero_option_value(&self, value: &TokenStream) -> TokenStream {
+        let path = self.nonzero.as_ref().unwrap().path();
+        if !self.size.is_signed() {
+            return quote! { #path::new(#value) };
+        }
+        let none = self.minimum_lit();
+        if none.to_string() == value.to_string() {
+            // Keep the minimum constant readable in the documentation examples.
+            return quote! { None };
+        }
+        quote! {
+            {
+                let value = #value;
+                if #none == value { None } else { #path::new(value) }
+            }
+        }
+    }
+
+    /// Convert the expression of a newtype, or a reference to one, to the supported type of the
+    /// field. The newtype is cloned, so the value isn't moved out of the arguments.
+    pub fn to_scalar(&self, value: &TokenStream) -> TokenStream {
+        if let Some(nonzero) = &self.nonzero {
+            return if nonzero.optional {
+                let none = self.minimum_lit();
+                quote! {
+                    match #value {
+                        Some(value) => value.get(),
+                        None => #none,
+                    }
+                }
+            }
+            else {
+                quote! { (#value).get() }
+            };
+        }
+        match &self.newtype {
+            Some(newtype) => {
+                let value_type = &self.value_type;
+                quote! {
+                    <#newtype as ::std::convert::Into<#value_type>>::into(
+                        <#newtype as ::std::clone::Clone>::clone(&#value))
+                }
+            }
+            None => value.clone(),
+        }
+    }
+
+    /// Return the type of the value of the field that is used by the getters and setters, which
+    /// is the newtype for fields with the `via` attribute.
+    pub fn value_decl(&self) -> TokenStream {
+        if self.nonzero.is_some() {
+            let field_type = &self.field_type;
+            return quote! { #field_type };
+        }
+        match &self.newtype {
+            Some(newtype) => quote! { #newtype },
+            None => {
+                let value_type = &self.value_type;
+                quote! { #value_type }
+            }
+        }
+    }
+
+    /// Return true if `Option` values are matched by reference, because the value type may not
+    /// be `Copy`.
+    pub fn is_borrowed(&self) -> bool {
+        self.codec.is_some() || self.newtype.is_some()
+    }
+
+    /// Convert the expression of a constant of the `const_type()` of the field to the supported
+    /// type of the field.
+    fn scalar_const_to_value(&self, value: &TokenStream) -> TokenStream {
+        match (self.size, self.option) {
+            (FieldSize::SystemTime, Some(_)) => quote! {
+                (#value).map(|duration| ::std::time::UNIX_EPOCH + duration)
+            },
+            (FieldSize::SystemTime, None) => quote! { ::std::time::UNIX_EPOCH + #value },
+            (FieldSize::Codec(_), option) => {
+                let codec = self.codec.as_ref().unwrap().as_trait();
+                match option {
+                    Some(_) => quote! { (#value).map(|bytes| #codec::decode(bytes)) },
+                    None => quote! { #codec::decode(#value) },
+                }
+            }
+            _ => value.clone(),
+        }
+    }
+
+    /// Return the code of a constant of the `const_type()` of the field converted to the declared
+    /// type of the field for the documentation examples.
+    pub fn doc_value(&self, value: &TokenStream) -> String {
+        replace_crate(self.const_to_value(value), &self.doc_crate).to_string()
+    }
+
+    /// Return the code of a constant of the `const_type()` of the field converted to the type
+    /// returned by the getter for the documentation examples.
+    pub fn doc_get(&self, value: &TokenStream) -> String {
+        replace_crate(self.const_to_get(value), &self.doc_crate).to_string()
+    }
+
+    /// Return the type returned by the getter, which is an `Option` for non-zero integer fields.
+    pub fn get_decl(&self) -> TokenStream {
+        match &self.nonzero {
+            Some(nonzero) => {
+                let path = nonzero.path();
+                quote! { Option<#path> }
+            }
+            None => self.value_decl(),
+        }
+    }
+
+    /// Return true if the field is a non-zero integer that isn't an `Option`, so the field
+    /// can't be zero.
+    pub fn is_nonzero_required(&self) -> bool {
+        matches!(&self.nonzero, Some(nonzero) if !nonzero.optional)
+    }
+
+    /// Return true if the field is an `Option` of a non-zero integer, which stores `None` as zero
+    /// bytes.
+    pub fn is_nonzero_option(&self) -> bool {
+        matches!(&self.nonzero, Some(nonzero) if nonzero.optional)
+    }
+
+    /// Return true if the field is an `Option` of a signed non-zero integer, which reserves the
+    /// minimum value of the field for `None`.
+    pub fn is_nonzero_signed_option(&self) -> bool {
+        self.is_nonzero_option() && self.size.is_signed()
+    }
+
+    /// Return the codec as a `db_key::KeyFieldCodec` for the documentation examples.
+    pub fn doc_codec(&self) -> Option<String> {
+        self.codec.as_ref()
+            .map(|codec| replace_crate(codec.as_trait(), &self.doc_crate).to_string())
+    }
+
+    /// Set the name of the crate used in place of `crate` in the documentation examples.
+    pub fn set_crate_name(&mut self, crate_name: &str) {
+        self.doc_crate = crate_name.to_string();
+    }
+
+    /// Return the largest count of the time unit that can be stored in a time field.
+    pub fn max_count(&self) -> u64 {
+        if 8 <= self.width { u64::MAX } else { (1_u64 << (self.width * 8)) - 1 }
+    }
+
+    /// Set the unit of the count stored for a time field.
+    pub fn set_time_unit(&mut self, time_unit: TimeUnit, span: Span) -> Result<()> {
+        if !self.size.is_time() {
+            return Err(Error::new(span,
+                "The time_unit attribute is only supported by Duration and SystemTime fields."));
+        }
+        self.time_unit = time_unit;
+        Ok(())
+    }
+
+    /// Set the normalization of a byte array field.
+    pub fn set_collation(&mut self, collation: Collation, span: Span) -> Result<()> {
+        if !matches!(self.size, FieldSize::Array(_)) {
+            return Err(Error::new(span,
+                "The collate attribute is only supported by byte array fields."));
+        }
+        self.collation = collation;
+        Ok(())
+    }
+
+    /// Return true if the field is an `Option`.
+    #[inline]
+    pub fn is_option(&self) -> bool {
+        self.option.is_some()
+    }
+
+    /// Return the presence byte stored before the value of `Some` values of `Option` fields.
+    pub fn some_marker(&self) -> u8 {
+        match self.option {
+            Some(OptionOrder::NoneLast) => 0,
+            _ => 1,
+        }
+    }
+
+    /// Return the presence byte stored for `None` values of `Option` fields.
+    pub fn none_marker(&self) -> u8 {
+        1 - self.some_marker()
+    }
+
+    /// Sort `None` after all `Some` values of an `Option` field.
+    pub fn set_none_last(&mut self, span: Span) -> Result<()> {
+        if !self.is_option() {
+            return Err(Error::new(span, "The none_last attribute is only supported by Option fields."));
+        }
+        self.option = Some(OptionOrder::NoneLast);
+        Ok(())
+    }
+
+    /// Return the number of bits the field uses in the key, including the presence byte of
+    /// `Option` fields.
+    #[inline]
+    pub fn key_bits(&self) -> usize {
+        match self.option {
+            Some(_) => self.value_bits() + 8,
+            None => self.value_bits(),
+        }
+    }
+
+    /// Return the size of the value of the field in the key in bytes.
     #[inline]
     pub fn size (&self) -> usize {
-        match self.size {
-            FieldSize::Signed8 => 1,
-            FieldSize::Signed16 => 2,
-            FieldSize::Signed32 => 4,
-            FieldSize::Signed64 => 8,
-            FieldSize::Signed128 => 16,
-            FieldSize::Unsigned8 => 1,
-            FieldSize::Unsigned16 => 2,
-            FieldSize::Unsigned32 => 4,
-            FieldSize::Unsigned64 => 8,
-            FieldSize::Unsigned128 => 16,
-            FieldSize::Array(size) => size,
+        self.width
+    }
+
+    /// Return the size of the field type in bytes.
+    #[inline]
+    pub fn type_size(&self) -> usize {
+        self.size.size()
+    }
+
+    /// Return true if the integer field is stored in fewer bytes than the size of its type.
+    #[inline]
+    pub fn is_narrow(&self) -> bool {
+        self.width < self.size.size() && !self.size.is_char()
+    }
+
+    /// Return the number of bytes that each character of `char` fields is stored in.
+    #[inline]
+    pub fn char_width(&self) -> usize {
+        self.width / self.size.char_count()
+    }
+
+    /// Return true if the integer field is stored in fewer bits than its type.
+    #[inline]
+    pub fn is_bit_field(&self) -> bool {
+        self.bits.is_some()
+    }
+
+    /// Return the number of bits of the value that are stored in the key.
+    #[inline]
+    pub fn value_bits(&self) -> usize {
+        self.bits.unwrap_or(self.width * 8)
+    }
+
+    /// Return true if the range of values that can be stored in the key is smaller than the range
+    /// of the type.
+    #[inline]
+    pub fn is_reduced(&self) -> bool {
+        self.value_bits() < self.type_size() * 8 && !self.size.is_char()
+    }
+
+    /// Set the number of bytes that the integer field is stored in, or the size of the encoded
+    /// value of a codec field.
+    pub fn set_width(&mut self, width: usize, span: Span) -> Result<()> {
+        if self.codec.is_some() {
+            if 0 == width {
+                return Err(Error::new(span,
+                    "The bytes attribute for a codec field must be more than 0."));
+            }
+            self.size = FieldSize::Codec(width);
+            self.width = width;
+            return Ok(());
+        }
+        if self.size.is_char() {
+            if !(3..=4).contains(&width) {
+                return Err(Error::new(span,
+                    "The bytes attribute for char fields must be 3 or 4 bytes per character."));
+            }
+            self.width = width * self.size.char_count();
+            return Ok(());
+        }
+        if !self.size.is_integer() && !self.size.is_time() {
+            return Err(Error::new(span,
+                "The bytes attribute is only supported by integer, time, char, and codec fields."));
+        }
+        if self.is_bit_field() {
+            return Err(Error::new(span, "The bytes and bits attributes can't both be used."));
         }
+        if 0 == width || self.size.size() < width {
+            return Err(Error::new(span, format!(
+                "The bytes attribute for a {} field must be in the range 1..={}.", self.string,
+                self.size.size())));
+        }
+        self.width = width;
+        Ok(())
+    }
+
+    /// Set the number of bits that the integer field is stored in.
+    pub fn set_bits(&mut self, bits: usize, span: Span) -> Result<()> {
+        if !self.size.is_integer() {
+            return Err(Error::new(span, "The bits attribute is only supported by integer fields."));
+        }
+        if self.is_narrow() {
+            return Err(Error::new(span, "The bytes and bits attributes can't both be used."));
+        }
+        if self.is_option() {
+            return Err(Error::new(span, "The bits attribute isn't supported by Option fields."));
+        }
+        let max_bits = std::cmp::min(self.size.size() * 8, 64);
+        if 0 == bits || max_bits < bits {
+            return Err(Error::new(span, format!(
+                "The bits attribute for a {} field must be in the range 1..={}.", self.string,
+                max_bits)));
+        }
+        self.bits = Some(bits);
+        Ok(())
+    }
+
+    /// Return an unsigned integer literal with the type suffix of this field.
+    fn uint_lit(&self, value: u128) -> TokenStream {
+        let lit = Literal::from_str(&format!("{:#X}_{}", value, self.string)).unwrap();
+        quote! { #lit }
+    }
+
+    /// Return a signed integer literal with the type suffix of this field.
+    fn int_lit(&self, value: i128) -> TokenStream {
+        let lit = Literal::from_str(&format!("{}_{}", value.unsigned_abs(), self.string)).unwrap();
+        if value < 0 { quote! { -#lit } } else { quote! { #lit } }
     }
 }
 
@@ -1,5 +1,6 @@
-use rand::{Rng, rngs::ThreadRng};
-use crate::field::FieldSize;
+use quote::ToTokens;
+use rand::{Rng, RngCore};
+use crate::field::{Collation, FieldSize, FieldType, TimeUnit, r#type::NonZero};
 use std::{
     borrow::Borrow,
     fmt::{Display, Formatter, Result},
@@ -9,19 +10,127 @@ use std::{
 pub struct FieldValue {
     size: FieldSize,
     value: Vec<u8>,
+    /// The value is wrapped in `Some` for `Option` fields.
+    optional: bool,
+    /// The unit of the count in the value of time fields.
+    time_unit: TimeUnit,
+    /// The codec of codec fields in the documentation examples. The value of a codec field is
+    /// either the minimum (0) or the maximum (1) of the codec, because the values of the type
+    /// are unknown.
+    codec: Option<String>,
+    /// The supported type of newtype fields, which the value is converted from.
+    via: Option<String>,
+    /// The non-zero type of non-zero integer fields, which the value is converted to.
+    nonzero: Option<NonZero>,
 }
 
 impl FieldValue {
-    pub fn random(size: FieldSize) -> Self {
-        let mut rng: ThreadRng = rand::thread_rng();
-        let len = size.size();
+    pub fn random(field_type: &FieldType) -> Self {
+        Self::random_from(field_type, &mut rand::thread_rng())
+    }
+
+    /// Return the value of a field in a reference key of the layout snapshot test, which is
+    /// the same every time the macro is expanded with the same generator.
+    pub fn reference(field_type: &FieldType, rng: &mut ReferenceRng) -> Self {
+        let mut value = Self::random_from(field_type, rng);
+        if value.codec.is_some() {
+            // The test is compiled in the crate of the key, so the path isn't changed.
+            value.codec = field_type.codec.as_ref().map(|codec| codec.as_trait().to_string());
+        }
+        value
+    }
+
+    fn random_from<R: Rng>(field_type: &FieldType, rng: &mut R) -> Self {
+        let mut value = Self::random_value(field_type, rng);
+        if field_type.is_nonzero_required() {
+            while value.is_zero() {
+                value = Self::random_value(field_type, rng);
+            }
+        }
+        else if field_type.is_nonzero_signed_option() {
+            // The minimum value is reserved for `None`.
+            while value.is_minimum(field_type) {
+                value = Self::random_value(field_type, rng);
+            }
+        }
+        value
+    }
+
+    /// Return true if the value of a signed integer field is the minimum value of the field.
+    fn is_minimum(&self, field_type: &FieldType) -> bool {
+        let bits = self.value.len() * 8;
+        let raw = self.value.iter().fold(0_u128, |raw, byte| (raw << 8) | *byte as u128);
+        let value = (((raw ^ (1 << (bits - 1))) << (128 - bits)) as i128) >> (128 - bits);
+        value == i128::MIN >> (128 - field_type.value_bits())
+    }
+
+    /// Return true if the value of an integer field is zero.
+    fn is_zero(&self) -> bool {
+        let mut bytes = self.value.iter();
+        let first = bytes.next().copied().unwrap_or(0);
+        let sign = if self.size.is_signed() { 0x80 } else { 0 };
+        first == sign && bytes.all(|byte| 0 == *byte)
+    }
+
+    fn random_value<R: Rng>(field_type: &FieldType, rng: &mut R) -> Self {
+        let size = field_type.size;
+        let len = if size.is_time() { field_type.size() } else { size.size() };
         let mut value = Vec::with_capacity(len);
         for _ in 0..len {
             value.push(rng.gen());
         }
+        if field_type.codec.is_some() {
+            return Self {
+                size,
+                value: vec![rng.gen::<bool>() as u8],
+                optional: field_type.is_option(),
+                time_unit: field_type.time_unit,
+                codec: field_type.doc_codec(),
+                via: None,
+                nonzero: None,
+            };
+        }
+        if size.is_char() {
+            value.clear();
+            for _ in 0..size.char_count() {
+                value.extend_from_slice(&(rng.gen::<char>() as u32).to_be_bytes());
+            }
+        }
+        else if Collation::Binary != field_type.collation {
+            // Collated values are already normalized, so they are returned unchanged.
+            for byte in value.iter_mut() {
+                *byte = rng.gen_range(b'a'..=b'z');
+            }
+        }
+        else if size.is_time() {
+            // Keep the times small enough to be represented by a `SystemTime` on any platform.
+            let bits = std::cmp::min(len * 8, 32);
+            let random: u128 = rng.gen::<u128>() & ((1 << bits) - 1);
+            value.copy_from_slice(&random.to_be_bytes()[16 - len..]);
+        }
+        else if field_type.is_reduced() {
+            // Only the low bits are random for integers stored in fewer bits than their type.
+            let bits = field_type.value_bits();
+            let random: u128 = rng.gen::<u128>() & ((1 << bits) - 1);
+            let random = if size.is_signed() {
+                // Signed values are stored with the sign bit of the type toggled.
+                let signed = (((random ^ (1 << (bits - 1))) << (128 - bits)) as i128) >> (128 - bits);
+                (signed as u128) ^ (1 << (len * 8 - 1))
+            }
+            else {
+                random
+            };
+            value.copy_from_slice(&random.to_be_bytes()[16 - len..]);
+        }
         Self {
             size,
             value,
+            optional: field_type.is_option(),
+            time_unit: field_type.time_unit,
+            codec: None,
+            via: field_type.newtype.as_ref().map(|_| field_type.value_type.to_token_stream()
+                .to_string()),
+            nonzero: field_type.nonzero.clone(),
         }
     }
 
@@ -30,6 +139,43 @@ impl FieldValue {
     }
 }
 
+/// A SplitMix64 generator for the values of the reference keys of the layout snapshot test,
+/// which must not change between builds like the values of the documentation examples.
+#[derive(Clone, Debug)]
+pub struct ReferenceRng(u64);
+
+impl ReferenceRng {
+    pub fn new(seed: u64) -> Self {
+        Self(seed)
+    }
+}
+
+impl RngCore for ReferenceRng {
+    fn next_u32(&mut self) -> u32 {
+        (self.next_u64() >> 32) as u32
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        for chunk in dest.chunks_mut(8) {
+            let bytes = self.next_u64().to_le_bytes();
+            chunk.copy_from_slice(&bytes[..chunk.len()]);
+        }
+    }
+
+    fn try_fill_bytes(&mut self, dest: &mut [u8]) -> std::result::Result<(), rand::Error> {
+        self.fill_bytes(dest);
+        Ok(())
+    }
+}
+
 macro_rules! impl_to_from_signed {
     ($(($ix:ident, $size:ident, $xor_mask:path),)+) => {
         $(
@@ -38,6 +184,11 @@ macro_rules! impl_to_from_signed {
                 Self {
                     size: FieldSize::$size,
                     value: (value ^ $xor_mask).to_be_bytes().to_vec(),
+                    optional: false,
+                    time_unit: TimeUnit::default(),
+                    codec: None,
+                    via: None,
+                    nonzero: None,
                 }
             }
         }
@@ -70,6 +221,11 @@ macro_rules! impl_to_from_unsigned {
                 Self {
                     size: FieldSize::$size,
                     value: value.to_be_bytes().to_vec(),
+                    optional: false,
+                    time_unit: TimeUnit::default(),
+                    codec: None,
+                    via: None,
+                    nonzero: None,
                 }
             }
         }
@@ -102,6 +258,11 @@ macro_rules! impl_to_from_array {
                 Self {
                     size: FieldSize::Array($size),
                     value: value.to_vec(),
+                    optional: false,
+                    time_unit: TimeUnit::default(),
+                    codec: None,
+                    via: None,
+                    nonzero: None,
                 }
             }
         }
@@ -175,26 +336,115 @@ macro_rules! from_be_bytes {
 
 impl Display for FieldValue {
     fn fmt(&self, f: &mut Formatter<'_>) -> Result {
-        match self.size {
-            FieldSize::Signed8 => write!(f, "{}_i8", self.value[0] as i8),
-            FieldSize::Signed16 => write!(f, "{}_i16", from_be_bytes!(self.value, i16)),
-            FieldSize::Signed32 => write!(f, "{}_i32", from_be_bytes!(self.value, i32)),
-            FieldSize::Signed64 => write!(f, "{}_i64", from_be_bytes!(self.value, i64)),
-            FieldSize::Signed128 => write!(f, "{}_i128", from_be_bytes!(self.value, i128)),
-            FieldSize::Unsigned8 => write!(f, "{:#04X}_u8", self.value[0]),
-            FieldSize::Unsigned16 => write!(f, "{:#06X}_u16", from_be_bytes!(self.value, u16)),
-            FieldSize::Unsigned32 => write!(f, "{:#010X}_u32", from_be_bytes!(self.value, u32)),
-            FieldSize::Unsigned64 => write!(f, "{:#018X}_u64", from_be_bytes!(self.value, u64)),
-            FieldSize::Unsigned128 => write!(f, "{:#034X}_u128", from_be_bytes!(self.value, u128)),
+        if self.optional {
+            f.write_str("Some(")?;
+        }
+        match (&self.via, &self.nonzero) {
+            (Some(via), _) => write!(f, "::std::convert::From::<{}>::from({})", via,
+                FieldValueInner(self))?,
+            (None, Some(nonzero)) => {
+                write!(f, "{}::new({})", nonzero.path(), FieldValueInner(self))?;
+                if !nonzero.optional {
+                    f.write_str(".unwrap()")?;
+                }
+            }
+            (None, None) => FieldValueInner(self).fmt(f)?,
+        }
+        if self.optional {
+            f.write_str(")")?;
+        }
+        Ok(())
+    }
+}
+
+/// A reference to a `FieldValue` with a `Display` implementation for the value without the `Some`
+/// of `Option` fields.
+#[derive(Copy, Clone, Debug)]
+struct FieldValueInner<'v>(&'v FieldValue);
+
+impl<'v> Display for FieldValueInner<'v> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        let value = &self.0.value;
+        match self.0.size {
+            FieldSize::Signed8 => write!(f, "{}_i8", (value[0] ^ 0x80) as i8),
+            FieldSize::Signed16 => write!(f, "{}_i16", from_be_bytes!(value, i16)),
+            FieldSize::Signed32 => write!(f, "{}_i32", from_be_bytes!(value, i32)),
+            FieldSize::Signed64 => write!(f, "{}_i64", from_be_bytes!(value, i64)),
+            FieldSize::Signed128 => write!(f, "{}_i128", from_be_bytes!(value, i128)),
+            FieldSize::Unsigned8 => write!(f, "{:#04X}_u8", value[0]),
+            FieldSize::Unsigned16 => write!(f, "{:#06X}_u16", from_be_bytes!(value, u16)),
+            FieldSize::Unsigned32 => write!(f, "{:#010X}_u32", from_be_bytes!(value, u32)),
+            FieldSize::Unsigned64 => write!(f, "{:#018X}_u64", from_be_bytes!(value, u64)),
+            FieldSize::Unsigned128 => write!(f, "{:#034X}_u128", from_be_bytes!(value, u128)),
             FieldSize::Array(size) => {
                 f.write_str("[")?;
-                f.write_fmt(format_args!("{:#04X}_u8", self.value[0]))?;
-                for i in 1..size {
-                    f.write_fmt(format_args!(", {:#04X}", self.value[i]))?;
+                f.write_fmt(format_args!("{:#04X}_u8", value[0]))?;
+                for byte in value[1..size].iter() {
+                    f.write_fmt(format_args!(", {:#04X}", byte))?;
+                }
+                f.write_str("]")
+            }
+            FieldSize::Ipv4 => write!(f, "::std::net::Ipv4Addr::new({}, {}, {}, {})", value[0],
+                value[1], value[2], value[3]),
+            FieldSize::Ipv6 => FieldValueIpv6(&value[..16]).fmt(f),
+            FieldSize::SocketV4 => write!(f,
+                "::std::net::SocketAddrV4::new(::std::net::Ipv4Addr::new({}, {}, {}, {}), {})",
+                value[0], value[1], value[2], value[3], from_be_bytes!(value[4..6], u16)),
+            FieldSize::SocketV6 => write!(f, "::std::net::SocketAddrV6::new({}, {}, {}, {})",
+                FieldValueIpv6(&value[..16]), from_be_bytes!(value[16..18], u16),
+                from_be_bytes!(value[18..22], u32), from_be_bytes!(value[22..26], u32)),
+            FieldSize::Uuid => write!(f, "::uuid::Uuid::from_u128({:#034X})",
+                from_be_bytes!(value, u128)),
+            FieldSize::Duration | FieldSize::SystemTime => {
+                let count = value.iter().fold(0_u64, |count, byte| (count << 8) | *byte as u64);
+                if FieldSize::SystemTime == self.0.size {
+                    f.write_str("::std::time::UNIX_EPOCH + ")?;
+                }
+                write!(f, "::std::time::Duration::from_{}({})", self.0.time_unit.name(), count)
+            }
+            FieldSize::Char => FieldValueChar(value).fmt(f),
+            FieldSize::CharArray(_) => {
+                f.write_str("[")?;
+                for (i, c) in value.chunks(4).enumerate() {
+                    if 0 < i {
+                        f.write_str(", ")?;
+                    }
+                    FieldValueChar(c).fmt(f)?;
                 }
                 f.write_str("]")
             }
+            FieldSize::Codec(_) => {
+                let codec = self.0.codec.as_deref().unwrap_or_default();
+                let bytes = if 0 == value[0] { "MIN" } else { "MAX" };
+                write!(f, "{0}::decode({0}::{1})", codec, bytes)
+            }
+        }
+    }
+}
+
+/// The big-endian scalar value of a `char` with a `Display` implementation that creates the
+/// character.
+struct FieldValueChar<'v>(&'v [u8]);
+
+impl<'v> Display for FieldValueChar<'v> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        write!(f, "'\\u{{{:X}}}'", from_be_bytes!(self.0, u32))
+    }
+}
+
+/// The octets of an IPv6 address with a `Display` implementation that creates the address.
+struct FieldValueIpv6<'v>(&'v [u8]);
+
+impl<'v> Display for FieldValueIpv6<'v> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        f.write_str("::std::net::Ipv6Addr::new(")?;
+        for (i, segment) in self.0.chunks(2).enumerate() {
+            if 0 < i {
+                f.write_str(", ")?;
+            }
+            write!(f, "{:#06X}", from_be_bytes!(segment, u16))?;
         }
+        f.write_str(")")
     }
 }
 
@@ -206,17 +456,23 @@ pub struct FieldValueAssertEq<'v>(&'v FieldValue);
 impl<'v> Display for FieldValueAssertEq<'v> {
     fn fmt(&self, f: &mut Formatter<'_>) -> Result {
         match self.0.size {
-            FieldSize::Array(_size) => {
+            // The getters of `Option` and newtype array fields return the value.
+            FieldSize::Array(_size) if !self.0.optional && self.0.via.is_none() => {
                 f.write_str("&")?;
                 self.0.fmt(f)
             }
-            _ => self.0.fmt(f),
+            // The getters of non-zero integer fields return an `Option`.
+            _ => match &self.0.nonzero {
+                Some(nonzero) => write!(f, "{}::new({})", nonzero.path(), FieldValueInner(self.0)),
+                None => self.0.fmt(f),
+            }
         }
     }
 }
 
 /// A reference to a `FieldValue` with a `Display` implementation for outputing just a comma
 /// separated list of bytes.
+#[allow(dead_code)]
 #[derive(Copy, Clone, Debug)]
 pub struct FieldValueJustBytes<'v>(&'v FieldValue);
 
@@ -9,6 +9,7 @@ use syn::{
     DeriveInput,
     Error,
     Meta,
+    Path,
     Result,
     spanned::Spanned,
     Visibility,
@@ -23,7 +24,7 @@ use crate::{
 
 #[derive(Debug)]
 pub enum DBKey {
-    Struct(DBKeyStruct),
+    Struct(Box<DBKeyStruct>),
     Error(Error),
 }
 
@@ -31,7 +32,7 @@ impl DBKey {
     /// Read in the token stream to process the attribute macro.
     pub fn attribute(attr: TokenStream, input: DeriveInput) -> Self {
         match DBKeyStruct::try_attribute(attr, input) {
-            Ok(db_key_struct) => Self::Struct(db_key_struct),
+            Ok(db_key_struct) => Self::Struct(Box::new(db_key_struct)),
             Err(err) => Self::Error(err),
         }
     }
@@ -39,7 +40,7 @@ impl DBKey {
     /// Read in the token stream to process the derive macro.
     pub fn derive(input: DeriveInput) -> Self {
         match DBKeyStruct::try_derive(input) {
-            Ok(db_key_struct) => Self::Struct(db_key_struct),
+            Ok(db_key_struct) => Self::Struct(Box::new(db_key_struct)),
             Err(err) => Self::Error(err),
         }
     }
@@ -66,6 +67,10 @@ enum ParseAttrParam {
     Path,
     RawDebug,
     AltName,
+    Reserve,
+    Version,
+    Previous,
+    LayoutSnapshot,
 }
 
 /// Indicates the state of what we are expecting when walking through the token stream reading the
@@ -79,9 +84,10 @@ enum ParseAttrExpect {
 }
 
 /// The output format of the raw array for the Debug trait.
-#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
 pub enum RawDebugFormat {
     /// A compact display of the raw key data that also demarcates the parts of the key.
+    #[default]
     Compact,
     /// Display the raw key in Debug that standard way you display any array.
     Standard,
@@ -97,12 +103,6 @@ pub enum RawDebugFormat {
     PrettyUpperHex,
 }
 
-impl Default for RawDebugFormat {
-    fn default() -> Self {
-        RawDebugFormat::Compact
-    }
-}
-
 impl TryFrom<&str> for RawDebugFormat {
     type Error = ();
 
@@ -119,6 +119,20 @@ impl TryFrom<&str> for RawDebugFormat {
     }
 }
 
+impl RawDebugFormat {
+    /// Return the name of the format in the `raw_debug` option.
+    pub fn name(&self) -> &'static str {
+        match self {
+            RawDebugFormat::Compact => "compact",
+            RawDebugFormat::Standard => "std",
+            RawDebugFormat::LowerHex => "lower_hex",
+            RawDebugFormat::UpperHex => "upper_hex",
+            RawDebugFormat::PrettyLowerHex => "pretty_lower_hex",
+            RawDebugFormat::PrettyUpperHex => "pretty_upper_hex",
+        }
+    }
+}
+
 /// Stores the status of the parameters read from the attribute macro.
 #[derive(Clone, Debug, Default)]
 pub struct DBKeyAttributes {
@@ -131,6 +145,26 @@ pub struct DBKeyAttributes {
     derive_copy: Option<bool>,
     pub(crate) min_key: bool,
     pub(crate) max_key: bool,
+    /// The size of the padding field added after the other fields.
+    reserve: Option<usize>,
+    /// The padding fields are checked by `validate()`, and `TryFrom<&[u8]>` replaces
+    /// `From<&[u8]>`.
+    pub(crate) strict: bool,
+    /// The version stored in the first byte of the key.
+    version: Option<u8>,
+    /// The key of the previous version, which is converted to this key.
+    pub(crate) previous: Option<Path>,
+    /// The path of the snapshot file of the layout test from the root of the crate.
+    layout_snapshot: Option<String>,
+    /// Generate the `SCHEMA` constant and the `schema_json()` function.
+    schema_json: bool,
+    /// Generate the `proptest::arbitrary::Arbitrary` implementations of the key and its
+    /// arguments.
+    proptest: bool,
+    /// Generate the `arbitrary::Arbitrary` implementations of the key and its arguments.
+    arbitrary: bool,
+    /// Generate the `<Key>Pattern` structure to match keys and scan their ranges.
+    pattern: bool,
 }
 
 impl TryFrom<TokenStream> for DBKeyAttributes {
@@ -146,6 +180,15 @@ impl TryFrom<TokenStream> for DBKeyAttributes {
         let mut max_key = true;
         let mut debug = true;
         let mut raw_fmt = RawDebugFormat::default();
+        let mut reserve = None;
+        let mut strict = false;
+        let mut version = None;
+        let mut previous = None;
+        let mut layout_snapshot = None;
+        let mut schema_json = false;
+        let mut proptest = false;
+        let mut arbitrary = false;
+        let mut pattern = false;
         let mut waiting_for = ParseAttrExpect::Param;
         for thing in attr {
             match thing {
@@ -191,8 +234,41 @@ impl TryFrom<TokenStream> for DBKeyAttributes {
                                 "path" => {
                                     waiting_for = ParseAttrExpect::Equals(ParseAttrParam::Path);
                                 }
+                                "reserve" => {
+                                    waiting_for = ParseAttrExpect::Equals(ParseAttrParam::Reserve);
+                                }
+                                "version" => {
+                                    waiting_for = ParseAttrExpect::Equals(ParseAttrParam::Version);
+                                }
+                                "previous" => {
+                                    waiting_for = ParseAttrExpect::Equals(ParseAttrParam::Previous);
+                                }
+                                "layout_snapshot" => {
+                                    waiting_for =
+                                        ParseAttrExpect::Equals(ParseAttrParam::LayoutSnapshot);
+                                }
+                                "schema_json" => {
+                                    schema_json = true;
+                                    waiting_for = ParseAttrExpect::Comma;
+                                }
+                                "proptest" => {
+                                    proptest = true;
+                                    waiting_for = ParseAttrExpect::Comma;
+                                }
+                                "arbitrary" => {
+                                    arbitrary = true;
+                                    waiting_for = ParseAttrExpect::Comma;
+                                }
+                                "pattern" => {
+                                    pattern = true;
+                                    waiting_for = ParseAttrExpect::Comma;
+                                }
+                                "strict" => {
+                                    strict = true;
+                                    waiting_for = ParseAttrExpect::Comma;
+                                }
                                 _ => {
-                                    return Err(Error::new(span.into(), "Unknown parameter"));
+                                    return Err(Error::new(span, "Unknown parameter"));
                                 }
                             }
                         }
@@ -207,7 +283,7 @@ impl TryFrom<TokenStream> for DBKeyAttributes {
                         ParseAttrExpect::IdentOrLit(ParseAttrParam::RawDebug) => {
                             let fmt_str = id.to_string();
                             raw_fmt = RawDebugFormat::try_from(fmt_str.as_str()).map_err(|_| {
-                                Error::new(id.span().into(), "Unknown raw_debug format")
+                                Error::new(id.span(), "Unknown raw_debug format")
                             })?;
                             waiting_for = ParseAttrExpect::Comma;
                         }
@@ -215,6 +291,22 @@ impl TryFrom<TokenStream> for DBKeyAttributes {
                             alt_name = Some(id.clone());
                             waiting_for = ParseAttrExpect::Comma;
                         }
+                        ParseAttrExpect::IdentOrLit(ParseAttrParam::Reserve) => {
+                            return Err(Error::new(id.span(),
+                                "The reserve parameter expects the number of bytes."));
+                        }
+                        ParseAttrExpect::IdentOrLit(ParseAttrParam::Version) => {
+                            return Err(Error::new(id.span(),
+                                "The version parameter expects a number from 0 to 255."));
+                        }
+                        ParseAttrExpect::IdentOrLit(ParseAttrParam::Previous) => {
+                            previous = Some(Path::from(id.clone()));
+                            waiting_for = ParseAttrExpect::Comma;
+                        }
+                        ParseAttrExpect::IdentOrLit(ParseAttrParam::LayoutSnapshot) => {
+                            return Err(Error::new(id.span(),
+                                "The layout_snapshot parameter expects the path of a file."));
+                        }
                         _ => {}
                     }
                 }
@@ -232,7 +324,7 @@ impl TryFrom<TokenStream> for DBKeyAttributes {
                             let fmt_str = lit.to_string();
                             raw_fmt = RawDebugFormat::try_from(fmt_str.trim_matches('"'))
                                 .map_err(|_| {
-                                    Error::new(lit.span().into(), "Unknown raw_debug format")
+                                    Error::new(lit.span(), "Unknown raw_debug format")
                             })?;
                             waiting_for = ParseAttrExpect::Comma;
                         }
@@ -241,8 +333,51 @@ impl TryFrom<TokenStream> for DBKeyAttributes {
                                 lit.span()));
                             waiting_for = ParseAttrExpect::Comma;
                         }
+                        ParseAttrExpect::IdentOrLit(ParseAttrParam::Reserve) => {
+                            match lit.to_string().trim_matches('"').parse::<usize>() {
+                                Ok(size) if 0 < size => {
+                                    reserve = Some(size);
+                                }
+                                _ => {
+                                    return Err(Error::new(lit.span(),
+                                        "The reserve parameter expects the number of bytes."));
+                                }
+                            }
+                            waiting_for = ParseAttrExpect::Comma;
+                        }
+                        ParseAttrExpect::IdentOrLit(ParseAttrParam::Version) => {
+                            match lit.to_string().trim_matches('"').parse::<u8>() {
+                                Ok(number) => {
+                                    version = Some(number);
+                                }
+                                _ => {
+                                    return Err(Error::new(lit.span(),
+                                        "The version parameter expects a number from 0 to 255."));
+                                }
+                            }
+                            waiting_for = ParseAttrExpect::Comma;
+                        }
+                        ParseAttrExpect::IdentOrLit(ParseAttrParam::Previous) => {
+                            let path = syn::parse_str::<Path>(lit.to_string().trim_matches('"'))
+                                .map_err(|_| Error::new(lit.span(),
+                                    "The previous parameter expects the path of a key."))?;
+                            previous = Some(path);
+                            waiting_for = ParseAttrExpect::Comma;
+                        }
+                        ParseAttrExpect::IdentOrLit(ParseAttrParam::LayoutSnapshot) => {
+                            match syn::parse_str::<syn::LitStr>(&lit.to_string()) {
+                                Ok(path) if !path.value().is_empty() => {
+                                    layout_snapshot = Some(path.value());
+                                }
+                                _ => {
+                                    return Err(Error::new(lit.span(),
+                                        "The layout_snapshot parameter expects the path of a file."));
+                                }
+                            }
+                            waiting_for = ParseAttrExpect::Comma;
+                        }
                         _ => {
-                            return Err(Error::new(lit.span().into(),
+                            return Err(Error::new(lit.span(),
                                 "Unexpected literal encountered"));
                         }
                     }
@@ -254,7 +389,7 @@ impl TryFrom<TokenStream> for DBKeyAttributes {
                                 waiting_for = ParseAttrExpect::IdentOrLit(next);
                             }
                             else {
-                                return Err(Error::new(punct.span().into(),
+                                return Err(Error::new(punct.span(),
                                     "Unexpected punctuation (not '=')"));
                             }
                         }
@@ -263,12 +398,12 @@ impl TryFrom<TokenStream> for DBKeyAttributes {
                                 waiting_for = ParseAttrExpect::Param;
                             }
                             else {
-                                return Err(Error::new(punct.span().into(),
+                                return Err(Error::new(punct.span(),
                                     "Unexpected punctuation (not ',')"));
                             }
                         }
                         _ => {
-                            return Err(Error::new(punct.span().into(),
+                            return Err(Error::new(punct.span(),
                                 "Unexpected punctuation"));
                         }
                     }
@@ -280,6 +415,10 @@ impl TryFrom<TokenStream> for DBKeyAttributes {
                 // If the CARGO_PKG_NAME has '-', convert them to '_'.
                 .replace('-', "_"));
         let use_path = use_path.unwrap_or_default();
+        if let (Some(previous), None) = (&previous, version) {
+            return Err(Error::new(previous.span(),
+                "The previous parameter requires the version parameter."));
+        }
         Ok(DBKeyAttributes {
             crate_name,
             use_path,
@@ -290,6 +429,15 @@ impl TryFrom<TokenStream> for DBKeyAttributes {
             derive_copy,
             min_key,
             max_key,
+            reserve,
+            strict,
+            version,
+            previous,
+            layout_snapshot,
+            schema_json,
+            proptest,
+            arbitrary,
+            pattern,
         })
     }
 }
@@ -317,11 +465,9 @@ impl DBKeyAttributes {
                 return Err(Error::new(attr.path().get_ident().span(), "Unexpected attribute"));
             }
         }
-        if result.is_none() {
-            DBKeyAttributes::try_from(TokenStream::default())
-        }
-        else {
-            Ok(result.unwrap())
+        match result {
+            Some(result) => Ok(result),
+            None => DBKeyAttributes::try_from(TokenStream::default()),
         }
     }
 }
@@ -356,7 +502,15 @@ impl DBKeyStruct {
             None => Ident::new(&format!("{}Args", ident), ident.span()),
             Some(alt_name) => alt_name.clone(),
         };
-        let fields = DBKeyFields::try_from(&input)?;
+        let mut fields = DBKeyFields::try_from(&input)?;
+        if let Some(reserve) = attr.reserve {
+            fields.add_reserve(reserve)?;
+        }
+        if let Some(version) = attr.version {
+            fields.add_version(version)?;
+        }
+        fields.set_crate_name(&attr.crate_name);
+        fields.set_define_args(true);
         let mut struct_attrs = input.attrs.clone();
         if struct_attrs.is_empty() {
             let doc = format!("The {} structure", ident);
@@ -390,7 +544,14 @@ impl DBKeyStruct {
             None => Ident::new(&format!("{}Key", args_ident), args_ident.span()),
             Some(alt_name) => alt_name.clone(),
         };
-        let fields = DBKeyFields::try_from(&input)?;
+        let mut fields = DBKeyFields::try_from(&input)?;
+        if let Some(reserve) = attr.reserve {
+            fields.add_reserve(reserve)?;
+        }
+        if let Some(version) = attr.version {
+            fields.add_version(version)?;
+        }
+        fields.set_crate_name(&attr.crate_name);
         if struct_attrs.is_empty() {
             let doc = format!("The {} structure", ident);
             add_doc(&mut struct_attrs, &doc);
@@ -408,6 +569,12 @@ impl DBKeyStruct {
 
     /// Create the use line for documentation.
     pub fn example_start(&self) -> String {
+        self.example_start_with(&[&self.ident, &self.args_ident])
+    }
+
+    /// Create the use line for documentation that imports the structures `idents`.
+    fn example_start_with(&self, idents: &[&Ident]) -> String {
+        let idents = idents.iter().map(|ident| ident.to_string()).collect::<Vec<_>>().join(", ");
         let (start_line, crate_name) = match self.vis {
             Visibility::Public(_) => {
                 ("```rust", self.attr.crate_name.clone())
@@ -417,12 +584,11 @@ impl DBKeyStruct {
             }
         };
         if self.attr.use_path.is_empty() {
-            format!("{0}\nuse {1}::{{{2}, {3}}};", start_line, crate_name, self.ident,
-                self.args_ident)
+            format!("{0}\nuse {1}::{{{2}}};", start_line, crate_name, idents)
         }
         else {
-            format!("{0}\nuse {1}::{2}::{{{3}, {4}}};", start_line, crate_name,
-                self.attr.use_path, self.ident, self.args_ident)
+            format!("{0}\nuse {1}::{2}::{{{3}}};", start_line, crate_name, self.attr.use_path,
+                idents)
         }
     }
 
@@ -452,6 +618,11 @@ impl DBKeyStruct {
     fn try_generate(&self) -> Result<TokenStream> {
         let example_start = self.example_start();
         let consts = self.fields.consts();
+        let key_length = self.fields.total_size();
+        let schema_description = self.fields.schema_description();
+        let schema_hash = format!("0x{:016x}", schema_hash(&schema_description))
+            .parse::<TokenStream>().unwrap();
+        let schema_doc = format!(" ```text\n{}\n ```", schema_description);
         let sizes = self.fields.sizes();
         let struct_fields = self.fields.struct_fields();
         let params = self.fields.params();
@@ -480,6 +651,119 @@ impl DBKeyStruct {
         let args_doc_header = format!("Argument structure used to create [{}] structures.", ident);
         let from_doc_header = format!("Create a `{}` from a [{}].", ident, args_ident);
         let derive_copy = self.derive_copy();
+        let args_derive_copy = self.args_derive_copy();
+        let checks = self.fields.checks();
+        let computes = self.fields.computes();
+        let validates = self.fields.validates();
+        // Keys with computed fields store the computed values after the other fields.
+        let (new_key, default_key, update_computed) = if self.fields.has_computed() {
+            (quote! {
+                let mut key = Self(buf);
+                key.update_computed();
+                key
+            },
+            quote! {
+                let mut key = Self::DEFAULT_KEY;
+                key.update_computed();
+                key
+            },
+            quote! {
+                /// Store the values of the computed fields, which are computed from the other
+                /// fields of the key.
+                fn update_computed(&mut self) {
+                    #(#computes)*
+                }
+            })
+        }
+        else {
+            (quote! { Self(buf) }, quote! { Self::DEFAULT_KEY }, quote! {})
+        };
+        // Keys with the strict attribute check the padding fields before the computed fields.
+        let padding_checks = if self.attr.strict {
+            self.fields.padding_checks()
+        }
+        else {
+            Vec::new()
+        };
+        let validate_function = if self.fields.has_computed() || self.attr.strict {
+            let validate_example = format!(r#"
+let key = {0}::default();
+
+assert!(key.validate().is_ok());"#,
+                ident);
+            let validate_computed = if self.fields.has_computed() {
+                quote! {
+                    let mut expected = Self(self.0);
+                    expected.update_computed();
+                    #(#validates)*
+                }
+            }
+            else {
+                quote! {}
+            };
+            quote! {
+                /// Check that the computed fields of the key match the other fields, and that the
+                /// padding fields of a key with the `strict` attribute are zero, which is only
+                /// needed for keys created from raw bytes (e.g. read from a database).
+                ///
+                /// # Examples
+                ///
+                #[doc = #example_start]
+                #[doc = #validate_example]
+                /// ```
+                pub fn validate(&self) -> ::std::result::Result<(), ::db_key::ValidationError> {
+                    #(#padding_checks)*
+                    #validate_computed
+                    Ok(())
+                }
+            }
+        }
+        else {
+            quote! {}
+        };
+        // Keys with the strict attribute are validated when they are converted from a slice.
+        let from_slice = if self.attr.strict {
+            let try_from_doc = format!(concat!("Create a `{}` from the bytes of a slice, or ",
+                "return an error if the slice doesn't have `KEY_LENGTH` bytes, or the error of ",
+                "`validate()`."), ident);
+            quote! {
+                #[doc = #try_from_doc]
+                impl TryFrom<&[u8]> for #ident {
+                    type Error = ::db_key::DecodeError;
+
+                    fn try_from(slice: &[u8]) -> ::std::result::Result<Self, Self::Error> {
+                        if #ident::KEY_LENGTH != slice.len() {
+                            return Err(::db_key::DecodeError::Length {
+                                expected: #ident::KEY_LENGTH,
+                                found: slice.len(),
+                            });
+                        }
+                        let mut output = #ident([0_u8; #ident::KEY_LENGTH]);
+                        output.0.copy_from_slice(slice);
+                        output.validate()?;
+                        Ok(output)
+                    }
+                }
+            }
+        }
+        else {
+            quote! {
+                impl From<&[u8]> for #ident {
+                    fn from(slice: &[u8]) -> Self {
+                        let size = ::std::cmp::min(#ident::KEY_LENGTH, slice.len());
+                        let mut output = #ident::default(); // Fills with zeros
+                        output.0[..size].copy_from_slice(&slice[..size]);
+                        output
+                    }
+                }
+            }
+        };
+        // Keys with the version attribute store the version in the first byte, and can be
+        // converted from the previous version.
+        let (version_items, version_traits) = match self.attr.version {
+            Some(version) => self.version_code(version),
+            None => (quote! {}, quote! {}),
+        };
         let mut optional_new_docs = Vec::new();
         let mut optional_new_partial_docs = Vec::new();
         let mut optional_functions = Vec::new();
@@ -500,10 +784,11 @@ impl DBKeyStruct {
                 ///
                 /// assert_eq!(new_key, from_key);
                 /// ```
+                #[allow(clippy::too_many_arguments)]
                 pub fn new(#(#params)*) -> Self {
                     let mut buf = [0_u8; #ident::KEY_LENGTH];
                     #(#defines)*
-                    Self(buf)
+                    #new_key
                 }
             });
             optional_new_docs.push(quote! {
@@ -580,6 +865,13 @@ impl DBKeyStruct {
                 }
             });
         }
+        let (to_args_functions, to_args_traits) = self.to_args_code();
+        let layout_snapshot_test = self.layout_snapshot_test();
+        let schema_items = self.schema_code();
+        let proptest_traits = self.proptest_code();
+        let arbitrary_traits = self.arbitrary_code();
+        let key_with_args_trait = self.key_with_args_code();
+        let pattern_items = self.pattern_code();
         let mut optional_consts = Vec::new();
         if self.attr.min_key {
             optional_consts.push(quote!{
@@ -621,7 +913,7 @@ impl DBKeyStruct {
                 #[doc = #verify_from_partial]
                 #(#optional_new_partial_docs)*
                 /// ```
-                #derive_copy
+                #args_derive_copy
                 #[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
                 #vis struct #args_ident {
                     #(#struct_fields)*
@@ -635,6 +927,8 @@ impl DBKeyStruct {
         Ok(quote! {
             #args_definition
 
+            #(#checks)*
+
             impl Default for #args_ident {
                 fn default() -> Self {
                     Self {
@@ -661,14 +955,25 @@ impl DBKeyStruct {
 
             impl Default for #ident {
                 fn default() -> Self {
-                    Self::DEFAULT_KEY
+                    #default_key
                 }
             }
 
             impl #ident {
                 /// The size in bytes of the key data.
-                pub const KEY_LENGTH: usize = 0 #(+ #sizes)*;
-                /// The sizes of the individual fields in order of definition.
+                pub const KEY_LENGTH: usize = #key_length;
+                /// The description of the layout of the key, with one line for each field that has
+                /// the name of the field, its encoding, its offset and size in bits, and the options
+                /// that change the bytes stored:
+                ///
+                #[doc = #schema_doc]
+                pub const SCHEMA_DESCRIPTION: &'static str = #schema_description;
+                /// The 64-bit FNV-1a hash of `SCHEMA_DESCRIPTION`, which changes when the layout of
+                /// the key changes (e.g. to check that stored keys were written with the same
+                /// layout).
+                pub const SCHEMA_HASH: u64 = #schema_hash;
+                /// The sizes in bits of the individual fields in order of definition, including
+                /// any unused bits to align the fields after bit fields to a whole byte.
                 ///
                 /// This is used by the default Debug implementation to split the raw output with
                 /// underscores.
@@ -689,6 +994,15 @@ impl DBKeyStruct {
 
                 #(#sets)*
 
+                #to_args_functions
+
+                #update_computed
+
+                #validate_function
+
+                #version_items
+
+                #schema_items
             }
 
             impl AsRef<[u8]> for #ident {
@@ -702,7 +1016,7 @@ impl DBKeyStruct {
                 fn from(args: #args_ident) -> Self {
                     let mut buf = [0_u8; #ident::KEY_LENGTH];
                     #(#from_args)*
-                    Self(buf)
+                    #new_key
                 }
             }
 
@@ -711,10 +1025,12 @@ impl DBKeyStruct {
                 fn from(args: &#args_ident) -> Self {
                     let mut buf = [0_u8; #ident::KEY_LENGTH];
                     #(#from_args)*
-                    Self(buf)
+                    #new_key
                 }
             }
 
+            #to_args_traits
+
             impl From<#ident> for [u8; #ident::KEY_LENGTH] {
                 fn from(key: #ident) -> Self {
                     key.0
@@ -727,14 +1043,7 @@ impl DBKeyStruct {
                 }
             }
 
-            impl From<&[u8]> for #ident {
-                fn from(slice: &[u8]) -> Self {
-                    let size = ::std::cmp::min(#ident::KEY_LENGTH, slice.len());
-                    let mut output = #ident::default(); // Fills with zeros
-                    output.0[..size].copy_from_slice(&slice[..size]);
-                    output
-                }
-            }
+            #from_slice
 
             impl From<#ident> for Vec<u8> {
                 fn from(key: #ident) -> Self {
@@ -768,10 +1077,582 @@ impl DBKeyStruct {
 
             #(#optional_traits)*
 
+            #version_traits
+
+            #layout_snapshot_test
+
+            #proptest_traits
+
+            #arbitrary_traits
+
+            #key_with_args_trait
+
+            #pattern_items
+
             #raw_debug_impl
         })
     }
 
+    /// Define the `to_args()` and `update()` functions of the key, and the conversions of the key
+    /// to its arguments, which read the fields with their getters.
+    fn to_args_code(&self) -> (TokenStream, TokenStream) {
+        let ident = &self.ident;
+        let args_ident = &self.args_ident;
+        let example_start = self.example_start();
+        let from_init_doc = self.from_init_doc();
+        let to_args = self.fields.to_args();
+        let to_args_doc = format!(concat!("Return the `{}` of the values of the fields of the ",
+            "key, which create the same key."), args_ident);
+        let to_args_example = format!(r#"
+{0}
+let args = from_key.to_args();
+
+assert_eq!({1}::from(&args), from_key);"#,
+            from_init_doc, ident);
+        let update_doc = format!(concat!("Change the fields of the key with a function that ",
+            "changes its `{}`, which is more efficient than calling several setters when the key ",
+            "has computed fields. The fields without a public setter keep their values."),
+            args_ident);
+        let (update_example, update_keeps) = if self.fields.has_kept() {
+            let keeps = self.fields.keeps();
+            (format!(r#"
+{0}
+let mut key = from_key.clone();
+key.update(|_| {{}});
+
+assert_eq!(key, from_key);"#,
+                from_init_doc),
+            quote! {
+                let kept = self.to_args();
+                #(#keeps)*
+            })
+        }
+        else {
+            (format!(r#"
+{0}
+let mut key = from_key.clone();
+key.update(|args| *args = {2}::default());
+
+assert_eq!(key, {1}::default());"#,
+                from_init_doc, ident, args_ident),
+            quote! {})
+        };
+        let from_doc = format!("Convert a `{}` to the `{}` of the values of its fields.", ident,
+            args_ident);
+        (quote! {
+            #[doc = #to_args_doc]
+            ///
+            /// # Examples
+            ///
+            #[doc = #example_start]
+            ///
+            #[doc = #to_args_example]
+            /// ```
+            pub fn to_args(&self) -> #args_ident {
+                #args_ident::from(self)
+            }
+
+            #[doc = #update_doc]
+            ///
+            /// # Examples
+            ///
+            #[doc = #example_start]
+            ///
+            #[doc = #update_example]
+            /// ```
+            pub fn update<F: FnOnce(&mut #args_ident)>(&mut self, f: F) {
+                let mut args = self.to_args();
+                f(&mut args);
+                #update_keeps
+                *self = Self::from(&args);
+            }
+        },
+        quote! {
+            #[doc = #from_doc]
+            impl From<&#ident> for #args_ident {
+                #[allow(clippy::needless_update)]
+                fn from(key: &#ident) -> Self {
+                    let defaults = #args_ident::default();
+                    Self {
+                        #(#to_args)*
+                        ..defaults
+                    }
+                }
+            }
+
+            #[doc = #from_doc]
+            impl From<#ident> for #args_ident {
+                fn from(key: #ident) -> Self {
+                    Self::from(&key)
+                }
+            }
+        })
+    }
+
+    /// Define the `proptest::arbitrary::Arbitrary` implementations of the arguments and the key
+    /// of a key with the `proptest` attribute. The arguments start with their default values,
+    /// and each input field is replaced by a value between its minimum and maximum values.
+    fn proptest_code(&self) -> TokenStream {
+        if !self.attr.proptest {
+            return quote! {};
+        }
+        let ident = &self.ident;
+        let args_ident = &self.args_ident;
+        let arbitraries = self.fields.arbitraries(self);
+        quote! {
+            impl ::proptest::arbitrary::Arbitrary for #args_ident {
+                type Parameters = ();
+                type Strategy = ::proptest::strategy::BoxedStrategy<Self>;
+
+                fn arbitrary_with(_: Self::Parameters) -> Self::Strategy {
+                    use ::proptest::strategy::Strategy as _;
+                    let strategy = ::proptest::strategy::LazyJust::new(
+                        <#args_ident as ::std::default::Default>::default).boxed();
+                    #(#arbitraries)*
+                    strategy
+                }
+            }
+
+            impl ::proptest::arbitrary::Arbitrary for #ident {
+                type Parameters = ();
+                type Strategy = ::proptest::strategy::BoxedStrategy<Self>;
+
+                fn arbitrary_with(_: Self::Parameters) -> Self::Strategy {
+                    use ::proptest::strategy::Strategy as _;
+                    ::proptest::arbitrary::any::<#args_ident>().prop_map(#ident::from).boxed()
+                }
+            }
+        }
+    }
+
+    /// Define the `arbitrary::Arbitrary` implementations of the arguments and the key of a key
+    /// with the `arbitrary` attribute. Like the proptest strategies, the arguments start with
+    /// their default values and each input field takes a value between its minimum and maximum
+    /// values, so fuzz targets get valid arguments from any input.
+    fn arbitrary_code(&self) -> TokenStream {
+        if !self.attr.arbitrary {
+            return quote! {};
+        }
+        let ident = &self.ident;
+        let args_ident = &self.args_ident;
+        let arbitrary_fields = self.fields.arbitrary_fields(self);
+        quote! {
+            impl<'a> ::arbitrary::Arbitrary<'a> for #args_ident {
+                fn arbitrary(u: &mut ::arbitrary::Unstructured<'a>) -> ::arbitrary::Result<Self> {
+                    let mut args = <#args_ident as ::std::default::Default>::default();
+                    #(#arbitrary_fields)*
+                    Ok(args)
+                }
+            }
+
+            impl<'a> ::arbitrary::Arbitrary<'a> for #ident {
+                fn arbitrary(u: &mut ::arbitrary::Unstructured<'a>) -> ::arbitrary::Result<Self> {
+                    Ok(#ident::from(&<#args_ident as ::arbitrary::Arbitrary<'a>>::arbitrary(u)?))
+                }
+            }
+        }
+    }
+
+    /// Define the `<Key>Pattern` structure of a key with the `pattern` attribute, which has an
+    /// `Option` for each input field, and its conversions to the range of the keys that can match
+    /// the pattern and the residual pattern that the keys in the range must still match.
+    fn pattern_code(&self) -> TokenStream {
+        if !self.attr.pattern {
+            return quote! {};
+        }
+        let vis = &self.vis;
+        let ident = &self.ident;
+        let args_ident = &self.args_ident;
+        let pattern_ident = Ident::new(&format!("{}Pattern", ident), ident.span());
+        let pattern_fields = self.fields.pattern_fields();
+        let pattern_args = self.fields.pattern_args();
+        let pattern_matches = self.fields.pattern_matches();
+        let pattern_fits = self.fields.pattern_fits();
+        let pattern_froms = self.fields.pattern_froms();
+        let pattern_bounds = self.fields.pattern_bounds(self);
+        let pattern_residuals = self.fields.pattern_residuals();
+        let example_start = self.example_start_with(&[ident, args_ident, &pattern_ident]);
+        // The bytes from a computed field followed by a `None` field range from all zeros to all
+        // ones.
+        let (computed_start, computed_end) = if self.fields.has_computed() {
+            (quote! {
+                // The first byte of the first computed field, and the first byte of the first
+                // computed field followed by a `None` field.
+                let mut computed: Option<usize> = None;
+                let mut unbounded: Option<usize> = None;
+            },
+            quote! {
+                let mut start = #ident::from(&start);
+                let mut end = #ident::from(&end);
+                if let Some(unbounded) = unbounded {
+                    start.0[unbounded..].fill(0x00);
+                    end.0[unbounded..].fill(0xFF);
+                }
+            })
+        }
+        else {
+            (quote! {}, quote! {
+                let (start, end) = (#ident::from(&start), #ident::from(&end));
+            })
+        };
+        let from_init_doc = self.from_init_doc();
+        let pattern_doc = format!(concat!("A pattern of `{0}` values, where each field is ",
+            "either `Some` value that the field of the key must store, or `None` for any value."),
+            ident);
+        let pattern_example = format!(r#"
+{0}
+let pattern = {1}::from(&from_key);
+
+assert!(pattern.matches(&from_key));
+assert!({1}::default().matches(&from_key));
+assert_eq!(pattern.range(), from_key.clone()..=from_key.clone());
+assert_eq!(pattern.residual(), {1}::default());"#,
+            from_init_doc, pattern_ident);
+        let from_doc = format!("Create the `{}` that only matches the values of a `{}`.",
+            pattern_ident, ident);
+        quote! {
+            #[doc = #pattern_doc]
+            ///
+            /// # Examples
+            ///
+            #[doc = #example_start]
+            ///
+            #[doc = #pattern_example]
+            /// ```
+            #[derive(Clone, Debug, Default, PartialEq, Eq)]
+            #vis struct #pattern_ident {
+                #(#pattern_fields)*
+            }
+
+            impl #pattern_ident {
+                /// Return true if the key stores the value of each field that is `Some` in the
+                /// pattern, after the value is converted like the setter of the field converts it
+                /// (e.g. normalized by the collation of the field). A value that doesn't fit in
+                /// its field (e.g. out of the range of a field with the `bytes` attribute) doesn't
+                /// match any key.
+                pub fn matches(&self, key: &#ident) -> bool {
+                    if !self.fits() {
+                        return false;
+                    }
+                    let mut args = #args_ident::default();
+                    #(#pattern_args)*
+                    let expected = #ident::from(&args);
+                    #(#pattern_matches)*
+                    true
+                }
+
+                /// Return the smallest range of keys that contains every key matching the
+                /// pattern, whose fields are between their minimum and maximum values. The
+                /// fields that are `Some` keep their values in the start and the end of the
+                /// range, and the fields that are `None` range from their minimum to their
+                /// maximum value, so the keys of the range only share the leading fields (the
+                /// fields that are `Some` before the first `None` field), and must still match
+                /// `residual()`. A computed field stored before a `None` field can't be bounded,
+                /// so the range covers all the bytes from the computed field. If a value doesn't
+                /// fit in its field, the range only contains the key of zero bytes, and the
+                /// residual pattern doesn't match it.
+                pub fn range(&self) -> ::std::ops::RangeInclusive<#ident> {
+                    self.split().0
+                }
+
+                /// Return the pattern of the fields that are `Some` and that the keys of `range()`
+                /// don't all share (e.g. the fields after a `None` field), which filters the keys
+                /// of the range: a key matches the pattern if and only if it's in the range and
+                /// matches the residual pattern.
+                pub fn residual(&self) -> Self {
+                    self.split().1
+                }
+
+                /// Return true if the value of each field that is `Some` fits in the field, so
+                /// the pattern can match a key.
+                fn fits(&self) -> bool {
+                    #(#pattern_fits)*
+                    true
+                }
+
+                /// Return the range and the residual pattern of the pattern.
+                #[allow(unused_assignments)]
+                fn split(&self) -> (::std::ops::RangeInclusive<#ident>, Self) {
+                    // A pattern that can't match has the range of the zero key, which the
+                    // pattern filters out, so the range is still valid for a range scan.
+                    if !self.fits() {
+                        let start = #ident::from([0x00_u8; #ident::KEY_LENGTH]);
+                        let end = #ident::from([0x00_u8; #ident::KEY_LENGTH]);
+                        return (start..=end, self.clone());
+                    }
+                    let mut start = #args_ident::default();
+                    let mut end = #args_ident::default();
+                    #computed_start
+                    #(#pattern_bounds)*
+                    #computed_end
+                    let mut residual = Self::default();
+                    #(#pattern_residuals)*
+                    (start..=end, residual)
+                }
+            }
+
+            #[doc = #from_doc]
+            impl From<&#ident> for #pattern_ident {
+                fn from(key: &#ident) -> Self {
+                    let args = key.to_args();
+                    Self {
+                        #(#pattern_froms)*
+                    }
+                }
+            }
+        }
+    }
+
+    /// Define the `db_key::KeyWithArgs` implementation of a key with the `proptest` or
+    /// `arbitrary` attribute, which test helpers use to generate the arguments of the key.
+    fn key_with_args_code(&self) -> TokenStream {
+        if !self.attr.proptest && !self.attr.arbitrary {
+            return quote! {};
+        }
+        let ident = &self.ident;
+        let args_ident = &self.args_ident;
+        quote! {
+            impl ::db_key::KeyWithArgs for #ident {
+                type Args = #args_ident;
+            }
+        }
+    }
+
+    /// Define the `SCHEMA` constant and the `schema_json()` function of a key with the
+    /// `schema_json` attribute.
+    fn schema_code(&self) -> TokenStream {
+        if !self.attr.schema_json {
+            return quote! {};
+        }
+        let ident = &self.ident;
+        let field_schemas = self.fields.field_schemas();
+        let raw_debug = self.attr.raw_fmt.name();
+        let custom_debug = !self.attr.debug;
+        let min_key = if self.attr.min_key {
+            quote! { Some(Self::MIN_KEY.as_ref()) }
+        }
+        else {
+            quote! { None }
+        };
+        let max_key = if self.attr.max_key {
+            quote! { Some(Self::MAX_KEY.as_ref()) }
+        }
+        else {
+            quote! { None }
+        };
+        let example = format!(concat!("{0}\n\nlet json = {1}::schema_json();\n\n",
+            "assert!(json.starts_with(\"{{\\\"key\\\":\\\"{1}\\\",\"));"),
+            self.example_start(), ident);
+        let c_example = format!(concat!("{0}\n\nlet header = {1}::c_header();\n\n",
+            "assert!(header.contains(\"#define {2}_LENGTH \"));"),
+            self.example_start(), ident, snake_case(&ident.to_string()).to_ascii_uppercase());
+        let python_example = format!(concat!("{0}\n\nlet module = {1}::python_module();\n\n",
+            "assert!(module.contains(\"class {1}:\"));"),
+            self.example_start(), ident);
+        quote! {
+            /// The layout of the key, which has the offset, size, and encoding of each field.
+            pub const SCHEMA: ::db_key::KeySchema = ::db_key::KeySchema {
+                key: stringify!(#ident),
+                length: Self::KEY_LENGTH,
+                hash: Self::SCHEMA_HASH,
+                raw_debug: #raw_debug,
+                custom_debug: #custom_debug,
+                fields: &[#(#field_schemas)*],
+            };
+
+            /// Return the layout of the key as JSON, which has the offset, size, and encoding of
+            /// each field, and its value in the default, minimum, and maximum keys, for decoding
+            /// the key in other languages.
+            ///
+            /// # Examples
+            ///
+            #[doc = #example]
+            /// ```
+            pub fn schema_json() -> String {
+                Self::SCHEMA.to_json(Self::default().as_ref(), #min_key, #max_key)
+            }
+
+            /// Return a C header with the layout of the key and the functions that encode and
+            /// decode its fields the same way as the key, which can be written by a build
+            /// script.
+            ///
+            /// # Examples
+            ///
+            #[doc = #c_example]
+            /// ```
+            pub fn c_header() -> String {
+                Self::SCHEMA.c_header(Self::default().as_ref())
+            }
+
+            /// Return a Python module with a class that encodes and decodes the key the same way
+            /// as the key, which can be written by a build script.
+            ///
+            /// # Examples
+            ///
+            #[doc = #python_example]
+            /// ```
+            pub fn python_module() -> String {
+                Self::SCHEMA.python_module(Self::default().as_ref())
+            }
+        }
+    }
+
+    /// Define the test of a key with the `layout_snapshot` attribute, which compares the layout
+    /// of the key and the bytes of reference keys with the snapshot file.
+    fn layout_snapshot_test(&self) -> TokenStream {
+        let path = match &self.attr.layout_snapshot {
+            Some(path) => path,
+            None => { return quote! {}; }
+        };
+        let ident = &self.ident;
+        let args_ident = &self.args_ident;
+        let test_ident = Ident::new(&format!("{}_layout_snapshot", snake_case(&ident.to_string())),
+            ident.span());
+        let mut keys = vec![quote! { ("default", #ident::DEFAULT_KEY) }];
+        if self.attr.min_key {
+            keys.push(quote! { ("min", #ident::MIN_KEY) });
+        }
+        if self.attr.max_key {
+            keys.push(quote! { ("max", #ident::MAX_KEY) });
+        }
+        // The reference keys have values generated from fixed seeds, so they only change when
+        // the encoding of the fields changes.
+        for seed in 1..=3_u64 {
+            let name = format!("reference {}", seed);
+            let references = self.fields.references(seed);
+            keys.push(quote! {
+                (#name, #ident::from(&#args_ident {
+                    #(#references)*
+                    ..Default::default()
+                }))
+            });
+        }
+        quote! {
+            #[cfg(test)]
+            #[test]
+            #[allow(clippy::needless_update)]
+            fn #test_ident() {
+                let keys = [#(#keys, )*];
+                let keys: Vec<(&str, &[u8])> = keys.iter()
+                    .map(|(name, key)| (*name, key.as_ref()))
+                    .collect();
+                ::db_key::LayoutSnapshot {
+                    key: stringify!(#ident),
+                    length: #ident::KEY_LENGTH,
+                    description: #ident::SCHEMA_DESCRIPTION,
+                    hash: #ident::SCHEMA_HASH,
+                    keys: &keys,
+                }.check(concat!(env!("CARGO_MANIFEST_DIR"), "/", #path));
+            }
+        }
+    }
+
+    /// Define the version constants and `decode_any()` of a key with the version attribute, and
+    /// the conversion from the previous version.
+    fn version_code(&self, version: u8) -> (TokenStream, TokenStream) {
+        let ident = &self.ident;
+        let args_ident = &self.args_ident;
+        let example_start = self.example_start();
+        let version_names = self.fields.version_names();
+        let version_doc = format!("The version of the `{}`, which is stored in the first byte.",
+            ident);
+        let decode_doc = format!(concat!("Create a `{}` from the bytes of a key of this version, ",
+            "or convert the key of a previous version selected by the first byte. Keys with ",
+            "computed fields or the `strict` attribute are checked with `validate()`."), ident);
+        let validate = if self.fields.has_computed() || self.attr.strict {
+            quote! { key.validate()?; }
+        }
+        else {
+            quote! {}
+        };
+        let decode_example = format!(r#"
+let key = {0}::default();
+
+assert_eq!({0}::decode_any(key.as_ref()), Ok(key));
+assert!({0}::decode_any(&[]).is_err());"#,
+            ident);
+        let (decode_previous, version_traits) = match &self.attr.previous {
+            Some(previous) => {
+                let missing_fields = self.fields.missing_fields();
+                let migrates = self.fields.migrates();
+                let migrate_checks = self.fields.migrate_checks(self);
+                (quote! {
+                    Some(_) => Ok(Self::from(#previous::decode_any(bytes)?)),
+                },
+                quote! {
+                    impl From<#previous> for #ident {
+                        #[allow(clippy::needless_update)]
+                        fn from(previous: #previous) -> Self {
+                            // The getters of the fields that aren't in the previous version.
+                            #[allow(dead_code)]
+                            trait MissingFields {
+                                #(#missing_fields)*
+                            }
+                            impl MissingFields for #previous {}
+                            const _: () = assert!(#previous::VERSION < #ident::VERSION,
+                                "The previous version must be less than the version of the key.");
+                            const _: () = assert!(
+                                ::db_key::contains_all(#ident::VERSION_FIELDS,
+                                    #previous::VERSION_FIELDS),
+                                "A field of the previous version isn't in the key.");
+                            #(#migrate_checks)*
+                            #[allow(unused_imports)]
+                            use ::db_key::{MigrateExact, MigrateFrom, MigrateNonZero, MigrateOption};
+                            let defaults = #args_ident::default();
+                            Self::from(#args_ident {
+                                #(#migrates)*
+                                ..defaults
+                            })
+                        }
+                    }
+                })
+            }
+            None => {
+                (quote! {
+                    Some(found) => Err(::db_key::DecodeError::Version { found: *found }),
+                },
+                quote! {})
+            }
+        };
+        let version_items = quote! {
+            #[doc = #version_doc]
+            pub const VERSION: u8 = #version;
+            /// The names of the fields, which must also be in the next version of the key.
+            #[doc(hidden)]
+            pub const VERSION_FIELDS: &'static [&'static str] = &[#(#version_names)*];
+
+            #[doc = #decode_doc]
+            ///
+            /// # Examples
+            ///
+            #[doc = #example_start]
+            #[doc = #decode_example]
+            /// ```
+            pub fn decode_any(bytes: &[u8]) -> ::std::result::Result<Self, ::db_key::DecodeError> {
+                match bytes.first() {
+                    Some(&Self::VERSION) => {
+                        if Self::KEY_LENGTH != bytes.len() {
+                            return Err(::db_key::DecodeError::Length {
+                                expected: Self::KEY_LENGTH,
+                                found: bytes.len(),
+                            });
+                        }
+                        let mut key = Self([0_u8; Self::KEY_LENGTH]);
+                        key.0.copy_from_slice(bytes);
+                        #validate
+                        Ok(key)
+                    }
+                    #decode_previous
+                    None => Err(::db_key::DecodeError::Length {
+                        expected: Self::KEY_LENGTH,
+                        found: 0,
+                    }),
+                }
+            }
+        };
+        (version_items, version_traits)
+    }
+
     fn gets(&self) -> Vec<TokenStream> {
         self.fields.gets(self)
     }
@@ -804,12 +1685,14 @@ impl DBKeyStruct {
             "")
     }
 
+    #[allow(clippy::wrong_self_convention)]
     fn from_init_doc(&self) -> String {
         self.fields.from_init_doc(
             format_args!("// Create a {0} from a {1} structure.\nlet from_key = {0}::from( {1} {{\n", self.ident, self.args_ident),
             "});")
     }
 
+    #[allow(clippy::wrong_self_convention)]
     fn from_init_partial(&self) -> String {
         self.fields.from_init_partial(
             format_args!("// Create a {0} from a partially defined {1} structure.\nlet partial_from_key = {0}::from( {1} {{\n", self.ident, self.args_ident),
@@ -831,8 +1714,7 @@ impl DBKeyStruct {
     fn derive_copy(&self) -> TokenStream {
         let derive_copy = self.attr.derive_copy.unwrap_or_else(|| {
             let total_size = self.fields.total_size();
-            if 64 < total_size { false }
-            else { true }
+            64 >= total_size
         });
         if derive_copy {
             quote! {
@@ -842,24 +1724,51 @@ impl DBKeyStruct {
         else { quote!{} }
     }
 
+    /// The argument structure is only `Copy` by default if it has no codec or newtype fields,
+    /// because their values may not be `Copy`.
+    fn args_derive_copy(&self) -> TokenStream {
+        if self.attr.derive_copy.is_none() && self.fields.has_custom_type() {
+            quote!{}
+        }
+        else {
+            self.derive_copy()
+        }
+    }
+
     fn raw_debug_format(&self) -> TokenStream {
         match self.attr.raw_fmt {
             RawDebugFormat::Compact => {
                 let ident = &self.ident;
                 quote! {
-                    f.write_str("0x")?;
-                    let mut field_sizes = #ident::FIELD_SIZES.iter();
-                    let mut field_remain: usize = *field_sizes.next().unwrap_or(&usize::MAX);
-                    // Display a leading underscore before the next byte.
-                    let mut leading_ = false;
-                    for byte in self.0.0.iter() {
-                        if leading_ { f.write_str("_")?; leading_ = false; }
-                        f.write_fmt(format_args!("{:02X}", byte))?;
-                        field_remain -= 1;
-                        if 0 == field_remain {
-                            leading_ = true;
-                            field_remain = *field_sizes.next().unwrap_or(&usize::MAX);
+                    // Fields that are whole bytes are displayed in hexadecimal, and bit fields are
+                    // displayed in binary.
+                    let mut bit = 0;
+                    let mut binary = None;
+                    for size in #ident::FIELD_SIZES.iter() {
+                        if 0 == bit % 8 && 0 == size % 8 {
+                            match binary {
+                                None => f.write_str("0x")?,
+                                Some(true) => f.write_str("_0x")?,
+                                Some(false) => f.write_str("_")?,
+                            }
+                            for byte in self.0.0[bit / 8..(bit + size) / 8].iter() {
+                                f.write_fmt(format_args!("{:02X}", byte))?;
+                            }
+                            binary = Some(false);
                         }
+                        else {
+                            match binary {
+                                None => f.write_str("0b")?,
+                                Some(false) => f.write_str("_0b")?,
+                                Some(true) => f.write_str("_")?,
+                            }
+                            for i in bit..(bit + size) {
+                                let set = 0 != self.0.0[i / 8] & (0x80 >> (i % 8));
+                                f.write_str(if set { "1" } else { "0" })?;
+                            }
+                            binary = Some(true);
+                        }
+                        bit += size;
                     }
                     Ok(())
                 }
@@ -876,7 +1785,7 @@ impl DBKeyStruct {
             RawDebugFormat::PrettyLowerHex => quote! {
                 f.write_str("[")?;
                 let mut byte_iter = self.0.0.iter();
-                f.write_fmt(format_args!("{:#04X}", byte_iter.next().unwrap()))?;
+                f.write_fmt(format_args!("{:#04x}", byte_iter.next().unwrap()))?;
                 for byte in byte_iter {
                     f.write_fmt(format_args!(", {:#04x}", byte))?;
                 }
@@ -965,3 +1874,34 @@ impl DBKeyStruct {
         }
     }
 }
+
+/// Return the 64-bit FNV-1a hash of the schema description of a key, which doesn't depend on the
+/// version of Rust like the hasher of the standard library.
+fn schema_hash(description: &str) -> u64 {
+    description.bytes().fold(0xcbf2_9ce4_8422_2325, |hash, byte| {
+        (hash ^ u64::from(byte)).wrapping_mul(0x0000_0100_0000_01b3)
+    })
+}
+
+/// Convert the name of a key to snake case for the name of its layout snapshot test (e.g.
+/// `sample_key` for `SampleKey`).
+fn snake_case(name: &str) -> String {
+    let chars: Vec<char> = name.chars().collect();
+    let mut snake = String::with_capacity(name.len() + 4);
+    for (i, c) in chars.iter().enumerate() {
+        if c.is_ascii_uppercase() {
+            // Acronyms stay together (e.g. `uuid_key` for `UUIDKey`).
+            let after_lower = 0 < i && !chars[i - 1].is_ascii_uppercase() && '_' != chars[i - 1];
+            let acronym_end = 0 < i && chars[i - 1].is_ascii_uppercase()
+                && chars.get(i + 1).map_or(false, |next| next.is_ascii_lowercase());
+            if after_lower || acronym_end {
+                snake.push('_');
+            }
+            snake.push(c.to_ascii_lowercase());
+        }
+        else {
+            snake.push(*c);
+        }
+    }
+    snake
+}
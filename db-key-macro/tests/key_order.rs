@@ -61,17 +61,17 @@ prop_compose! {
         elevens in proptest::collection::btree_set(i128::MIN..=i128::MAX, count..=count),
     ) -> OrderedInputs {
         OrderedInputs {
-            ones: ones.iter().map(|v| *v).collect(),
-            twos: twos.iter().map(|v| *v).collect(),
-            threes: threes.iter().map(|v| *v).collect(),
-            fours: fours.iter().map(|v| *v).collect(),
-            fives: fives.iter().map(|v| *v).collect(),
-            sixes: sixes.iter().map(|v| *v).collect(),
-            sevens: sevens.iter().map(|v| *v).collect(),
-            eights: eights.iter().map(|v| *v).collect(),
-            nines: nines.iter().map(|v| *v).collect(),
-            tens: tens.iter().map(|v| *v).collect(),
-            elevens: elevens.iter().map(|v| *v).collect(),
+            ones: ones.iter().copied().collect(),
+            twos: twos.iter().copied().collect(),
+            threes: threes.iter().copied().collect(),
+            fours: fours.iter().copied().collect(),
+            fives: fives.iter().copied().collect(),
+            sixes: sixes.iter().copied().collect(),
+            sevens: sevens.iter().copied().collect(),
+            eights: eights.iter().copied().collect(),
+            nines: nines.iter().copied().collect(),
+            tens: tens.iter().copied().collect(),
+            elevens: elevens.iter().copied().collect(),
         }
     }
 }
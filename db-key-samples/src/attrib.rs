@@ -3,10 +3,20 @@
 pub mod debug;
 
 use db_key_macro::db_key;
-use std::fmt::Debug;
+use std::{
+    fmt::Debug,
+    net::{Ipv4Addr, Ipv6Addr, SocketAddrV4, SocketAddrV6},
+    num::{NonZeroI16, NonZeroI32, NonZeroI8, NonZeroU32, NonZeroU64},
+    time::{Duration, SystemTime},
+};
+use uuid::Uuid;
+use crate::{
+    codec::{Cents, Label},
+    ids::{GroupId, Region, UserId},
+};
 
 /// This is a sample key to test the `db_key` attribute macro.
-#[db_key(path = attrib)]
+#[db_key(path = attrib, proptest, arbitrary, pattern)]
 pub struct SampleKey {
     /// This is the ID number for the something.
     #[name = "ID"]
@@ -165,7 +175,7 @@ pub struct NoDocKey {
 }
 
 /// This is a sample key with minimums and maximums.
-#[db_key(path = attrib)]
+#[db_key(path = attrib, proptest, arbitrary)]
 pub struct MinMaxKey {
     /// This is the ID number for the something.
     #[name = "ID"]
@@ -199,7 +209,7 @@ pub struct MinMaxKey {
 }
 
 /// This is a sample key with signed integer values.
-#[db_key(path = attrib)]
+#[db_key(path = attrib, proptest, arbitrary)]
 pub struct SignedKey {
     /// This is the ID number for the something.
     #[name = "ID"]
@@ -295,6 +305,407 @@ pub struct NoMaxKey {
     end: [u8; 3],
 }
 
+/// This is a sample key with integer fields stored in fewer bytes than their types.
+#[db_key(path = attrib, proptest, arbitrary)]
+pub struct NarrowKey {
+    /// A 48-bit identifier.
+    #[name = "ID"]
+    #[bytes = 6]
+    #[default = 0x123456789ABC]
+    id: u64,
+    /// A 24-bit signed offset.
+    #[name = "Offset"]
+    #[bytes = 3]
+    offset: i32,
+    /// A 40-bit signed value that saturates when it is out of range.
+    #[name = "Saturated"]
+    #[bytes = 5]
+    #[overflow = saturate]
+    saturated: i64,
+    /// A 24-bit counter that only keeps the low bytes when it is out of range.
+    #[name = "Counter"]
+    #[bytes = 3]
+    #[overflow = "truncate"]
+    counter: u32,
+}
+
+/// This is a sample key with bit fields.
+#[db_key(path = attrib, schema_json, proptest, arbitrary, pattern)]
+pub struct BitsKey {
+    /// A 4-bit priority.
+    #[name = "Priority"]
+    #[bits = 4]
+    #[default = 5]
+    priority: u8,
+    /// A 12-bit shard number.
+    #[name = "Shard"]
+    #[bits = 12]
+    shard: u16,
+    /// A 3-bit signed adjustment.
+    #[name = "Adjustment"]
+    #[bits = 3]
+    adjust: i8,
+    /// A byte aligned identifier after the bit fields.
+    #[name = "ID"]
+    id: u32,
+    /// A 10-bit signed level that saturates when it is out of range.
+    #[name = "Level"]
+    #[bits = 10]
+    #[overflow = saturate]
+    level: i16,
+}
+
+/// This is a sample key with optional fields.
+#[db_key(path = attrib, schema_json, proptest, arbitrary, pattern,
+    layout_snapshot = "tests/snapshots/option_key.txt")]
+pub struct OptionKey {
+    /// An optional parent identifier, where `None` sorts first.
+    #[name = "Parent"]
+    parent: Option<u64>,
+    /// An optional signed kind.
+    #[name = "Kind"]
+    kind: Option<i8>,
+    /// An optional tag, where `None` sorts last.
+    #[name = "Tag"]
+    #[none_last]
+    tag: Option<[u8; 3]>,
+    /// An optional offset stored in 3 bytes.
+    #[name = "Offset"]
+    #[bytes = 3]
+    offset: Option<i32>,
+    /// A required identifier.
+    #[name = "ID"]
+    id: u16,
+}
+
+/// This is a sample key with network address fields.
+#[db_key(path = attrib, schema_json, proptest, arbitrary)]
+pub struct FlowKey {
+    /// The tenant that owns the flow.
+    #[name = "Tenant"]
+    tenant: u16,
+    /// The source IPv4 address.
+    #[name = "Source"]
+    source: Ipv4Addr,
+    /// The destination IPv6 address.
+    #[name = "Destination"]
+    destination: Ipv6Addr,
+    /// The client IPv4 socket address.
+    #[name = "Client"]
+    client: SocketAddrV4,
+    /// The server IPv6 socket address.
+    #[name = "Server"]
+    server: SocketAddrV6,
+    /// An optional gateway address.
+    #[name = "Gateway"]
+    gateway: Option<std::net::Ipv4Addr>,
+}
+
+/// This is a sample key with time fields.
+#[db_key(path = attrib, schema_json, proptest, arbitrary, pattern,
+    layout_snapshot = "tests/snapshots/event_key.txt")]
+pub struct EventKey {
+    /// The time of the event in nanoseconds since the Unix epoch.
+    #[name = "Time"]
+    time: SystemTime,
+    /// The time the event was updated in milliseconds since the Unix epoch.
+    #[name = "Updated"]
+    #[time_unit = "ms"]
+    #[bytes = 6]
+    updated: SystemTime,
+    /// The time to live of the event in seconds, which saturates at about 136 years.
+    #[name = "TTL"]
+    #[time_unit = s]
+    #[bytes = 4]
+    #[overflow = saturate]
+    ttl: Duration,
+    /// An optional expiration time in seconds since the Unix epoch.
+    #[name = "Expires"]
+    #[time_unit = "s"]
+    #[bytes = 5]
+    expires: Option<SystemTime>,
+}
+
+/// This is a sample key with UUID fields.
+#[db_key(path = attrib, schema_json, proptest, arbitrary)]
+pub struct RecordKey {
+    /// The UUID of the table, where v7 UUIDs sort by time.
+    #[name = "Table"]
+    table: Uuid,
+    /// The optional UUID of the parent record.
+    #[name = "Parent"]
+    parent: Option<uuid::Uuid>,
+    /// The record number.
+    #[name = "Number"]
+    number: u32,
+}
+
+/// This is a sample key with codec fields.
+#[db_key(path = attrib, pattern,
+    layout_snapshot = "tests/snapshots/ledger_key.txt")]
+pub struct LedgerKey {
+    /// The account number.
+    #[name = "Account"]
+    account: u32,
+    /// The balance of the account.
+    #[name = "Balance"]
+    #[codec = crate::codec::CentsCodec]
+    #[bytes = 8]
+    balance: Cents,
+    /// An optional label for the entry.
+    #[name = "Label"]
+    #[codec = crate::codec::LabelCodec]
+    #[bytes = 8]
+    label: Option<Label>,
+}
+
+/// This is a sample key with newtype fields.
+#[db_key(path = attrib, proptest, arbitrary,
+    layout_snapshot = "tests/snapshots/member_key.txt")]
+pub struct MemberKey {
+    /// The region of the group.
+    #[name = "Region"]
+    #[via = [u8; 2]]
+    region: Region,
+    /// The optional group of the user, which is stored in 3 bytes.
+    #[name = "Group"]
+    #[via = u32]
+    #[bytes = 3]
+    group: Option<GroupId>,
+    /// The user.
+    #[name = "User"]
+    #[via = u64]
+    #[default = 1]
+    user: UserId,
+}
+
+/// This is a sample key with char fields for a prefix-indexed dictionary.
+#[db_key(path = attrib, schema_json, proptest, arbitrary,
+    layout_snapshot = "tests/snapshots/word_key.txt")]
+pub struct WordKey {
+    /// The first letter of the word.
+    #[name = "Initial"]
+    initial: char,
+    /// The next letters of the word, which are stored in 3 bytes each.
+    #[name = "Letters"]
+    #[bytes = 3]
+    letters: [char; 3],
+    /// The optional last letter of the word.
+    #[name = "Last"]
+    #[bytes = 3]
+    last: Option<char>,
+}
+
+/// This is a sample key for a case-insensitive user lookup index.
+#[db_key(path = attrib, schema_json, proptest, arbitrary, pattern)]
+pub struct LoginKey {
+    /// The user name, where `Alice` and `alice` are the same user.
+    #[name = "User name"]
+    #[collate = ascii_ci]
+    user: [u8; 8],
+    /// The optional domain of the user, which also ignores the surrounding whitespace.
+    #[name = "Domain"]
+    #[collate = ascii_ci_trim]
+    domain: Option<[u8; 6]>,
+    /// The tag of the user, which is case sensitive.
+    #[name = "Tag"]
+    #[collate = binary]
+    tag: [u8; 2],
+}
+
+/// This is a sample key with fixed-point decimal fields for a ledger index sorted by amount.
+#[db_key(path = attrib, schema_json, proptest, arbitrary)]
+pub struct AmountKey {
+    /// The amount in ten-thousandths of the currency unit.
+    #[name = "Amount"]
+    #[decimal(scale = 4)]
+    amount: i64,
+    /// The exchange rate in hundredths, which is stored in 3 bytes.
+    #[name = "Rate"]
+    #[decimal(scale = 2)]
+    #[bytes = 3]
+    rate: u32,
+    /// The entry in the ledger.
+    #[name = "Entry"]
+    entry: u32,
+}
+
+/// This is a sample key with non-zero identifiers.
+#[db_key(path = attrib, schema_json, proptest, arbitrary)]
+pub struct OrderKey {
+    /// The customer of the order.
+    #[name = "Customer"]
+    customer: NonZeroU64,
+    /// The optional parent order, which is stored as zero when it's `None`.
+    #[name = "Parent"]
+    #[bytes = 6]
+    parent: Option<NonZeroU64>,
+    /// The change of the order.
+    #[name = "Delta"]
+    delta: NonZeroI16,
+}
+
+/// This is a sample key with optional signed non-zero values, which store `None` as the minimum
+/// value of the field.
+#[db_key(path = attrib, schema_json, proptest, pattern)]
+pub struct AdjustmentKey {
+    /// The account of the adjustment.
+    #[name = "Account"]
+    account: u32,
+    /// The optional adjustment of the balance.
+    #[name = "Adjustment"]
+    adjustment: Option<NonZeroI32>,
+    /// The optional correction, which is stored in 3 bytes and saturates.
+    #[name = "Correction"]
+    #[bytes = 3]
+    #[overflow = saturate]
+    correction: Option<NonZeroI32>,
+    /// The optional step, which is stored in 4 bits.
+    #[name = "Step"]
+    #[bits = 4]
+    step: Option<NonZeroI8>,
+}
+
+/// This is a sample key with non-zero values that are truncated to fewer bytes, where values
+/// that would be stored as zero bytes panic instead of reading back as `None`.
+#[db_key(path = attrib, pattern)]
+pub struct TruncatedKey {
+    /// An optional signed value stored in the low 3 bytes.
+    #[name = "Signed"]
+    #[bytes = 3]
+    #[overflow = truncate]
+    signed: Option<NonZeroI32>,
+    /// An optional unsigned value stored in the low 3 bytes.
+    #[name = "Unsigned"]
+    #[bytes = 3]
+    #[overflow = truncate]
+    unsigned: Option<NonZeroU32>,
+    /// A required value stored in the low 2 bytes.
+    #[name = "Required"]
+    #[bytes = 2]
+    #[overflow = truncate]
+    required: NonZeroU32,
+}
+
+/// Return the shard of a `ShardedKey`, which is the high byte of a hash of the user.
+pub fn user_shard(key: &ShardedKey) -> u8 {
+    (key.user().wrapping_mul(0x9E37_79B9_7F4A_7C15) >> 56) as u8
+}
+
+/// Return the checksum of the fields of a `ShardedKey` before the checksum.
+pub fn key_checksum(key: &ShardedKey) -> u16 {
+    key.as_ref()[..ShardedKey::CHECK_START].iter()
+        .fold(0_u16, |sum, byte| sum.wrapping_mul(31).wrapping_add(*byte as u16))
+}
+
+/// This is a sample key that starts with a shard computed from the user to avoid write hot spots.
+#[db_key(path = attrib, schema_json, pattern,
+    layout_snapshot = "tests/snapshots/sharded_key.txt")]
+pub struct ShardedKey {
+    /// The shard of the user.
+    #[name = "Shard"]
+    #[computed = user_shard]
+    shard: u8,
+    /// The user.
+    #[name = "User"]
+    user: u64,
+    /// The event of the user.
+    #[name = "Event"]
+    event: u32,
+    /// The checksum of the other fields.
+    #[name = "Checksum"]
+    #[computed = crate::attrib::key_checksum]
+    check: u16,
+}
+
+/// This is a sample key with a version that can't be changed, a hidden tag, and an identifier
+/// that can only be accessed in this crate.
+#[db_key(path = attrib, schema_json)]
+pub struct TaggedKey {
+    /// The version of the key schema.
+    #[name = "Version"]
+    #[default = 2]
+    #[readonly]
+    version: u8,
+    /// The type tag of the record.
+    #[name = "Tag"]
+    #[hidden]
+    tag: u16,
+    /// The internal identifier of the record.
+    #[name = "Internal ID"]
+    #[vis(pub(crate))]
+    internal: u64,
+    /// The entry of the record.
+    #[name = "Entry"]
+    entry: u32,
+}
+
+/// This is a sample key with padding reserved for a future region field after the account, and
+/// two reserved bytes at the end, which are checked by `validate()`.
+#[db_key(path = attrib, strict, reserve = 2, schema_json, proptest, arbitrary,
+    layout_snapshot = "tests/snapshots/reserved_key.txt")]
+pub struct ReservedKey {
+    /// The account.
+    #[name = "Account"]
+    account: u32,
+    /// The bytes reserved for the region of the account.
+    #[padding]
+    _region: [u8; 3],
+    /// The entry of the account.
+    #[name = "Entry"]
+    entry: u16,
+}
+
+/// This is a sample key with a version and a reserved byte, which `decode_any()` checks, and
+/// the pretty lowercase hexadecimal raw format.
+#[db_key(path = attrib, version = 1, strict, reserve = 1, raw_debug = pretty_lower_hex,
+    schema_json)]
+pub struct JournalKey {
+    /// The account of the journal entry.
+    #[name = "Account"]
+    account: u32,
+}
+
+/// This is the first version of a sample key for the activities of a user.
+#[db_key(path = attrib, version = 1)]
+pub struct ActivityKeyV1 {
+    /// The user of the activity.
+    #[name = "User"]
+    user: u32,
+    /// The tag of the activity.
+    #[name = "Tag"]
+    tag: [u8; 2],
+    /// The optional parent activity.
+    #[name = "Parent"]
+    parent: Option<u16>,
+    /// The owner of the user.
+    #[name = "Owner"]
+    owner: NonZeroU32,
+}
+
+/// This is the second version of a sample key for the activities of a user, which widens the
+/// fields of the first version and adds the kind of the activity.
+#[db_key(path = attrib, version = 2, previous = ActivityKeyV1, schema_json, proptest,
+    arbitrary, pattern, layout_snapshot = "tests/snapshots/activity_key.txt")]
+pub struct ActivityKey {
+    /// The user of the activity.
+    #[name = "User"]
+    user: u64,
+    /// The tag of the activity.
+    #[name = "Tag"]
+    tag: [u8; 4],
+    /// The optional parent activity.
+    #[name = "Parent"]
+    parent: Option<u32>,
+    /// The owner of the user.
+    #[name = "Owner"]
+    owner: NonZeroU64,
+    /// The kind of the activity, which is 7 for the activities of the first version.
+    #[name = "Kind"]
+    #[default = 7]
+    kind: u8,
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -310,10 +721,10 @@ mod tests {
             ends in proptest::collection::btree_set(
                 proptest::collection::vec(0..=u8::MAX, 3..=3), 2..=2),
         ) {
-            let id: Vec<u64> = ids.iter().map(|x| *x).collect();
-            let word: Vec<u16> = words.iter().map(|x| *x).collect();
-            let byte: Vec<u8> = bytes.iter().map(|x| *x).collect();
-            let long: Vec<u32> = longs.iter().map(|x| *x).collect();
+            let id: Vec<u64> = ids.iter().copied().collect();
+            let word: Vec<u16> = words.iter().copied().collect();
+            let byte: Vec<u8> = bytes.iter().copied().collect();
+            let long: Vec<u32> = longs.iter().copied().collect();
             let end: Vec<[u8; 3]> = ends.iter().map(|x| {
                 let mut buf = [0_u8; 3];
                 buf.copy_from_slice(x);
@@ -331,9 +742,1405 @@ mod tests {
             let mut key_from_array = Vec::with_capacity(2);
             for i in 0..2 {
                 new_key.push(SampleKey::new(id[i], word[i], byte[i], long[i], end[i]));
-                key_from_array.push(SampleKey::from(array[i].clone()));
+                key_from_array.push(SampleKey::from(array[i]));
                 assert_eq!(new_key[i], key_from_array[i]);
             }
         }
+
+        #[test]
+        fn narrow_tests(
+            ids in proptest::collection::btree_set(0..=0xFFFF_FFFF_FFFF_u64, 2..=2),
+            offsets in proptest::collection::btree_set(-0x80_0000..=0x7F_FFFF_i32, 2..=2),
+            saturated in i64::MIN..=i64::MAX,
+            counter in 0..=u32::MAX,
+        ) {
+            let id: Vec<u64> = ids.iter().copied().collect();
+            let offset: Vec<i32> = offsets.iter().copied().collect();
+            let first = NarrowKey::new(id[0], offset[1], saturated, counter);
+            let second = NarrowKey::new(id[1], offset[0], saturated, counter);
+            assert!(first < second);
+            let first = NarrowKey::new(id[0], offset[0], saturated, counter);
+            let second = NarrowKey::new(id[0], offset[1], saturated, counter);
+            assert!(first < second);
+            assert_eq!(first.id(), id[0]);
+            assert_eq!(first.offset(), offset[0]);
+            assert_eq!(second.offset(), offset[1]);
+            assert_eq!(first.saturated(), saturated.clamp(-0x80_0000_0000, 0x7F_FFFF_FFFF));
+            assert_eq!(first.counter(), counter & 0xFF_FFFF);
+            assert_eq!(first.as_ref().len(), NarrowKey::KEY_LENGTH);
+        }
+    }
+
+    #[test]
+    fn narrow_limits() {
+        assert_eq!(NarrowKey::KEY_LENGTH, 17);
+        assert_eq!(NarrowKey::MIN_KEY.offset(), -0x80_0000);
+        assert_eq!(NarrowKey::MAX_KEY.offset(), 0x7F_FFFF);
+        assert_eq!(NarrowKey::MAX_KEY.id(), 0xFFFF_FFFF_FFFF);
+        assert_eq!(NarrowKey::DEFAULT_KEY.id(), 0x1234_5678_9ABC);
+        assert_eq!(&NarrowKey::MIN_KEY.as_ref()[..9], &[0; 9]);
+        assert_eq!(&NarrowKey::MAX_KEY.as_ref()[..9], &[0xFF; 9]);
+    }
+
+    proptest! {
+        #[test]
+        fn bits_tests(
+            priorities in proptest::collection::btree_set(0..=0xF_u8, 2..=2),
+            shards in proptest::collection::btree_set(0..=0xFFF_u16, 2..=2),
+            adjusts in proptest::collection::btree_set(-4..=3_i8, 2..=2),
+            id in 0..=u32::MAX,
+            level in i16::MIN..=i16::MAX,
+        ) {
+            let priority: Vec<u8> = priorities.iter().copied().collect();
+            let shard: Vec<u16> = shards.iter().copied().collect();
+            let adjust: Vec<i8> = adjusts.iter().copied().collect();
+            let first = BitsKey::new(priority[0], shard[1], adjust[1], id, level);
+            let second = BitsKey::new(priority[1], shard[0], adjust[0], id, level);
+            assert!(first < second);
+            let first = BitsKey::new(priority[0], shard[0], adjust[1], id, level);
+            let second = BitsKey::new(priority[0], shard[1], adjust[0], id, level);
+            assert!(first < second);
+            let first = BitsKey::new(priority[0], shard[0], adjust[0], id, level);
+            let mut second = BitsKey::new(priority[0], shard[0], adjust[1], id, level);
+            assert!(first < second);
+            assert_eq!(first.priority(), priority[0]);
+            assert_eq!(first.shard(), shard[0]);
+            assert_eq!(first.adjust(), adjust[0]);
+            assert_eq!(first.id(), id);
+            assert_eq!(first.level(), level.clamp(-0x200, 0x1FF));
+            second.set_shard(shard[1]);
+            second.set_priority(priority[1]);
+            second.set_adjust(adjust[0]);
+            assert_eq!(second.priority(), priority[1]);
+            assert_eq!(second.shard(), shard[1]);
+            assert_eq!(second.adjust(), adjust[0]);
+            assert_eq!(second.id(), id);
+        }
+    }
+
+    #[test]
+    fn bits_layout() {
+        assert_eq!(BitsKey::KEY_LENGTH, 9);
+        assert_eq!(BitsKey::SHARD_BIT_OFFSET, 4);
+        assert_eq!(BitsKey::SHARD_BITS, 12);
+        assert_eq!(BitsKey::ADJUST_RANGE, 2..3);
+        assert_eq!(BitsKey::ID_START, 3);
+        assert_eq!(BitsKey::LEVEL_BIT_OFFSET, 56);
+        assert_eq!(BitsKey::MIN_KEY.adjust(), -4);
+        assert_eq!(BitsKey::MAX_KEY.level(), 0x1FF);
+        assert_eq!(BitsKey::MIN_KEY.as_ref(), &[0; 9]);
+        assert_eq!(BitsKey::MAX_KEY.as_ref(), &[0xFF, 0xFF, 0xE0, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xC0]);
+        let key = BitsKey::new(0xA, 0x123, -1, 0x456789AB, -2);
+        assert_eq!(key.as_ref(), &[0xA1, 0x23, 0x60, 0x45, 0x67, 0x89, 0xAB, 0x7F, 0x80]);
+        assert_eq!(&format!("{:?}", key), concat!("BitsKey { priority: 10, shard: 291, ",
+            "adjust: -1, id: 1164413355, level: -2, ",
+            "raw: 0b1010_000100100011_011_00000_0x456789AB_0b0111111110_000000 }"));
+    }
+
+    #[test]
+    #[should_panic]
+    fn bits_overflow() {
+        let mut key = BitsKey::default();
+        key.set_priority(0x10);
+    }
+
+    #[test]
+    #[should_panic]
+    fn narrow_overflow() {
+        let mut key = NarrowKey::default();
+        key.set_id(0x1_0000_0000_0000);
+    }
+
+    proptest! {
+        #[test]
+        fn option_tests(
+            parents in proptest::collection::btree_set(proptest::option::of(0..=u64::MAX), 2..=2),
+            kind in proptest::option::of(i8::MIN..=i8::MAX),
+            tags in proptest::collection::btree_set(proptest::array::uniform3(0..=u8::MAX), 2..=2),
+            offset in proptest::option::of(-0x80_0000..=0x7F_FFFF_i32),
+            id in 0..=u16::MAX,
+        ) {
+            let parent: Vec<Option<u64>> = parents.iter().copied().collect();
+            let tag: Vec<[u8; 3]> = tags.iter().copied().collect();
+            let first = OptionKey::new(parent[0], kind, Some(tag[1]), offset, id);
+            let second = OptionKey::new(parent[1], kind, Some(tag[0]), offset, id);
+            assert!(first < second);
+            let first = OptionKey::new(parent[0], kind, Some(tag[1]), offset, id);
+            let second = OptionKey::new(parent[0], kind, None, offset, id);
+            assert!(first < second);
+            let first = OptionKey::new(parent[0], kind, Some(tag[0]), offset, id);
+            let mut second = OptionKey::new(parent[0], kind, Some(tag[1]), offset, id);
+            assert!(first < second);
+            assert_eq!(first.parent(), parent[0]);
+            assert_eq!(first.kind(), kind);
+            assert_eq!(first.tag(), Some(tag[0]));
+            assert_eq!(first.offset(), offset);
+            assert_eq!(first.id(), id);
+            second.set_tag(None);
+            second.set_kind(None);
+            second.set_offset(None);
+            assert_eq!(second, OptionKey::new(parent[0], None, None, None, id));
+        }
+    }
+
+    #[test]
+    fn option_layout() {
+        assert_eq!(OptionKey::KEY_LENGTH, 21);
+        assert_eq!(OptionKey::PARENT_RANGE, 0..9);
+        assert_eq!(OptionKey::PARENT_VALUE_RANGE, 1..9);
+        assert_eq!(OptionKey::TAG_VALUE_RANGE, 12..15);
+        assert_eq!(OptionKey::OFFSET_SIZE, 4);
+        assert_eq!(OptionKey::DEFAULT_KEY, OptionKey::default());
+        assert_eq!(OptionKey::MIN_KEY.parent(), None);
+        assert_eq!(OptionKey::MIN_KEY.tag(), Some([0; 3]));
+        assert_eq!(OptionKey::MAX_KEY.parent(), Some(u64::MAX));
+        assert_eq!(OptionKey::MAX_KEY.tag(), None);
+        assert_eq!(OptionKey::MAX_KEY.offset(), Some(0x7F_FFFF));
+        assert_eq!(OptionKey::MAX_KEY.as_ref(),
+            &[1, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 1, 0xFF, 1, 0, 0, 0,
+                1, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF]);
+        let key = OptionKey::new(Some(0x1234), Some(-1), None, Some(-2), 0xABCD);
+        assert_eq!(key.as_ref(), &[1, 0, 0, 0, 0, 0, 0, 0x12, 0x34, 1, 0x7F, 1, 0, 0, 0,
+            1, 0x7F, 0xFF, 0xFE, 0xAB, 0xCD]);
+        assert_eq!(&format!("{:?}", key), concat!("OptionKey { parent: Some(4660), ",
+            "kind: Some(-1), tag: None, offset: Some(-2), id: 43981, ",
+            "raw: 0x01_0000000000001234_01_7F_01_000000_01_7FFFFE_ABCD }"));
+        let mut key = OptionKey::from(OptionKeyArgs {
+            tag: Some(*b"abc"),
+            ..Default::default()
+        });
+        assert_eq!(key.tag(), Some(*b"abc"));
+        key.set_tag(None);
+        assert_eq!(key, OptionKey::default());
+    }
+
+    proptest! {
+        #[test]
+        fn flow_tests(
+            tenant in 0..=u16::MAX,
+            sources in proptest::collection::btree_set(any::<Ipv4Addr>(), 2..=2),
+            destination in any::<Ipv6Addr>(),
+            clients in proptest::collection::btree_set(any::<SocketAddrV4>(), 2..=2),
+            servers in proptest::collection::btree_set(any::<SocketAddrV6>(), 2..=2),
+            gateway in proptest::option::of(any::<Ipv4Addr>()),
+        ) {
+            let source: Vec<Ipv4Addr> = sources.iter().copied().collect();
+            let client: Vec<SocketAddrV4> = clients.iter().copied().collect();
+            let server: Vec<SocketAddrV6> = servers.iter().copied().collect();
+            let first = FlowKey::new(tenant, source[0], destination, client[1], server[1], gateway);
+            let second = FlowKey::new(tenant, source[1], destination, client[0], server[0], gateway);
+            assert!(first < second);
+            let first = FlowKey::new(tenant, source[0], destination, client[0], server[1], gateway);
+            let second = FlowKey::new(tenant, source[0], destination, client[1], server[0], gateway);
+            assert!(first < second);
+            let first = FlowKey::new(tenant, source[0], destination, client[0], server[0], gateway);
+            let mut second = FlowKey::new(tenant, source[0], destination, client[1], server[1],
+                gateway);
+            assert!(first < second);
+            assert_eq!(first.tenant(), tenant);
+            assert_eq!(first.source(), source[0]);
+            assert_eq!(first.destination(), destination);
+            assert_eq!(first.client(), client[0]);
+            assert_eq!(first.server(), server[0]);
+            assert_eq!(first.gateway(), gateway);
+            second.set_client(client[0]);
+            second.set_server(server[0]);
+            assert_eq!(first, second);
+            let range = first.source_prefix_range(16);
+            assert!(range.contains(&first));
+            let octets = source[1].octets();
+            let other = FlowKey::new(tenant, Ipv4Addr::new(octets[0], octets[1], 0, 0), destination,
+                client[0], server[0], gateway);
+            assert_eq!(range.contains(&other), source[0].octets()[..2] == octets[..2]);
+        }
+    }
+
+    #[test]
+    fn flow_layout() {
+        assert_eq!(FlowKey::KEY_LENGTH, 59);
+        assert_eq!(FlowKey::SOURCE_RANGE, 2..6);
+        assert_eq!(FlowKey::DESTINATION_RANGE, 6..22);
+        assert_eq!(FlowKey::CLIENT_RANGE, 22..28);
+        assert_eq!(FlowKey::SERVER_RANGE, 28..54);
+        assert_eq!(FlowKey::GATEWAY_VALUE_RANGE, 55..59);
+        assert_eq!(FlowKey::MIN_KEY.source(), Ipv4Addr::UNSPECIFIED);
+        assert_eq!(FlowKey::MAX_KEY.source(), Ipv4Addr::BROADCAST);
+        assert_eq!(FlowKey::MAX_KEY.server().scope_id(), u32::MAX);
+        assert_eq!(FlowKey::MAX_KEY.gateway(), Some(Ipv4Addr::BROADCAST));
+        assert_eq!(&FlowKey::MAX_KEY.as_ref()[..54], &[0xFF; 54]);
+        let key = FlowKey::new(7, Ipv4Addr::new(10, 1, 2, 3), Ipv6Addr::LOCALHOST,
+            SocketAddrV4::new(Ipv4Addr::new(192, 168, 0, 1), 8080),
+            SocketAddrV6::new(Ipv6Addr::LOCALHOST, 443, 1, 2), None);
+        assert_eq!(&key.as_ref()[..6], &[0, 7, 10, 1, 2, 3]);
+        assert_eq!(&key.as_ref()[22..28], &[192, 168, 0, 1, 0x1F, 0x90]);
+        assert_eq!(&key.as_ref()[44..54], &[0x01, 0xBB, 0, 0, 0, 1, 0, 0, 0, 2]);
+        let range = key.source_prefix_range(8);
+        assert_eq!(range.start().source(), Ipv4Addr::new(10, 0, 0, 0));
+        assert_eq!(range.end().source(), Ipv4Addr::new(10, 255, 255, 255));
+        assert_eq!(range.start().tenant(), 7);
+        assert_eq!(&range.start().as_ref()[6..], &[0; 53]);
+        assert_eq!(&range.end().as_ref()[6..], &[0xFF; 53]);
+        let range = key.client_prefix_range(32);
+        assert_eq!(range.start().client(), SocketAddrV4::new(Ipv4Addr::new(192, 168, 0, 1), 0));
+        assert_eq!(range.end().client(),
+            SocketAddrV4::new(Ipv4Addr::new(192, 168, 0, 1), u16::MAX));
+        let range = key.destination_prefix_range(0);
+        assert_eq!(range.start().destination(), Ipv6Addr::UNSPECIFIED);
+    }
+
+    #[test]
+    #[should_panic]
+    fn flow_prefix_too_long() {
+        let _ = FlowKey::default().source_prefix_range(33);
+    }
+
+    proptest! {
+        #[test]
+        fn event_tests(
+            times in proptest::collection::btree_set(0..=u64::MAX, 2..=2),
+            updated in 0..=0xFFFF_FFFF_FFFF_u64,
+            ttl in 0..=u32::MAX as u64,
+            expires in proptest::option::of(0..=0xFF_FFFF_FFFF_u64),
+        ) {
+            let time: Vec<SystemTime> = times.iter()
+                .map(|time| SystemTime::UNIX_EPOCH + Duration::from_nanos(*time))
+                .collect();
+            let updated = SystemTime::UNIX_EPOCH + Duration::from_millis(updated);
+            let ttl = Duration::from_secs(ttl);
+            let expires = expires.map(|expires| SystemTime::UNIX_EPOCH + Duration::from_secs(expires));
+            let first = EventKey::new(time[0], updated, ttl, expires);
+            let mut second = EventKey::new(time[1], updated, ttl, expires);
+            assert!(first < second);
+            assert_eq!(first.time(), time[0]);
+            assert_eq!(first.updated(), updated);
+            assert_eq!(first.ttl(), ttl);
+            assert_eq!(first.expires(), expires);
+            second.set_time(time[0]);
+            assert_eq!(first, second);
+        }
+    }
+
+    #[test]
+    fn event_layout() {
+        assert_eq!(EventKey::KEY_LENGTH, 24);
+        assert_eq!(EventKey::UPDATED_RANGE, 8..14);
+        assert_eq!(EventKey::TTL_MAX, Duration::from_secs(u32::MAX as u64));
+        assert_eq!(EventKey::EXPIRES_MAX, Some(Duration::from_secs(0xFF_FFFF_FFFF)));
+        assert_eq!(EventKey::MIN_KEY.time(), SystemTime::UNIX_EPOCH);
+        assert_eq!(EventKey::MAX_KEY.ttl(), Duration::from_secs(u32::MAX as u64));
+        assert_eq!(&EventKey::MAX_KEY.as_ref()[..18], &[0xFF; 18]);
+        assert_eq!(&EventKey::MAX_KEY.as_ref()[18..], &[1, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF]);
+        let key = EventKey::new(SystemTime::UNIX_EPOCH + Duration::new(1, 5),
+            SystemTime::UNIX_EPOCH + Duration::from_micros(1_500_999), Duration::from_secs(u64::MAX),
+            Some(SystemTime::UNIX_EPOCH + Duration::from_millis(2_999)));
+        assert_eq!(key.time(), SystemTime::UNIX_EPOCH + Duration::new(1, 5));
+        // Times are rounded down to the time unit.
+        assert_eq!(key.updated(), SystemTime::UNIX_EPOCH + Duration::from_millis(1_500));
+        assert_eq!(key.ttl(), Duration::from_secs(u32::MAX as u64));
+        assert_eq!(key.expires(), Some(SystemTime::UNIX_EPOCH + Duration::from_secs(2)));
+        assert_eq!(key.as_ref(), &[0, 0, 0, 0, 0x3B, 0x9A, 0xCA, 0x05, 0, 0, 0, 0, 0x05, 0xDC,
+            0xFF, 0xFF, 0xFF, 0xFF, 1, 0, 0, 0, 0, 2]);
+    }
+
+    #[test]
+    #[should_panic]
+    fn event_before_epoch() {
+        let mut key = EventKey::default();
+        key.set_time(SystemTime::UNIX_EPOCH - Duration::from_secs(1));
+    }
+
+    #[test]
+    #[should_panic]
+    fn event_overflow() {
+        let mut key = EventKey::default();
+        key.set_updated(SystemTime::UNIX_EPOCH + Duration::from_millis(0x1_0000_0000_0000));
+    }
+
+    proptest! {
+        #[test]
+        fn record_tests(
+            tables in proptest::collection::btree_set(0..=u128::MAX, 2..=2),
+            parent in proptest::option::of(0..=u128::MAX),
+            number in 0..=u32::MAX,
+        ) {
+            let table: Vec<Uuid> = tables.iter().map(|table| Uuid::from_u128(*table)).collect();
+            let parent = parent.map(Uuid::from_u128);
+            let first = RecordKey::new(table[0], parent, number);
+            let mut second = RecordKey::new(table[1], parent, number);
+            assert!(first < second);
+            assert_eq!(first.table(), table[0]);
+            assert_eq!(first.parent(), parent);
+            assert_eq!(first.number(), number);
+            second.set_table(table[0]);
+            assert_eq!(first, second);
+        }
+    }
+
+    #[test]
+    fn record_layout() {
+        assert_eq!(RecordKey::KEY_LENGTH, 37);
+        assert_eq!(RecordKey::TABLE_RANGE, 0..16);
+        assert_eq!(RecordKey::PARENT_VALUE_RANGE, 17..33);
+        assert_eq!(RecordKey::MIN_KEY.table(), Uuid::nil());
+        assert_eq!(RecordKey::MAX_KEY.table(), Uuid::from_u128(u128::MAX));
+        let table = Uuid::parse_str("01890a5d-ac96-774b-bcce-b302099a8057").unwrap();
+        let key = RecordKey::new(table, None, 7);
+        assert_eq!(&key.as_ref()[..4], &[0x01, 0x89, 0x0A, 0x5D]);
+        assert!(format!("{:?}", key).starts_with(concat!("RecordKey { table: ",
+            "01890a5d-ac96-774b-bcce-b302099a8057, parent: None, number: 7, ")));
+    }
+
+    proptest! {
+        #[test]
+        fn ledger_tests(
+            account in 0..=u32::MAX,
+            balances in proptest::collection::btree_set(i64::MIN..=i64::MAX, 2..=2),
+            label in proptest::option::of("[ -~]{0,8}"),
+        ) {
+            let balance: Vec<Cents> = balances.iter().map(|balance| Cents(*balance)).collect();
+            let label = label.map(Label);
+            let first = LedgerKey::new(account, balance[0], label.clone());
+            let mut second = LedgerKey::from(LedgerKeyArgs {
+                account,
+                balance: balance[1],
+                label: label.clone(),
+            });
+            assert!(first < second);
+            assert_eq!(first.account(), account);
+            assert_eq!(first.balance(), balance[0]);
+            assert_eq!(first.label(), label);
+            second.set_balance(balance[0]);
+            assert_eq!(first, second);
+        }
+    }
+
+    #[test]
+    fn ledger_layout() {
+        assert_eq!(LedgerKey::KEY_LENGTH, 21);
+        assert_eq!(LedgerKey::BALANCE_RANGE, 4..12);
+        assert_eq!(LedgerKey::LABEL_VALUE_RANGE, 13..21);
+        assert_eq!(LedgerKey::MIN_KEY.balance(), Cents(i64::MIN));
+        assert_eq!(LedgerKey::MAX_KEY.balance(), Cents(i64::MAX));
+        assert_eq!(LedgerKey::MIN_KEY.label(), None);
+        assert_eq!(LedgerKey::MAX_KEY.label(), Some(Label("~~~~~~~~".to_string())));
+        assert_eq!(LedgerKeyArgs::default().balance, Cents(i64::MIN));
+        let mut key = LedgerKey::new(7, Cents(-1), Some(Label("rent".to_string())));
+        assert_eq!(&key.as_ref()[4..12], &[0x7F, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF]);
+        assert_eq!(&key.as_ref()[12..], b"\x01rent\0\0\0\0");
+        key.set_label(None);
+        assert_eq!(&key.as_ref()[12..], &[0_u8; 9]);
+        assert!(format!("{:?}", key).starts_with(
+            "LedgerKey { account: 7, balance: Cents(-1), label: None, "));
+    }
+
+    proptest! {
+        #[test]
+        fn member_tests(
+            region in proptest::array::uniform2(0..=u8::MAX),
+            group in proptest::option::of(0..=0xFF_FFFF_u32),
+            users in proptest::collection::btree_set(0..=u64::MAX, 2..=2),
+        ) {
+            let user: Vec<UserId> = users.iter().map(|user| UserId(*user)).collect();
+            let region = Region(region);
+            let group = group.map(GroupId);
+            let first = MemberKey::new(region.clone(), group, user[0]);
+            let mut second = MemberKey::from(&MemberKeyArgs {
+                region: region.clone(),
+                group,
+                user: user[1],
+            });
+            assert!(first < second);
+            assert_eq!(first.region(), region);
+            assert_eq!(first.group(), group);
+            assert_eq!(first.user(), user[0]);
+            second.set_user(user[0]);
+            assert_eq!(first, second);
+        }
+    }
+
+    #[test]
+    fn member_layout() {
+        assert_eq!(MemberKey::KEY_LENGTH, 14);
+        assert_eq!(MemberKey::GROUP_VALUE_RANGE, 3..6);
+        assert_eq!(MemberKey::USER_DEFAULT, 1);
+        assert_eq!(MemberKey::default().user(), UserId(1));
+        assert_eq!(MemberKeyArgs::default().user, UserId(1));
+        assert_eq!(MemberKey::MAX_KEY.group(), Some(GroupId(0xFF_FFFF)));
+        let mut key = MemberKey::new(Region(*b"US"), Some(GroupId(0x123456)), UserId(7));
+        assert_eq!(key.as_ref(), &[b'U', b'S', 1, 0x12, 0x34, 0x56, 0, 0, 0, 0, 0, 0, 0, 7]);
+        key.set_group(None);
+        key.set_region(Region(*b"CA"));
+        assert_eq!(&key.as_ref()[..6], &[b'C', b'A', 0, 0, 0, 0]);
+        assert!(format!("{:?}", key).starts_with(
+            "MemberKey { region: Region([67, 65]), group: None, user: UserId(7), "));
+    }
+
+    proptest! {
+        #[test]
+        fn word_tests(
+            initials in proptest::collection::btree_set(any::<char>(), 2..=2),
+            letters in proptest::array::uniform3(any::<char>()),
+            last in proptest::option::of(any::<char>()),
+        ) {
+            let initial: Vec<char> = initials.iter().copied().collect();
+            let first = WordKey::new(initial[0], letters, last);
+            let mut second = WordKey::new(initial[1], letters, last);
+            assert!(first < second);
+            assert_eq!(first.initial(), initial[0]);
+            assert_eq!(first.letters(), letters);
+            assert_eq!(first.last(), last);
+            assert_eq!(first.try_letters(), Ok(letters));
+            second.set_initial(initial[0]);
+            assert_eq!(first, second);
+        }
+    }
+
+    #[test]
+    fn word_layout() {
+        assert_eq!(WordKey::KEY_LENGTH, 17);
+        assert_eq!(WordKey::LETTERS_RANGE, 4..13);
+        assert_eq!(WordKey::MAX_KEY.letters(), [char::MAX; 3]);
+        let key = WordKey::new('d', ['o', 'g', '\u{1F415}'], Some('!'));
+        assert_eq!(key.as_ref(), &[0, 0, 0, b'd', 0, 0, b'o', 0, 0, b'g', 0x01, 0xF4, 0x15, 1,
+            0, 0, b'!']);
+        let mut raw = [0_u8; WordKey::KEY_LENGTH];
+        raw.copy_from_slice(key.as_ref());
+        raw[4..7].copy_from_slice(&[0x11, 0x00, 0x00]);
+        raw[14..17].copy_from_slice(&[0x00, 0xD8, 0x00]);
+        let invalid = WordKey::from(raw);
+        assert!(invalid.try_letters().is_err());
+        assert_eq!(invalid.letters(), [char::REPLACEMENT_CHARACTER, 'g', '\u{1F415}']);
+        assert!(invalid.try_last().is_err());
+        assert_eq!(invalid.try_initial(), Ok('d'));
+    }
+
+    proptest! {
+        #[test]
+        fn login_tests(
+            user in proptest::array::uniform8(any::<u8>()),
+            domain in proptest::option::of(proptest::array::uniform6(any::<u8>())),
+            tag in proptest::array::uniform2(any::<u8>()),
+        ) {
+            let key = LoginKey::new(user, domain, tag);
+            assert_eq!(key.user(), user.to_ascii_lowercase());
+            assert_eq!(key.tag(), tag);
+            let mut upper = user;
+            upper.make_ascii_uppercase();
+            let mut other = LoginKey::new(upper, domain, tag);
+            assert_eq!(key, other);
+            other.set_user(user);
+            assert_eq!(key, other);
+            assert_eq!(key, LoginKey::from(LoginKeyArgs { user: upper, domain, tag }));
+        }
+    }
+
+    #[test]
+    fn login_layout() {
+        assert_eq!(LoginKey::KEY_LENGTH, 17);
+        let alice = LoginKey::new(*b"Alice\0\0\0", Some(*b" Ex.IO"), *b"Ab");
+        assert_eq!(alice, LoginKey::new(*b"ALICE\0\0\0", Some(*b"ex.io "), *b"Ab"));
+        assert_ne!(alice, LoginKey::new(*b"alice\0\0\0", Some(*b"ex.io\0"), *b"ab"));
+        assert_eq!(&alice.user(), b"alice\0\0\0");
+        assert_eq!(alice.domain(), Some(*b"ex.io\0"));
+        let mut key = alice;
+        key.set_domain(Some(*b"\tA B\n "));
+        assert_eq!(key.domain(), Some(*b"a b\0\0\0"));
+        key.set_domain(None);
+        assert_eq!(key.domain(), None);
+        assert_eq!(LoginKey::MAX_KEY.user(), [0xFF; 8]);
+    }
+
+    proptest! {
+        #[test]
+        fn amount_tests(
+            amounts in proptest::collection::btree_set(any::<i64>(), 2..=2),
+            rate in 0..=0xFF_FFFF_u32,
+            entry: u32,
+        ) {
+            let amount: Vec<i64> = amounts.iter().copied().collect();
+            let first = AmountKey::new(amount[0], rate, entry);
+            let mut second = AmountKey::new(amount[1], rate, entry);
+            assert!(first < second);
+            assert_eq!(first.amount_decimal().value(), amount[0] as i128);
+            second.set_amount_str(&first.amount_string()).unwrap();
+            assert_eq!(first, second);
+            second.set_rate_str(&first.rate_string()).unwrap();
+            assert_eq!(second.rate(), rate);
+        }
+    }
+
+    #[test]
+    fn amount_layout() {
+        assert_eq!(AmountKey::KEY_LENGTH, 15);
+        assert_eq!(AmountKey::AMOUNT_SCALE, 4);
+        assert_eq!(AmountKey::RATE_SCALE, 2);
+        let mut key = AmountKey::new(-123_400, 150, 7);
+        assert_eq!(key.amount_string(), "-12.3400");
+        assert_eq!(key.amount_f64(), -12.34);
+        assert_eq!(key.rate_string(), "1.50");
+        assert!(format!("{:?}", key).starts_with(
+            "AmountKey { amount: -12.3400, rate: 1.50, entry: 7, "));
+        key.set_amount_str("5.25").unwrap();
+        assert_eq!(key.amount(), 52_500);
+        assert_eq!(key.set_amount_str("5.00001"), Err(db_key::ParseDecimalError::TooPrecise));
+        assert_eq!(key.set_amount_str("-x"), Err(db_key::ParseDecimalError::InvalidDigit));
+        assert_eq!(key.set_rate_str("-1"), Err(db_key::ParseDecimalError::OutOfRange));
+        assert_eq!(key.amount(), 52_500);
+        key.set_amount_f64(-0.00016);
+        assert_eq!(key.amount(), -2);
+        key.set_amount_f64(f64::INFINITY);
+        assert_eq!(key.amount(), i64::MAX);
+        key.set_rate_f64(2.346);
+        assert_eq!(key.rate(), 235);
+    }
+
+    #[test]
+    fn amount_narrowed_bounds() {
+        let mut key = AmountKey::new(0, 150, 7);
+        key.set_rate_f64(1e12);
+        assert_eq!(key.rate(), 0xFF_FFFF);
+        key.set_rate_f64(-3.5);
+        assert_eq!(key.rate(), 0);
+        key.set_rate_f64(f64::INFINITY);
+        assert_eq!(key.rate(), 0xFF_FFFF);
+        key.set_rate_f64(f64::NAN);
+        assert_eq!(key.rate(), 0);
+        assert_eq!(key.set_rate_str("167772.15"), Ok(()));
+        assert_eq!(key.rate(), 0xFF_FFFF);
+        assert_eq!(key.set_rate_str("167772.16"), Err(db_key::ParseDecimalError::OutOfRange));
+        assert_eq!(key.set_rate_str("42949672.95"), Err(db_key::ParseDecimalError::OutOfRange));
+        assert_eq!(key.rate(), 0xFF_FFFF);
+        assert_eq!(key.entry(), 7);
+    }
+
+    proptest! {
+        #[test]
+        fn order_tests(
+            customers in proptest::collection::btree_set(1..=u64::MAX, 2..=2),
+            parent in proptest::option::of(1..=0xFFFF_FFFF_FFFF_u64),
+            delta in any::<i16>().prop_filter("non-zero", |delta| 0 != *delta),
+        ) {
+            let customer: Vec<NonZeroU64> = customers.iter()
+                .map(|customer| NonZeroU64::new(*customer).unwrap()).collect();
+            let parent = parent.map(|parent| NonZeroU64::new(parent).unwrap());
+            let delta = NonZeroI16::new(delta).unwrap();
+            let first = OrderKey::new(customer[0], parent, delta);
+            let mut second = OrderKey::new(customer[1], parent, delta);
+            assert!(first < second);
+            assert_eq!(first.customer(), Some(customer[0]));
+            assert_eq!(first.parent(), parent);
+            assert_eq!(first.delta(), Some(delta));
+            second.set_customer(customer[0]);
+            assert_eq!(first, second);
+        }
+    }
+
+    #[test]
+    fn order_layout() {
+        assert_eq!(OrderKey::KEY_LENGTH, 16);
+        assert_eq!(OrderKey::CUSTOMER_MIN, 1);
+        assert_eq!(OrderKey::MIN_KEY.customer(), NonZeroU64::new(1));
+        assert_eq!(OrderKey::MIN_KEY.parent(), None);
+        assert_eq!(OrderKey::MIN_KEY.delta(), NonZeroI16::new(i16::MIN));
+        assert_eq!(OrderKey::default().customer(), NonZeroU64::new(1));
+        assert_eq!(OrderKey::default().delta(), NonZeroI16::new(1));
+        assert_eq!(OrderKeyArgs::default().parent, None);
+        let mut key = OrderKey::new(NonZeroU64::new(5).unwrap(), None,
+            NonZeroI16::new(-1).unwrap());
+        assert_eq!(key.as_ref(), &[0, 0, 0, 0, 0, 0, 0, 5, 0, 0, 0, 0, 0, 0, 0x7F, 0xFF]);
+        key.set_parent(NonZeroU64::new(0x1234));
+        assert_eq!(&key.as_ref()[8..14], &[0, 0, 0, 0, 0x12, 0x34]);
+        let zero = OrderKey::from([0_u8; OrderKey::KEY_LENGTH]);
+        assert_eq!(zero.customer(), None);
+        assert_eq!(zero.parent(), None);
+        assert_eq!(zero.delta(), NonZeroI16::new(i16::MIN));
+    }
+
+    #[test]
+    fn adjustment_order() {
+        let values = [None, Some(i32::MIN + 1), Some(-5), Some(-1), Some(1), Some(5),
+            Some(i32::MAX)];
+        let keys: Vec<AdjustmentKey> = values.iter()
+            .map(|value| AdjustmentKey::new(7, value.and_then(NonZeroI32::new), None, None))
+            .collect();
+        for (key, value) in keys.iter().zip(values.iter()) {
+            assert_eq!(key.adjustment().map(NonZeroI32::get), *value);
+        }
+        assert!(keys.windows(2).all(|pair| pair[0] < pair[1]));
+        assert_eq!(&keys[0].as_ref()[AdjustmentKey::ADJUSTMENT_RANGE], &[0; 4]);
+        let steps = [None, Some(-7), Some(-1), Some(1), Some(7)];
+        let keys: Vec<AdjustmentKey> = steps.iter()
+            .map(|step| AdjustmentKey::new(7, None, None, step.and_then(NonZeroI8::new)))
+            .collect();
+        for (key, step) in keys.iter().zip(steps.iter()) {
+            assert_eq!(key.step().map(NonZeroI8::get), *step);
+        }
+        assert!(keys.windows(2).all(|pair| pair[0] < pair[1]));
+        assert_eq!(AdjustmentKey::MIN_KEY.adjustment(), None);
+        assert_eq!(AdjustmentKey::MIN_KEY.correction(), None);
+        assert_eq!(AdjustmentKey::MIN_KEY.step(), None);
+        assert_eq!(AdjustmentKey::default().as_ref(), &[0; AdjustmentKey::KEY_LENGTH]);
+        let zero = AdjustmentKey::from([0_u8; AdjustmentKey::KEY_LENGTH]);
+        assert_eq!(zero.adjustment(), None);
+        assert_eq!(zero.correction(), None);
+        assert_eq!(zero.step(), None);
+    }
+
+    #[test]
+    #[should_panic(expected = "doesn't fit in the Adjustment field")]
+    fn adjustment_minimum() {
+        let mut key = AdjustmentKey::default();
+        key.set_adjustment(NonZeroI32::new(i32::MIN));
+    }
+
+    #[test]
+    #[should_panic(expected = "doesn't fit in the Step field")]
+    fn adjustment_step_minimum() {
+        let mut key = AdjustmentKey::default();
+        key.set_step(NonZeroI8::new(-8));
+    }
+
+    #[test]
+    fn adjustment_saturate() {
+        let mut key = AdjustmentKey::default();
+        key.set_correction(NonZeroI32::new(i32::MIN));
+        assert_eq!(key.correction(), NonZeroI32::new(-0x7F_FFFF));
+        key.set_correction(NonZeroI32::new(-0x80_0000));
+        assert_eq!(key.correction(), NonZeroI32::new(-0x7F_FFFF));
+        key.set_correction(NonZeroI32::new(i32::MAX));
+        assert_eq!(key.correction(), NonZeroI32::new(0x7F_FFFF));
+        key.set_correction(None);
+        assert_eq!(key.correction(), None);
+        assert_eq!(&key.as_ref()[AdjustmentKey::CORRECTION_RANGE], &[0; 3]);
+    }
+
+    #[test]
+    fn truncated_nonzero() {
+        // The values keep their low bytes, and signed values are sign extended.
+        let key = TruncatedKey::new(NonZeroI32::new(0x0180_0001), NonZeroU32::new(0x0100_0001),
+            NonZeroU32::new(0x1_0002).unwrap());
+        assert_eq!(key.signed(), NonZeroI32::new(-0x7F_FFFF));
+        assert_eq!(key.unsigned(), NonZeroU32::new(1));
+        assert_eq!(key.required(), NonZeroU32::new(2));
+        let key = TruncatedKey::new(None, None, NonZeroU32::new(1).unwrap());
+        assert_eq!(&key.as_ref()[..TruncatedKey::REQUIRED_START], &[0; 6]);
+        // Values truncated to zero bytes don't fit.
+        let pattern = TruncatedKeyPattern { unsigned: Some(NonZeroU32::new(0x0100_0000)),
+            ..Default::default() };
+        assert!(!pattern.matches(&key));
+        let pattern = TruncatedKeyPattern { signed: Some(NonZeroI32::new(0x0100_0000)),
+            ..Default::default() };
+        assert!(!pattern.matches(&key));
+    }
+
+    #[test]
+    #[should_panic(expected = "doesn't fit in the Signed field")]
+    fn truncated_signed_none() {
+        // The low bytes are the minimum value of the field, which is reserved for `None`.
+        let _ = TruncatedKey::new(NonZeroI32::new(0x7F80_0000), None, NonZeroU32::new(1).unwrap());
+    }
+
+    #[test]
+    #[should_panic(expected = "doesn't fit in the Signed field")]
+    fn truncated_signed_zero() {
+        let _ = TruncatedKey::new(NonZeroI32::new(0x0100_0000), None, NonZeroU32::new(1).unwrap());
+    }
+
+    #[test]
+    #[should_panic(expected = "doesn't fit in the Unsigned field")]
+    fn truncated_unsigned_zero() {
+        let mut key = TruncatedKey::default();
+        key.set_unsigned(NonZeroU32::new(0x0100_0000));
+    }
+
+    #[test]
+    #[should_panic(expected = "doesn't fit in the Required field")]
+    fn truncated_required_zero() {
+        let mut key = TruncatedKey::default();
+        key.set_required(NonZeroU32::new(0x1_0000).unwrap());
+    }
+
+    proptest! {
+        #[test]
+        fn sharded_tests(
+            users in proptest::collection::btree_set(any::<u64>(), 2..=2),
+            event: u32,
+        ) {
+            let user: Vec<u64> = users.iter().copied().collect();
+            let first = ShardedKey::new(user[0], event);
+            let mut second = ShardedKey::from(ShardedKeyArgs { user: user[1], event });
+            assert_eq!(first.shard(), user_shard(&first));
+            assert_eq!(first.check(), key_checksum(&first));
+            assert!(first.validate().is_ok());
+            assert!(second.validate().is_ok());
+            second.set_user(user[0]);
+            assert_eq!(first, second);
+            assert!(second.validate().is_ok());
+        }
+    }
+
+    #[test]
+    fn sharded_layout() {
+        assert_eq!(ShardedKey::KEY_LENGTH, 15);
+        let key = ShardedKey::new(1, 7);
+        let shard = (0x9E37_79B9_7F4A_7C15_u64 >> 56) as u8;
+        assert_eq!(key.shard(), shard);
+        assert_eq!(&key.as_ref()[..13], &[shard, 0, 0, 0, 0, 0, 0, 0, 1, 0, 0, 0, 7]);
+        assert_eq!(ShardedKey::default().validate(), Ok(()));
+        assert_eq!(ShardedKey::default().shard(), 0);
+        let mut raw: [u8; ShardedKey::KEY_LENGTH] = key.into();
+        raw[0] ^= 1;
+        let invalid = ShardedKey::from(raw);
+        assert_eq!(invalid.validate(), Err(db_key::ValidationError::Computed { field: "shard" }));
+        raw[0] ^= 1;
+        raw[14] ^= 1;
+        assert_eq!(ShardedKey::from(raw).validate().unwrap_err().field(), "check");
+    }
+
+    proptest! {
+        #[test]
+        fn tagged_tests(
+            tag: u16,
+            internal: u64,
+            entries in proptest::collection::btree_set(any::<u32>(), 2..=2),
+        ) {
+            let entry: Vec<u32> = entries.iter().copied().collect();
+            let mut key = TaggedKey::new(2, tag, internal, entry[0]);
+            assert_eq!(key.version(), 2);
+            assert_eq!(key.tag(), tag);
+            assert_eq!(key.internal(), internal);
+            key.set_tag(!tag);
+            assert_eq!(key.tag(), !tag);
+            key.set_internal(!internal);
+            assert_eq!(key.internal(), !internal);
+            key.set_entry(entry[1]);
+            assert_eq!(key.entry(), entry[1]);
+            assert_eq!(key.version(), 2);
+            assert!(TaggedKey::new(2, tag, internal, entry[0]) <
+                TaggedKey::new(2, tag, internal, entry[1]));
+        }
+    }
+
+    #[test]
+    fn tagged_layout() {
+        assert_eq!(TaggedKey::KEY_LENGTH, 15);
+        assert_eq!(TaggedKey::default().version(), 2);
+        let key = TaggedKey::new(1, 0x1234, 5, 6);
+        assert_eq!(key.as_ref(), &[1, 0x12, 0x34, 0, 0, 0, 0, 0, 0, 0, 5, 0, 0, 0, 6]);
+        let debug = format!("{:?}", key);
+        assert!(debug.contains("version: 1"));
+        assert!(debug.contains("internal: 5"));
+        assert!(!debug.contains("tag"));
+        assert!(debug.ends_with("raw: 0x01_1234_0000000000000005_00000006 }"));
+    }
+
+    proptest! {
+        #[test]
+        fn reserved_tests(
+            accounts in proptest::collection::btree_set(any::<u32>(), 2..=2),
+            entry: u16,
+        ) {
+            let account: Vec<u32> = accounts.iter().copied().collect();
+            let first = ReservedKey::new(account[0], entry);
+            let mut second = ReservedKey::from(ReservedKeyArgs { account: account[1], entry });
+            assert!(first < second);
+            assert!(first.validate().is_ok());
+            second.set_account(account[0]);
+            assert_eq!(first, second);
+            assert_eq!(ReservedKey::try_from(first.as_ref()), Ok(first));
+        }
+    }
+
+    #[test]
+    fn reserved_layout() {
+        assert_eq!(ReservedKey::KEY_LENGTH, 11);
+        assert_eq!(ReservedKey::_REGION_RANGE, 4..7);
+        assert_eq!(ReservedKey::_RESERVED_RANGE, 9..11);
+        assert_eq!(ReservedKey::MAX_KEY.as_ref(),
+            &[0xFF, 0xFF, 0xFF, 0xFF, 0, 0, 0, 0xFF, 0xFF, 0, 0]);
+        let key = ReservedKey::new(0x1234, 5);
+        assert_eq!(key.as_ref(), &[0, 0, 0x12, 0x34, 0, 0, 0, 0, 5, 0, 0]);
+        assert_eq!(format!("{:?}", key), concat!("ReservedKey { account: 4660, ",
+            "_region: padding [0, 0, 0], entry: 5, _reserved: padding [0, 0], ",
+            "raw: 0x00001234_000000_0005_0000 }"));
+        let mut raw: [u8; ReservedKey::KEY_LENGTH] = key.into();
+        raw[5] = 1;
+        assert_eq!(ReservedKey::from(raw).validate(),
+            Err(db_key::ValidationError::Padding { field: "_region" }));
+        raw[5] = 0;
+        raw[10] = 1;
+        assert_eq!(ReservedKey::try_from(&raw[..]), Err(db_key::DecodeError::Invalid(
+            db_key::ValidationError::Padding { field: "_reserved" })));
+    }
+
+    #[test]
+    fn journal_decode_any() {
+        let key = JournalKey::new(7);
+        assert_eq!(JournalKey::decode_any(key.as_ref()), Ok(key));
+        let mut raw: [u8; JournalKey::KEY_LENGTH] = key.into();
+        raw[5] = 1;
+        assert_eq!(JournalKey::decode_any(&raw), Err(db_key::DecodeError::Invalid(
+            db_key::ValidationError::Padding { field: "_reserved" })));
+        assert_eq!(JournalKey::decode_any(&raw), JournalKey::try_from(&raw[..]));
+    }
+
+    #[test]
+    fn reserved_slice_length() {
+        let key = ReservedKey::new(0x1234, 5);
+        assert_eq!(ReservedKey::try_from(key.as_ref()), Ok(key));
+        assert_eq!(ReservedKey::try_from(&[0, 0, 0x12, 0x34][..]),
+            Err(db_key::DecodeError::Length { expected: ReservedKey::KEY_LENGTH, found: 4 }));
+        assert_eq!(ReservedKey::try_from(&[][..]),
+            Err(db_key::DecodeError::Length { expected: ReservedKey::KEY_LENGTH, found: 0 }));
+        let mut long = key.as_ref().to_vec();
+        long.push(0);
+        assert_eq!(ReservedKey::try_from(&long[..]),
+            Err(db_key::DecodeError::Length { expected: ReservedKey::KEY_LENGTH,
+                found: ReservedKey::KEY_LENGTH + 1 }));
+    }
+
+    proptest! {
+        #[test]
+        fn activity_tests(
+            user: u32,
+            tag: [u8; 2],
+            parent: Option<u16>,
+            owner in 1..=u32::MAX,
+        ) {
+            let owner = NonZeroU32::new(owner).unwrap();
+            let previous = ActivityKeyV1::new(user, tag, parent, owner);
+            let key = ActivityKey::from(previous);
+            assert_eq!(key.user(), user as u64);
+            assert_eq!(key.tag(), &[tag[0], tag[1], 0, 0]);
+            assert_eq!(key.parent(), parent.map(u32::from));
+            assert_eq!(key.owner(), Some(NonZeroU64::from(owner)));
+            assert_eq!(key.kind(), 7);
+            assert_eq!(ActivityKey::decode_any(previous.as_ref()), Ok(key));
+            assert_eq!(ActivityKey::decode_any(key.as_ref()), Ok(key));
+            assert_eq!(ActivityKeyV1::decode_any(previous.as_ref()), Ok(previous));
+        }
+    }
+
+    #[test]
+    fn activity_layout() {
+        assert_eq!(ActivityKeyV1::VERSION, 1);
+        assert_eq!(ActivityKey::VERSION, 2);
+        assert_eq!(ActivityKeyV1::KEY_LENGTH, 14);
+        assert_eq!(ActivityKey::KEY_LENGTH, 27);
+        assert_eq!(ActivityKey::USER_START, 1);
+        assert_eq!(ActivityKey::MIN_KEY.as_ref()[0], 2);
+        assert_eq!(ActivityKey::MAX_KEY.as_ref()[0], 2);
+        assert_eq!(ActivityKey::default().as_ref()[0], 2);
+        let previous = ActivityKeyV1::new(0x1234, *b"ab", None, NonZeroU32::new(5).unwrap());
+        assert_eq!(previous.as_ref(), &[1, 0, 0, 0x12, 0x34, b'a', b'b', 0, 0, 0, 0, 0, 0, 5]);
+        assert!(format!("{:?}", previous).starts_with("ActivityKeyV1 { _version: 1, user: 4660,"));
+        let key = ActivityKey::from(ActivityKeyV1::from(ActivityKeyV1Args {
+            user: 0x1234,
+            tag: *b"ab",
+            parent: Some(3),
+            owner: NonZeroU32::new(5).unwrap(),
+        }));
+        assert_eq!(key.as_ref(), &[2, 0, 0, 0, 0, 0, 0, 0x12, 0x34, b'a', b'b', 0, 0,
+            1, 0, 0, 0, 3, 0, 0, 0, 0, 0, 0, 0, 5, 7]);
+        assert_eq!(ActivityKey::decode_any(&[3]), Err(db_key::DecodeError::Version { found: 3 }));
+        assert_eq!(ActivityKey::decode_any(&previous.as_ref()[..5]),
+            Err(db_key::DecodeError::Length { expected: 14, found: 5 }));
+        assert_eq!(ActivityKey::decode_any(&[]),
+            Err(db_key::DecodeError::Length { expected: 27, found: 0 }));
+    }
+
+    #[test]
+    fn schema_layout() {
+        assert_eq!(AmountKey::SCHEMA_DESCRIPTION,
+            "amount: i64 @0+64 decimal=4\nrate: u32 @64+24 decimal=2\nentry: u32 @88+32");
+        assert_eq!(OptionKey::SCHEMA_DESCRIPTION, "parent: Option<u64> @0+72\n\
+            kind: Option<i8> @72+16\ntag: Option<[u8;3]> none_last @88+32\n\
+            offset: Option<i32> @120+32\nid: u16 @152+16");
+        assert_eq!(EventKey::SCHEMA_DESCRIPTION, "time: SystemTime time_unit=nanos @0+64\n\
+            updated: SystemTime time_unit=millis @64+48\nttl: Duration time_unit=secs @112+32\n\
+            expires: Option<SystemTime> time_unit=secs @144+48");
+        assert_eq!(LoginKey::SCHEMA_DESCRIPTION, "user: [u8;8] collate=ascii_ci @0+64\n\
+            domain: Option<[u8;6]> collate=ascii_ci_trim @64+56\ntag: [u8;2] @120+16");
+        assert_eq!(OrderKey::SCHEMA_DESCRIPTION, "customer: NonZeroU64 @0+64\n\
+            parent: Option<NonZeroU64> @64+48\ndelta: NonZeroI16 @112+16");
+        assert_eq!(ShardedKey::SCHEMA_DESCRIPTION, "shard: u8 @0+8 computed=user_shard\n\
+            user: u64 @8+64\nevent: u32 @72+32\ncheck: u16 @104+16 computed=crate::attrib::key_checksum");
+        assert_eq!(ReservedKey::SCHEMA_DESCRIPTION, "account: u32 @0+32\n\
+            _region: [u8;3] @32+24 padding\nentry: u16 @56+16\n_reserved: [u8;2] @72+16 padding");
+        assert_eq!(ActivityKey::SCHEMA_DESCRIPTION, "_version: u8 @0+8 version=2\n\
+            user: u64 @8+64\ntag: [u8;4] @72+32\nparent: Option<u32> @104+40\n\
+            owner: NonZeroU64 @144+64\nkind: u8 @208+8");
+        // The hash is the 64-bit FNV-1a hash of the description.
+        let fnv = |description: &str| description.bytes().fold(0xcbf2_9ce4_8422_2325_u64,
+            |hash, byte| (hash ^ byte as u64).wrapping_mul(0x100_0000_01b3));
+        assert_eq!(AmountKey::SCHEMA_HASH, fnv(AmountKey::SCHEMA_DESCRIPTION));
+        assert_eq!(ActivityKey::SCHEMA_HASH, fnv(ActivityKey::SCHEMA_DESCRIPTION));
+        assert_ne!(ActivityKey::SCHEMA_HASH, ActivityKeyV1::SCHEMA_HASH);
+        // The hash doesn't depend on the macro, the names or the documentation of the fields.
+        assert_eq!(AmountKey::SCHEMA_HASH, crate::derive::AmountKey::SCHEMA_HASH);
+        assert_eq!(ActivityKey::SCHEMA_HASH, crate::derive::ActivityKey::SCHEMA_HASH);
+        assert_eq!(BitsKey::SCHEMA_HASH, crate::derive::BitsKey::SCHEMA_HASH);
+    }
+
+    /// Decode an integer field of a key with the layout of the field in the JSON schema.
+    fn decode_json_integer(field: &serde_json::Value, key: &[u8]) -> i128 {
+        let bit_offset = field["bit_offset"].as_u64().unwrap() as usize;
+        let bits = field["bits"].as_u64().unwrap() as usize;
+        let mut value = 0_u128;
+        for bit in bit_offset..bit_offset + bits {
+            value = (value << 1) | ((key[bit / 8] >> (7 - bit % 8)) & 1) as u128;
+        }
+        if field["sign_flip"].as_bool().unwrap() {
+            // Flip the sign bit back and extend the sign.
+            let value = value ^ (1 << (bits - 1));
+            ((value << (128 - bits)) as i128) >> (128 - bits)
+        }
+        else {
+            value as i128
+        }
+    }
+
+    proptest! {
+        #[test]
+        fn schema_json_tests(
+            priority in 0..=15_u8,
+            shard in 0..=0xFFF_u16,
+            adjust in -4..=3_i8,
+            id in 0..=u32::MAX,
+            level in -512..=511_i16,
+        ) {
+            let json: serde_json::Value = serde_json::from_str(&BitsKey::schema_json()).unwrap();
+            let key = BitsKey::new(priority, shard, adjust, id, level);
+            let fields = json["fields"].as_array().unwrap();
+            assert_eq!(decode_json_integer(&fields[0], key.as_ref()), priority as i128);
+            assert_eq!(decode_json_integer(&fields[1], key.as_ref()), shard as i128);
+            assert_eq!(decode_json_integer(&fields[2], key.as_ref()), adjust as i128);
+            assert_eq!(decode_json_integer(&fields[3], key.as_ref()), id as i128);
+            assert_eq!(decode_json_integer(&fields[4], key.as_ref()), level as i128);
+        }
+    }
+
+    #[test]
+    fn schema_json_layout() {
+        let json: serde_json::Value = serde_json::from_str(&BitsKey::schema_json()).unwrap();
+        assert_eq!(json["key"], "BitsKey");
+        assert_eq!(json["length"], BitsKey::KEY_LENGTH);
+        assert_eq!(json["hash"], format!("{:#018x}", BitsKey::SCHEMA_HASH));
+        let priority = &json["fields"][0];
+        assert_eq!(priority["name"], "priority");
+        assert_eq!(priority["label"], "Priority");
+        assert_eq!(priority["encoding"], "uint");
+        assert_eq!((&priority["bit_offset"], &priority["bits"]), (&0.into(), &4.into()));
+        assert_eq!((&priority["default"], &priority["min"], &priority["max"]),
+            (&"0x05".into(), &"0x00".into(), &"0x0f".into()));
+        let adjust = &json["fields"][2];
+        assert_eq!((&adjust["encoding"], &adjust["sign_flip"]), (&"int".into(), &true.into()));
+        assert_eq!((&adjust["offset"], &adjust["size"]), (&2.into(), &1.into()));
+        assert_eq!(adjust["descending"], false);
+
+        let json: serde_json::Value = serde_json::from_str(&OptionKey::schema_json()).unwrap();
+        let tag = &json["fields"][2];
+        assert_eq!((&tag["type"], &tag["option"]), (&"Option<[u8;3]>".into(), &"none_last".into()));
+        assert_eq!((&tag["default"], &tag["max"]), (&"0x01000000".into(), &"0x01000000".into()));
+        let json: serde_json::Value = serde_json::from_str(&OrderKey::schema_json()).unwrap();
+        assert_eq!(json["fields"][1]["option"], "zero");
+        assert_eq!(json["fields"][2]["default"], "0x8001");
+        let json: serde_json::Value = serde_json::from_str(&EventKey::schema_json()).unwrap();
+        assert_eq!(json["fields"][1]["time_unit"], "millis");
+        assert_eq!(json["fields"][3]["encoding"], "system_time");
+        let json: serde_json::Value = serde_json::from_str(&AmountKey::schema_json()).unwrap();
+        assert_eq!(json["fields"][1]["decimal_scale"], 2);
+        assert_eq!(AmountKey::SCHEMA.fields.len(), 3);
+        assert_eq!(AmountKey::SCHEMA.fields[2].bit_offset, 88);
+        let json: serde_json::Value =
+            serde_json::from_str(&crate::derive::FlowKey::schema_json()).unwrap();
+        assert_eq!(json["fields"][1]["encoding"], "ipv4");
+        assert_eq!(json["fields"][5]["type"], "Option<Ipv4Addr>");
+    }
+
+    #[test]
+    fn arbitrary_order_preserving() {
+        db_key::assert_order_preserving::<SampleKey>();
+        db_key::assert_order_preserving::<MinMaxKey>();
+        db_key::assert_order_preserving::<SignedKey>();
+        db_key::assert_order_preserving::<NarrowKey>();
+        db_key::assert_order_preserving::<BitsKey>();
+        db_key::assert_order_preserving::<FlowKey>();
+        db_key::assert_order_preserving::<EventKey>();
+        db_key::assert_order_preserving::<RecordKey>();
+        db_key::assert_order_preserving::<MemberKey>();
+        db_key::assert_order_preserving::<WordKey>();
+        db_key::assert_order_preserving::<LoginKey>();
+        db_key::assert_order_preserving::<AmountKey>();
+        db_key::assert_order_preserving::<OrderKey>();
+        db_key::assert_order_preserving::<AdjustmentKey>();
+        db_key::assert_order_preserving::<ReservedKey>();
+        db_key::assert_order_preserving::<ActivityKey>();
+    }
+
+    #[test]
+    #[should_panic(expected = "aren't in the same order")]
+    fn arbitrary_none_last_order() {
+        // The arguments sort `None` before `Some`, but the tag field sorts it last.
+        db_key::assert_order_preserving::<OptionKey>();
+    }
+
+    #[test]
+    fn arbitrary_bounds() {
+        // An exhausted input creates the minimum values, which are the bounds of the fields, not
+        // the minimums of their types.
+        let mut u = arbitrary::Unstructured::new(&[]);
+        let min_max: MinMaxKeyArgs = u.arbitrary().unwrap();
+        assert_eq!(MinMaxKey::from(&min_max), MinMaxKey::MIN_KEY);
+        assert_eq!((min_max.id, min_max.word, min_max.byte), (1, 150, u8::MAX));
+        let narrow: NarrowKeyArgs = u.arbitrary().unwrap();
+        assert_eq!(NarrowKey::from(&narrow), NarrowKey::MIN_KEY);
+        assert_eq!((narrow.offset, narrow.saturated), (-0x80_0000, -0x80_0000_0000));
+        let bits: BitsKeyArgs = u.arbitrary().unwrap();
+        assert_eq!(BitsKey::from(&bits), BitsKey::MIN_KEY);
+        // The largest input values don't exceed the maximums of the narrowed fields.
+        let ones = [0xFF_u8; 256];
+        let mut u = arbitrary::Unstructured::new(&ones);
+        let narrow: NarrowKeyArgs = u.arbitrary().unwrap();
+        assert_eq!((narrow.id, narrow.counter), (0xFFFF_FFFF_FFFF, 0xFF_FFFF));
+        assert_eq!(narrow.saturated, NarrowKey::from(&narrow).saturated());
+        let bits: BitsKeyArgs = u.arbitrary().unwrap();
+        assert_eq!((bits.priority, bits.shard), (0xF, 0xFFF));
+        let reserved: ReservedKey = u.arbitrary().unwrap();
+        assert!(ReservedKey::try_from(reserved.as_ref()).is_ok());
+    }
+
+    proptest! {
+        #[test]
+        fn arbitrary_narrowed_fit(
+            narrow in any::<NarrowKeyArgs>(),
+            bits in any::<BitsKeyArgs>(),
+            option in any::<OptionKeyArgs>(),
+            adjustment in any::<AdjustmentKeyArgs>(),
+        ) {
+            // The generated values fit in the narrowed fields, so they aren't saturated or
+            // truncated, and the keys are between the minimum and maximum keys.
+            let key = NarrowKey::from(&narrow);
+            prop_assert_eq!(key.to_args(), narrow);
+            prop_assert!((NarrowKey::MIN_KEY..=NarrowKey::MAX_KEY).contains(&key));
+            let key = BitsKey::from(&bits);
+            prop_assert_eq!(key.to_args(), bits);
+            prop_assert!((BitsKey::MIN_KEY..=BitsKey::MAX_KEY).contains(&key));
+            prop_assert_eq!(OptionKey::from(&option).to_args(), option);
+            // The minimum of a signed non-zero option is reserved for `None`.
+            prop_assert_eq!(AdjustmentKey::from(&adjustment).to_args(), adjustment);
+        }
+
+        #[test]
+        fn arbitrary_none_order(option in any::<OptionKeyArgs>()) {
+            // `None` sorts before the smallest value, except for the `none_last` tag field,
+            // which sorts it after the largest value.
+            let key = |args: &OptionKeyArgs| OptionKey::from(args);
+            let none = OptionKeyArgs { parent: None, ..option };
+            let smallest = OptionKeyArgs { parent: Some(0), ..option };
+            prop_assert!(key(&none) < key(&smallest));
+            let none = OptionKeyArgs { kind: None, ..option };
+            let smallest = OptionKeyArgs { kind: Some(i8::MIN), ..option };
+            prop_assert!(key(&none) < key(&smallest));
+            let none = OptionKeyArgs { tag: None, ..option };
+            let largest = OptionKeyArgs { tag: Some([0xFF; 3]), ..option };
+            prop_assert!(key(&largest) < key(&none));
+            let none = OptionKeyArgs { offset: None, ..option };
+            let smallest = OptionKeyArgs { offset: Some(-0x80_0000), ..option };
+            prop_assert!(key(&none) < key(&smallest));
+        }
+    }
+
+    #[test]
+    fn to_args() {
+        let key = LedgerKey::new(7, Cents(-1), Some(Label("rent".to_string())));
+        assert_eq!(key.to_args(), LedgerKeyArgs {
+            account: 7,
+            balance: Cents(-1),
+            label: Some(Label("rent".to_string())),
+        });
+        let key = LoginKey::new(*b"alice\0\0\0", None, *b"ab");
+        assert_eq!(LoginKey::from(key.to_args()), key);
+        let key = TaggedKey::new(1, 0x1234, 5, 6);
+        assert_eq!(TaggedKeyArgs::from(&key), TaggedKeyArgs {
+            version: 1,
+            tag: 0x1234,
+            internal: 5,
+            entry: 6,
+        });
+        // The computed fields aren't arguments, and the reserved bytes are ignored.
+        let key = ShardedKey::new(1, 7);
+        assert_eq!(ShardedKeyArgs::from(key), ShardedKeyArgs { user: 1, event: 7 });
+        let mut raw: [u8; ReservedKey::KEY_LENGTH] = ReservedKey::new(3, 4).into();
+        raw[5] = 1;
+        assert_eq!(ReservedKeyArgs::from(ReservedKey::from(raw)),
+            ReservedKeyArgs { account: 3, entry: 4 });
+        // A non-zero field that is zero in an invalid raw key takes its default value.
+        let key = OrderKey::from([0_u8; OrderKey::KEY_LENGTH]);
+        assert_eq!(key.to_args().customer, OrderKeyArgs::default().customer);
+    }
+
+    #[test]
+    fn update() {
+        let mut key = ShardedKey::new(1, 7);
+        key.update(|args| {
+            args.user = 2;
+            args.event = 8;
+        });
+        assert_eq!(key, ShardedKey::new(2, 8));
+        assert_eq!(key.validate(), Ok(()));
+        let mut key = ActivityKey::default();
+        key.update(|args| args.kind = 3);
+        assert_eq!((key.as_ref()[0], key.kind()), (2, 3));
+    }
+
+    #[test]
+    fn update_keeps_readonly() {
+        let mut key = TaggedKey::new(1, 2, 3, 4);
+        key.update(|args| {
+            args.version = 9;
+            args.tag = 8;
+            args.internal = 7;
+            args.entry = 6;
+        });
+        // Only the entry has a public setter.
+        assert_eq!(key, TaggedKey::new(1, 2, 3, 6));
+        assert_eq!((key.version(), key.tag(), key.internal()), (1, 2, 3));
+    }
+
+    proptest! {
+        #[test]
+        fn to_args_arbitrary(
+            option in any::<OptionKeyArgs>(),
+            flow in any::<FlowKeyArgs>(),
+            event in any::<EventKeyArgs>(),
+            member in any::<MemberKeyArgs>(),
+            word in any::<WordKeyArgs>(),
+            login in any::<LoginKeyArgs>(),
+            order in any::<OrderKeyArgs>(),
+            activity in any::<ActivityKeyArgs>(),
+        ) {
+            prop_assert_eq!(OptionKey::from(&option).to_args(), option);
+            prop_assert_eq!(FlowKey::from(&flow).to_args(), flow);
+            prop_assert_eq!(EventKey::from(&event).to_args(), event);
+            prop_assert_eq!(MemberKey::from(&member).to_args(), member);
+            prop_assert_eq!(WordKey::from(&word).to_args(), word);
+            prop_assert_eq!(LoginKey::from(&login).to_args(), login);
+            prop_assert_eq!(OrderKey::from(&order).to_args(), order);
+            prop_assert_eq!(ActivityKey::from(&activity).to_args(), activity);
+        }
+    }
+
+    /// Check that each key matches a pattern if and only if it's in the range of the pattern and
+    /// matches the residual pattern.
+    fn check_split<K: Ord + std::fmt::Debug>(
+        keys: &[K],
+        matches: impl Fn(&K) -> bool,
+        range: std::ops::RangeInclusive<K>,
+        residual: impl Fn(&K) -> bool,
+    ) -> Result<(), TestCaseError> {
+        for key in keys {
+            prop_assert_eq!(matches(key), range.contains(key) && residual(key),
+                "the key {:?} of the range {:?}", key, range);
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn pattern_range() {
+        let key = SampleKey::new(1, 2, 3, 4, [5, 6, 7]);
+        let pattern = SampleKeyPattern { id: Some(1), word: Some(2), long: Some(4),
+            ..Default::default() };
+        assert!(pattern.matches(&key));
+        assert!(!pattern.matches(&SampleKey::new(1, 2, 3, 5, [5, 6, 7])));
+        // The byte field isn't fixed, so the residual pattern filters the long field.
+        assert_eq!(pattern.range(), SampleKey::new(1, 2, 0, 4, [0; 3])..=
+            SampleKey::new(1, 2, u8::MAX, 4, [u8::MAX; 3]));
+        assert_eq!(pattern.residual(), SampleKeyPattern { long: Some(4), ..Default::default() });
+        assert_eq!(SampleKeyPattern::default().range(), SampleKey::MIN_KEY..=SampleKey::MAX_KEY);
+        // The pattern values are normalized like the stored values.
+        let pattern = LoginKeyPattern { user: Some(*b"ALICE   "), ..Default::default() };
+        assert!(pattern.matches(&LoginKey::new(*b"alice   ", None, *b"ab")));
+        assert!(!pattern.matches(&LoginKey::new(*b"bob     ", None, *b"ab")));
+        let pattern = LedgerKeyPattern { label: Some(Some(Label("rent".to_string()))),
+            ..Default::default() };
+        assert!(pattern.matches(&LedgerKey::new(7, Cents(-1), Some(Label("rent".to_string())))));
+        assert!(!pattern.matches(&LedgerKey::new(7, Cents(-1), None)));
+        assert_eq!(pattern.residual(), pattern);
+        // The shard is computed from the user, so the range can't select the user.
+        let pattern = ShardedKeyPattern { user: Some(5), ..Default::default() };
+        assert_eq!(pattern.range(), ShardedKey::from([0x00; ShardedKey::KEY_LENGTH])..=
+            ShardedKey::from([0xFF; ShardedKey::KEY_LENGTH]));
+        assert_eq!(pattern.residual(), pattern);
+        // With every input fixed, the range is the single key and its computed fields.
+        let key = ShardedKey::new(5, 9);
+        let pattern = ShardedKeyPattern::from(&key);
+        assert_eq!(pattern.range(), key..=key);
+        assert_eq!(pattern.residual(), ShardedKeyPattern::default());
+    }
+
+    /// Return the keys of a `BTreeMap` scan of the range of a pattern that match the residual
+    /// pattern.
+    fn scan<K: Ord + Clone>(
+        keys: &[K],
+        range: std::ops::RangeInclusive<K>,
+        residual: impl Fn(&K) -> bool,
+    ) -> Vec<K> {
+        let map: std::collections::BTreeMap<K, ()> =
+            keys.iter().map(|key| (key.clone(), ())).collect();
+        map.range(range).map(|(key, _)| key.clone()).filter(|key| residual(key)).collect()
+    }
+
+    #[test]
+    fn pattern_out_of_range() {
+        // A value that doesn't fit in a narrowed field matches no key, and its range only has
+        // the zero key, which the residual pattern filters out.
+        let key = OptionKey::new(None, None, None, Some(-1), 2);
+        let zero = OptionKey::from([0; OptionKey::KEY_LENGTH]);
+        let pattern = OptionKeyPattern { offset: Some(Some(i32::MAX)), ..Default::default() };
+        assert!(!pattern.matches(&key));
+        assert_eq!(pattern.range(), zero..=zero);
+        assert!(!pattern.residual().matches(&zero));
+        let keys = [zero, key, OptionKey::MAX_KEY];
+        assert!(scan(&keys, pattern.range(), |key| pattern.residual().matches(key)).is_empty());
+        let pattern = OptionKeyPattern { offset: Some(Some(-0x80_0000)), id: Some(2),
+            ..Default::default() };
+        let key = OptionKey::new(None, None, None, Some(-0x80_0000), 2);
+        assert!(pattern.matches(&key));
+        assert_eq!(scan(&[key], pattern.range(), |key| pattern.residual().matches(key)), [key]);
+        let key = BitsKey::new(1, 0xFFF, -4, 7, 0);
+        let keys = [BitsKey::MIN_KEY, key, BitsKey::MAX_KEY];
+        for pattern in [
+            BitsKeyPattern { shard: Some(0x1000), ..Default::default() },
+            BitsKeyPattern { adjust: Some(-5), ..Default::default() },
+            BitsKeyPattern { adjust: Some(i8::MAX), id: Some(7), ..Default::default() },
+        ] {
+            assert!(!pattern.matches(&key));
+            assert!(!pattern.range().is_empty());
+            assert!(scan(&keys, pattern.range(), |key| pattern.residual().matches(key)).is_empty());
+        }
+        // A saturated value matches the key that stores the saturated value.
+        let pattern = BitsKeyPattern { level: Some(i16::MAX), ..Default::default() };
+        assert!(pattern.matches(&BitsKey::new(1, 2, 3, 4, 511)));
+        assert!(pattern.range().contains(&BitsKey::new(1, 2, 3, 4, 511)));
+        // The minimum of a signed non-zero option is reserved for `None`.
+        let pattern = AdjustmentKeyPattern { adjustment: Some(NonZeroI32::new(i32::MIN)),
+            ..Default::default() };
+        assert!(!pattern.matches(&AdjustmentKey::default()));
+        assert!(!pattern.residual().matches(pattern.range().start()));
+        let pattern = AdjustmentKeyPattern { correction: Some(NonZeroI32::new(i32::MIN)),
+            ..Default::default() };
+        assert!(pattern.matches(&AdjustmentKey::new(0, None, NonZeroI32::new(-0x7F_FFFF), None)));
+        // Times before the Unix epoch or after the last time of the field don't fit.
+        let key = EventKey::default();
+        let before_epoch = SystemTime::UNIX_EPOCH - Duration::from_secs(1);
+        for pattern in [
+            EventKeyPattern { time: Some(before_epoch), ..Default::default() },
+            EventKeyPattern { expires: Some(Some(before_epoch)), ..Default::default() },
+            EventKeyPattern { expires: Some(Some(SystemTime::UNIX_EPOCH
+                + Duration::from_secs(1 << 40))), ..Default::default() },
+        ] {
+            assert!(!pattern.matches(&key));
+            let keys = [key];
+            assert!(scan(&keys, pattern.range(), |key| pattern.residual().matches(key)).is_empty());
+        }
+        let pattern = EventKeyPattern { ttl: Some(Duration::MAX), ..Default::default() };
+        assert!(pattern.matches(&EventKey::new(SystemTime::UNIX_EPOCH, SystemTime::UNIX_EPOCH,
+            Duration::from_secs(u32::MAX as u64), None)));
+    }
+
+    proptest! {
+        #[test]
+        fn pattern_split(
+            key in any::<SampleKey>(),
+            other in any::<SampleKey>(),
+            fixed in any::<[bool; 5]>(),
+            mix in any::<[bool; 5]>(),
+        ) {
+            let (args, other_args) = (key.to_args(), other.to_args());
+            let pattern = SampleKeyPattern {
+                id: fixed[0].then_some(other_args.id),
+                word: fixed[1].then_some(other_args.word),
+                byte: fixed[2].then_some(other_args.byte),
+                long: fixed[3].then_some(other_args.long),
+                end: fixed[4].then_some(other_args.end),
+            };
+            let mut mixed = other;
+            mixed.update(|mixed| {
+                if mix[0] { mixed.id = args.id; }
+                if mix[1] { mixed.word = args.word; }
+                if mix[2] { mixed.byte = args.byte; }
+                if mix[3] { mixed.long = args.long; }
+                if mix[4] { mixed.end = args.end; }
+            });
+            prop_assert!(pattern.matches(&other));
+            let residual = pattern.residual();
+            check_split(&[key, other, mixed], |key| pattern.matches(key), pattern.range(),
+                |key| residual.matches(key))?;
+        }
+
+        #[test]
+        fn pattern_split_fields(
+            bits in any::<(BitsKey, BitsKey)>(),
+            option in any::<(OptionKey, OptionKey)>(),
+            login in any::<(LoginKey, LoginKey)>(),
+            activity in any::<(ActivityKey, ActivityKey)>(),
+            sharded in any::<[(u64, u32); 2]>(),
+            fixed in any::<[bool; 5]>(),
+        ) {
+            // The patterns are the fields of the second key that are fixed, so the second key
+            // matches them.
+            let args = bits.1.to_args();
+            let pattern = BitsKeyPattern {
+                priority: fixed[0].then_some(args.priority),
+                shard: fixed[1].then_some(args.shard),
+                adjust: fixed[2].then_some(args.adjust),
+                id: fixed[3].then_some(args.id),
+                level: fixed[4].then_some(args.level),
+            };
+            let residual = pattern.residual();
+            check_split(&[bits.0, bits.1], |key| pattern.matches(key), pattern.range(),
+                |key| residual.matches(key))?;
+            let args = option.1.to_args();
+            let pattern = OptionKeyPattern {
+                parent: fixed[0].then_some(args.parent),
+                kind: fixed[1].then_some(args.kind),
+                tag: fixed[2].then_some(args.tag),
+                offset: fixed[3].then_some(args.offset),
+                id: fixed[4].then_some(args.id),
+            };
+            let residual = pattern.residual();
+            check_split(&[option.0, option.1], |key| pattern.matches(key), pattern.range(),
+                |key| residual.matches(key))?;
+            let args = login.1.to_args();
+            let pattern = LoginKeyPattern {
+                user: fixed[0].then_some(args.user),
+                domain: fixed[1].then_some(args.domain),
+                tag: fixed[2].then_some(args.tag),
+            };
+            let residual = pattern.residual();
+            check_split(&[login.0, login.1], |key| pattern.matches(key), pattern.range(),
+                |key| residual.matches(key))?;
+            let args = activity.1.to_args();
+            let pattern = ActivityKeyPattern {
+                user: fixed[0].then_some(args.user),
+                tag: fixed[1].then_some(args.tag),
+                parent: fixed[2].then_some(args.parent),
+                owner: fixed[3].then_some(args.owner),
+                kind: fixed[4].then_some(args.kind),
+            };
+            let residual = pattern.residual();
+            check_split(&[activity.0, activity.1], |key| pattern.matches(key), pattern.range(),
+                |key| residual.matches(key))?;
+            let keys = [ShardedKey::new(sharded[0].0, sharded[0].1),
+                ShardedKey::new(sharded[1].0, sharded[1].1)];
+            let pattern = ShardedKeyPattern {
+                user: fixed[0].then_some(sharded[1].0),
+                event: fixed[1].then_some(sharded[1].1),
+            };
+            let residual = pattern.residual();
+            check_split(&keys, |key| pattern.matches(key), pattern.range(),
+                |key| residual.matches(key))?;
+        }
     }
 }
@@ -0,0 +1,55 @@
+use db_key::KeyFieldCodec;
+
+/// An amount of money in cents.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Cents(pub i64);
+
+/// Store `Cents` as a big-endian `i64` with the sign bit toggled.
+#[derive(Debug)]
+pub struct CentsCodec;
+
+impl KeyFieldCodec for CentsCodec {
+    type Value = Cents;
+    const SIZE: usize = 8;
+    const MIN: &'static [u8] = &[0x00; 8];
+    const MAX: &'static [u8] = &[0xFF; 8];
+
+    fn encode(value: &Cents, bytes: &mut [u8]) {
+        bytes.copy_from_slice(&((value.0 as u64) ^ (1 << 63)).to_be_bytes());
+    }
+
+    fn decode(bytes: &[u8]) -> Cents {
+        let mut buf = [0_u8; 8];
+        buf.copy_from_slice(bytes);
+        Cents((u64::from_be_bytes(buf) ^ (1 << 63)) as i64)
+    }
+}
+
+/// A label of up to 8 printable ASCII characters, which isn't `Copy`.
+#[derive(Clone, Debug, Default, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Label(pub String);
+
+/// Store a `Label` as its characters padded with zeros.
+#[derive(Debug)]
+pub struct LabelCodec;
+
+impl KeyFieldCodec for LabelCodec {
+    type Value = Label;
+    const SIZE: usize = 8;
+    const MIN: &'static [u8] = &[0x00; 8];
+    const MAX: &'static [u8] = b"~~~~~~~~";
+
+    fn encode(value: &Label, bytes: &mut [u8]) {
+        let label = value.0.as_bytes();
+        assert!(label.len() <= Self::SIZE, "The label {:?} is too long", value.0);
+        assert!(label.iter().all(|c| (b' '..=b'~').contains(c)),
+            "The label {:?} isn't printable ASCII", value.0);
+        bytes.fill(0);
+        bytes[..label.len()].copy_from_slice(label);
+    }
+
+    fn decode(bytes: &[u8]) -> Label {
+        let len = bytes.iter().position(|c| 0 == *c).unwrap_or(bytes.len());
+        Label(String::from_utf8_lossy(&bytes[..len]).into_owned())
+    }
+}
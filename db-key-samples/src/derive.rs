@@ -1,11 +1,21 @@
 pub mod debug;
 
 use db_key_macro::DBKey;
-use std::fmt::Debug;
+use std::{
+    fmt::Debug,
+    net::{Ipv4Addr, Ipv6Addr, SocketAddrV4, SocketAddrV6},
+    num::{NonZeroI16, NonZeroU32, NonZeroU64},
+    time::{Duration, SystemTime},
+};
+use uuid::Uuid;
+use crate::{
+    codec::{Cents, Label},
+    ids::{GroupId, Region, UserId},
+};
 
 /// This is a sample key using `derive(DBKey)`.
 #[derive(Copy, Clone, DBKey, Debug, PartialEq, Eq, PartialOrd, Ord)]
-#[key(path = derive)]
+#[key(path = derive, proptest, arbitrary)]
 pub struct Sample {
     /// This is the ID number for the something.
     #[name = "ID"]
@@ -95,7 +105,7 @@ pub struct NoDoc {
 
 /// This is a sample key with minimums and maximums.
 #[derive(Copy, Clone, DBKey, Debug, PartialEq, Eq, PartialOrd, Ord)]
-#[key(path = derive)]
+#[key(path = derive, proptest, arbitrary)]
 pub struct MinMax {
     /// This is the ID number for the something.
     #[name = "ID"]
@@ -217,6 +227,366 @@ pub struct NoMax {
     pub end: [u8; 3],
 }
 
+/// This is a sample key with integer fields stored in fewer bytes than their types.
+#[derive(Copy, Clone, DBKey, Debug, PartialEq, Eq, PartialOrd, Ord)]
+#[key(path = derive)]
+pub struct Narrow {
+    /// A 48-bit identifier.
+    #[name = "ID"]
+    #[bytes = 6]
+    #[default = 0x123456789ABC]
+    pub id: u64,
+    /// A 24-bit signed offset.
+    #[name = "Offset"]
+    #[bytes = 3]
+    pub offset: i32,
+    /// A 40-bit signed value that saturates when it is out of range.
+    #[name = "Saturated"]
+    #[bytes = 5]
+    #[overflow = "saturate"]
+    pub saturated: i64,
+    /// A 24-bit counter that only keeps the low bytes when it is out of range.
+    #[name = "Counter"]
+    #[bytes = "3"]
+    #[overflow = "truncate"]
+    pub counter: u32,
+}
+
+/// This is a sample key with bit fields.
+#[derive(Copy, Clone, DBKey, Debug, PartialEq, Eq, PartialOrd, Ord)]
+#[key(path = derive)]
+pub struct Bits {
+    /// A 4-bit priority.
+    #[name = "Priority"]
+    #[bits = 4]
+    #[default = 5]
+    pub priority: u8,
+    /// A 12-bit shard number.
+    #[name = "Shard"]
+    #[bits = 12]
+    pub shard: u16,
+    /// A 3-bit signed adjustment.
+    #[name = "Adjustment"]
+    #[bits = 3]
+    pub adjust: i8,
+    /// A byte aligned identifier after the bit fields.
+    #[name = "ID"]
+    pub id: u32,
+    /// A 10-bit signed level that saturates when it is out of range.
+    #[name = "Level"]
+    #[bits = 10]
+    #[overflow = "saturate"]
+    pub level: i16,
+}
+
+/// This is a sample key with optional fields.
+#[derive(Copy, Clone, DBKey, Debug, PartialEq, Eq, PartialOrd, Ord)]
+#[key(path = derive, proptest, arbitrary)]
+pub struct Optional {
+    /// An optional parent identifier, where `None` sorts first.
+    #[name = "Parent"]
+    pub parent: Option<u64>,
+    /// An optional signed kind.
+    #[name = "Kind"]
+    pub kind: Option<i8>,
+    /// An optional tag, where `None` sorts last.
+    #[name = "Tag"]
+    #[none_last]
+    pub tag: Option<[u8; 3]>,
+    /// An optional offset stored in 3 bytes.
+    #[name = "Offset"]
+    #[bytes = 3]
+    pub offset: Option<i32>,
+    /// A required identifier.
+    #[name = "ID"]
+    pub id: u16,
+}
+
+/// This is a sample key with network address fields.
+#[derive(Copy, Clone, DBKey, Debug, PartialEq, Eq, PartialOrd, Ord)]
+#[key(path = derive, schema_json, proptest, arbitrary,
+    layout_snapshot = "tests/snapshots/derive_flow_key.txt")]
+pub struct Flow {
+    /// The tenant that owns the flow.
+    #[name = "Tenant"]
+    pub tenant: u16,
+    /// The source IPv4 address.
+    #[name = "Source"]
+    pub source: Ipv4Addr,
+    /// The destination IPv6 address.
+    #[name = "Destination"]
+    pub destination: Ipv6Addr,
+    /// The client IPv4 socket address.
+    #[name = "Client"]
+    pub client: SocketAddrV4,
+    /// The server IPv6 socket address.
+    #[name = "Server"]
+    pub server: SocketAddrV6,
+    /// An optional gateway address.
+    #[name = "Gateway"]
+    pub gateway: Option<std::net::Ipv4Addr>,
+}
+
+/// This is a sample key with time fields.
+#[derive(Copy, Clone, DBKey, Debug, PartialEq, Eq, PartialOrd, Ord)]
+#[key(path = derive, proptest, arbitrary)]
+pub struct Event {
+    /// The time of the event in nanoseconds since the Unix epoch.
+    #[name = "Time"]
+    pub time: SystemTime,
+    /// The time the event was updated in milliseconds since the Unix epoch.
+    #[name = "Updated"]
+    #[time_unit = "ms"]
+    #[bytes = 6]
+    pub updated: SystemTime,
+    /// The time to live of the event in seconds, which saturates at about 136 years.
+    #[name = "TTL"]
+    #[time_unit = "s"]
+    #[bytes = 4]
+    #[overflow = "saturate"]
+    pub ttl: Duration,
+    /// An optional expiration time in seconds since the Unix epoch.
+    #[name = "Expires"]
+    #[time_unit = "s"]
+    #[bytes = 5]
+    pub expires: Option<SystemTime>,
+}
+
+/// This is a sample key with UUID fields.
+#[derive(Copy, Clone, DBKey, Debug, PartialEq, Eq, PartialOrd, Ord)]
+#[key(path = derive)]
+pub struct Record {
+    /// The UUID of the table, where v7 UUIDs sort by time.
+    #[name = "Table"]
+    pub table: Uuid,
+    /// The optional UUID of the parent record.
+    #[name = "Parent"]
+    pub parent: Option<uuid::Uuid>,
+    /// The record number.
+    #[name = "Number"]
+    pub number: u32,
+}
+
+/// This is a sample key with codec fields.
+#[derive(Clone, DBKey, Debug, PartialEq, Eq, PartialOrd, Ord)]
+#[key(path = derive)]
+pub struct Ledger {
+    /// The account number.
+    #[name = "Account"]
+    pub account: u32,
+    /// The balance of the account.
+    #[name = "Balance"]
+    #[codec = "crate::codec::CentsCodec"]
+    #[bytes = 8]
+    pub balance: Cents,
+    /// An optional label for the entry.
+    #[name = "Label"]
+    #[codec = "crate::codec::LabelCodec"]
+    #[bytes = 8]
+    pub label: Option<Label>,
+}
+
+/// This is a sample key with newtype fields.
+#[derive(Clone, DBKey, Debug, PartialEq, Eq, PartialOrd, Ord)]
+#[key(path = derive, proptest, arbitrary)]
+pub struct Member {
+    /// The region of the group.
+    #[name = "Region"]
+    #[via = "[u8; 2]"]
+    pub region: Region,
+    /// The optional group of the user, which is stored in 3 bytes.
+    #[name = "Group"]
+    #[via = "u32"]
+    #[bytes = 3]
+    pub group: Option<GroupId>,
+    /// The user.
+    #[name = "User"]
+    #[via = "u64"]
+    #[default = 1]
+    pub user: UserId,
+}
+
+/// This is a sample key with char fields for a prefix-indexed dictionary.
+#[derive(Copy, Clone, DBKey, Debug, PartialEq, Eq, PartialOrd, Ord)]
+#[key(path = derive)]
+pub struct Word {
+    /// The first letter of the word.
+    #[name = "Initial"]
+    pub initial: char,
+    /// The next letters of the word, which are stored in 3 bytes each.
+    #[name = "Letters"]
+    #[bytes = 3]
+    pub letters: [char; 3],
+    /// The optional last letter of the word.
+    #[name = "Last"]
+    #[bytes = 3]
+    pub last: Option<char>,
+}
+
+/// This is a sample key for a case-insensitive user lookup index.
+#[derive(Copy, Clone, DBKey, Debug, PartialEq, Eq, PartialOrd, Ord)]
+#[key(path = derive, proptest, arbitrary)]
+pub struct Login {
+    /// The user name, where `Alice` and `alice` are the same user.
+    #[name = "User name"]
+    #[collate = "ascii_ci"]
+    pub user: [u8; 8],
+    /// The optional domain of the user, which also ignores the surrounding whitespace.
+    #[name = "Domain"]
+    #[collate = "ascii_ci_trim"]
+    pub domain: Option<[u8; 6]>,
+    /// The tag of the user, which is case sensitive.
+    #[name = "Tag"]
+    #[collate = "binary"]
+    pub tag: [u8; 2],
+}
+
+/// This is a sample key with fixed-point decimal fields for a ledger index sorted by amount.
+#[derive(Copy, Clone, DBKey, Debug, PartialEq, Eq, PartialOrd, Ord)]
+#[key(path = derive)]
+pub struct Amount {
+    /// The amount in ten-thousandths of the currency unit.
+    #[name = "Amount"]
+    #[decimal(scale = 4)]
+    pub amount: i64,
+    /// The exchange rate in hundredths, which is stored in 3 bytes.
+    #[name = "Rate"]
+    #[decimal(scale = 2)]
+    #[bytes = 3]
+    pub rate: u32,
+    /// The entry in the ledger.
+    #[name = "Entry"]
+    pub entry: u32,
+}
+
+/// This is a sample key with non-zero identifiers.
+#[derive(Copy, Clone, DBKey, Debug, PartialEq, Eq, PartialOrd, Ord)]
+#[key(path = derive, proptest, arbitrary)]
+pub struct Order {
+    /// The customer of the order.
+    #[name = "Customer"]
+    pub customer: NonZeroU64,
+    /// The optional parent order, which is stored as zero when it's `None`.
+    #[name = "Parent"]
+    #[bytes = 6]
+    pub parent: Option<NonZeroU64>,
+    /// The change of the order.
+    #[name = "Delta"]
+    pub delta: NonZeroI16,
+}
+
+/// Return the shard of a `ShardedKey`, which is the high byte of a hash of the user.
+pub fn user_shard(key: &ShardedKey) -> u8 {
+    (key.user().wrapping_mul(0x9E37_79B9_7F4A_7C15) >> 56) as u8
+}
+
+/// Return the checksum of the fields of a `ShardedKey` before the checksum.
+pub fn key_checksum(key: &ShardedKey) -> u16 {
+    key.as_ref()[..ShardedKey::CHECK_START].iter()
+        .fold(0_u16, |sum, byte| sum.wrapping_mul(31).wrapping_add(*byte as u16))
+}
+
+/// This is a sample key that starts with a shard computed from the user to avoid write hot spots.
+#[derive(Copy, Clone, DBKey, Debug, PartialEq, Eq, PartialOrd, Ord)]
+#[key(path = derive, proptest, arbitrary, pattern)]
+pub struct Sharded {
+    /// The shard of the user, which is ignored when creating a key.
+    #[name = "Shard"]
+    #[computed = "user_shard"]
+    pub shard: u8,
+    /// The user.
+    #[name = "User"]
+    pub user: u64,
+    /// The event of the user.
+    #[name = "Event"]
+    pub event: u32,
+    /// The checksum of the other fields, which is ignored when creating a key.
+    #[name = "Checksum"]
+    #[computed = "crate::derive::key_checksum"]
+    pub check: u16,
+}
+
+/// This is a sample key with a version that can't be changed, a hidden tag, and an identifier
+/// that can only be accessed in this crate.
+#[derive(Copy, Clone, DBKey, Debug, PartialEq, Eq, PartialOrd, Ord)]
+#[key(path = derive)]
+pub struct Tagged {
+    /// The version of the key schema.
+    #[name = "Version"]
+    #[default = 2]
+    #[readonly]
+    pub version: u8,
+    /// The type tag of the record.
+    #[name = "Tag"]
+    #[hidden]
+    pub tag: u16,
+    /// The internal identifier of the record.
+    #[name = "Internal ID"]
+    #[vis = "pub(crate)"]
+    pub internal: u64,
+    /// The entry of the record.
+    #[name = "Entry"]
+    pub entry: u32,
+}
+
+/// This is a sample key with padding reserved for a future region field after the account, and
+/// two reserved bytes at the end, which are checked by `validate()`.
+#[derive(Copy, Clone, DBKey, Debug, PartialEq, Eq, PartialOrd, Ord)]
+#[key(path = derive, strict, reserve = 2, proptest, arbitrary, pattern)]
+pub struct Reserved {
+    /// The account.
+    #[name = "Account"]
+    pub account: u32,
+    /// The bytes reserved for the region of the account, which are ignored when creating a key.
+    #[padding]
+    pub _region: [u8; 3],
+    /// The entry of the account.
+    #[name = "Entry"]
+    pub entry: u16,
+}
+
+/// This is the first version of a sample key for the activities of a user.
+#[derive(Copy, Clone, DBKey, Debug, PartialEq, Eq, PartialOrd, Ord)]
+#[key(path = derive, version = 1)]
+pub struct ActivityV1 {
+    /// The user of the activity.
+    #[name = "User"]
+    pub user: u32,
+    /// The tag of the activity.
+    #[name = "Tag"]
+    pub tag: [u8; 2],
+    /// The optional parent activity.
+    #[name = "Parent"]
+    pub parent: Option<u16>,
+    /// The owner of the user.
+    #[name = "Owner"]
+    pub owner: NonZeroU32,
+}
+
+/// This is the second version of a sample key for the activities of a user, which widens the
+/// fields of the first version and adds the kind of the activity.
+#[derive(Copy, Clone, DBKey, Debug, PartialEq, Eq, PartialOrd, Ord)]
+#[key(path = derive, version = 2, previous = ActivityV1Key, proptest, arbitrary,
+    layout_snapshot = "tests/snapshots/derive_activity_key.txt")]
+pub struct Activity {
+    /// The user of the activity.
+    #[name = "User"]
+    pub user: u64,
+    /// The tag of the activity.
+    #[name = "Tag"]
+    pub tag: [u8; 4],
+    /// The optional parent activity.
+    #[name = "Parent"]
+    pub parent: Option<u32>,
+    /// The owner of the user.
+    #[name = "Owner"]
+    pub owner: NonZeroU64,
+    /// The kind of the activity, which is 7 for the activities of the first version.
+    #[name = "Kind"]
+    #[default = 7]
+    pub kind: u8,
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -232,10 +602,10 @@ mod tests {
             ends in proptest::collection::btree_set(
                 proptest::collection::vec(0..=u8::MAX, 3..=3), 2..=2),
         ) {
-            let id: Vec<u64> = ids.iter().map(|x| *x).collect();
-            let word: Vec<u16> = words.iter().map(|x| *x).collect();
-            let byte: Vec<u8> = bytes.iter().map(|x| *x).collect();
-            let long: Vec<u32> = longs.iter().map(|x| *x).collect();
+            let id: Vec<u64> = ids.iter().copied().collect();
+            let word: Vec<u16> = words.iter().copied().collect();
+            let byte: Vec<u8> = bytes.iter().copied().collect();
+            let long: Vec<u32> = longs.iter().copied().collect();
             let end: Vec<[u8; 3]> = ends.iter().map(|x| {
                 let mut buf = [0_u8; 3];
                 buf.copy_from_slice(x);
@@ -253,9 +623,113 @@ mod tests {
             let mut key_from_array = Vec::with_capacity(2);
             for i in 0..2 {
                 new_key.push(SampleKey::new(id[i], word[i], byte[i], long[i], end[i]));
-                key_from_array.push(SampleKey::from(array[i].clone()));
+                key_from_array.push(SampleKey::from(array[i]));
                 assert_eq!(new_key[i], key_from_array[i]);
             }
         }
     }
+
+    #[test]
+    fn arbitrary_order_preserving() {
+        db_key::assert_order_preserving::<SampleKey>();
+        db_key::assert_order_preserving::<MinMaxKey>();
+        db_key::assert_order_preserving::<FlowKey>();
+        db_key::assert_order_preserving::<EventKey>();
+        db_key::assert_order_preserving::<MemberKey>();
+        db_key::assert_order_preserving::<LoginKey>();
+        db_key::assert_order_preserving::<OrderKey>();
+        db_key::assert_order_preserving::<ReservedKey>();
+        db_key::assert_order_preserving::<ActivityKey>();
+    }
+
+    proptest! {
+        #[test]
+        fn arbitrary_definitions(
+            min_max in any::<MinMax>(),
+            optional in any::<Optional>(),
+            sharded in any::<ShardedKey>(),
+        ) {
+            prop_assert!((150..=60000).contains(&min_max.word));
+            prop_assert!(([0x20; 3]..=[0xEF; 3]).contains(&min_max.end));
+            let key = OptionalKey::from(&optional);
+            prop_assert_eq!((key.parent(), key.kind(), key.tag(), key.offset()),
+                (optional.parent, optional.kind, optional.tag, optional.offset));
+            // The computed fields of the definition keep their default values, and the key
+            // computes them.
+            prop_assert!(sharded.validate().is_ok());
+        }
+    }
+
+    #[test]
+    fn arbitrary_unstructured() {
+        // An exhausted input creates the minimum values of the fields.
+        let mut u = arbitrary::Unstructured::new(&[]);
+        let min_max: MinMax = u.arbitrary().unwrap();
+        assert_eq!(MinMaxKey::from(&min_max), MinMaxKey::MIN_KEY);
+        let sharded: ShardedKey = u.arbitrary().unwrap();
+        assert!(sharded.validate().is_ok());
+        // The largest input values fit in the narrowed offset, and the computed fields are
+        // computed from the other fields.
+        let ones = [0xFF_u8; 128];
+        let mut u = arbitrary::Unstructured::new(&ones);
+        let optional: Optional = u.arbitrary().unwrap();
+        assert!(optional.offset.iter().all(|offset| (-0x80_0000..=0x7F_FFFF).contains(offset)));
+        assert_eq!(OptionalKey::from(&optional).to_args(), optional);
+        let sharded: ShardedKey = u.arbitrary().unwrap();
+        assert!(sharded.validate().is_ok());
+    }
+
+    #[test]
+    fn to_definition() {
+        // The definition has the computed values of the key.
+        let mut key = ShardedKey::from(Sharded { user: 1, event: 7, ..Default::default() });
+        let sharded = Sharded::from(key);
+        assert_eq!(sharded, Sharded { shard: key.shard(), user: 1, event: 7, check: key.check() });
+        key.update(|sharded| sharded.user = 2);
+        assert_eq!((key.user(), key.event()), (2, 7));
+        assert_eq!(key.validate(), Ok(()));
+        // The padding of the definition keeps its default value.
+        let mut raw: [u8; ReservedKey::KEY_LENGTH] = ReservedKey::new(3, 4).into();
+        raw[5] = 1;
+        assert_eq!(ReservedKey::try_from(&raw[..]).ok(), None);
+        let key = ReservedKey::from(raw);
+        assert_eq!(key.to_args(), Reserved { account: 3, entry: 4, ..Default::default() });
+    }
+
+    proptest! {
+        #[test]
+        fn to_definition_arbitrary(
+            min_max in any::<MinMax>(),
+            optional in any::<Optional>(),
+            login in any::<Login>(),
+            order in any::<Order>(),
+        ) {
+            prop_assert_eq!(MinMaxKey::from(&min_max).to_args(), min_max);
+            prop_assert_eq!(OptionalKey::from(&optional).to_args(), optional);
+            prop_assert_eq!(Login::from(LoginKey::from(&login)), login);
+            prop_assert_eq!(Order::from(&OrderKey::from(&order)), order);
+        }
+    }
+
+    #[test]
+    fn pattern_range() {
+        let key = ShardedKey::from(Sharded { user: 5, event: 9, ..Default::default() });
+        let pattern = ShardedKeyPattern { event: Some(9), ..Default::default() };
+        assert!(pattern.matches(&key));
+        assert!(!pattern.matches(&ShardedKey::from(Sharded { user: 5, event: 8,
+            ..Default::default() })));
+        assert_eq!(pattern.residual(), pattern);
+        assert_eq!(ShardedKeyPattern::from(&key).range(), key..=key);
+        // The padding of the reserved key is zero in the range.
+        let key = ReservedKey::from(Reserved { account: 3, entry: 4, ..Default::default() });
+        let pattern = ReservedKeyPattern { account: Some(3), ..Default::default() };
+        assert!(pattern.matches(&key));
+        assert_eq!(pattern.range(), ReservedKey::from(Reserved { account: 3, entry: 0,
+            ..Default::default() })..=ReservedKey::from(Reserved { account: 3, entry: u16::MAX,
+            ..Default::default() }));
+        assert_eq!(pattern.residual(), ReservedKeyPattern::default());
+        let pattern = ReservedKeyPattern { entry: Some(4), ..Default::default() };
+        assert_eq!(pattern.residual(), pattern);
+        assert!(pattern.range().contains(&key) && pattern.residual().matches(&key));
+    }
 }
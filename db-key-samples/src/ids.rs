@@ -0,0 +1,47 @@
+/// A user ID.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct UserId(pub u64);
+
+impl From<u64> for UserId {
+    fn from(id: u64) -> Self {
+        Self(id)
+    }
+}
+
+impl From<UserId> for u64 {
+    fn from(id: UserId) -> Self {
+        id.0
+    }
+}
+
+/// A group ID.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct GroupId(pub u32);
+
+impl From<u32> for GroupId {
+    fn from(id: u32) -> Self {
+        Self(id)
+    }
+}
+
+impl From<GroupId> for u32 {
+    fn from(id: GroupId) -> Self {
+        id.0
+    }
+}
+
+/// A two letter region code, which isn't `Copy`.
+#[derive(Clone, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Region(pub [u8; 2]);
+
+impl From<[u8; 2]> for Region {
+    fn from(code: [u8; 2]) -> Self {
+        Self(code)
+    }
+}
+
+impl From<Region> for [u8; 2] {
+    fn from(region: Region) -> Self {
+        region.0
+    }
+}
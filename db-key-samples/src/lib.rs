@@ -1,5 +1,9 @@
 #![forbid(future_incompatible)]
 
+/// Sample field codecs used by the sample keys
+pub mod codec;
+/// Sample newtypes used by the sample keys
+pub mod ids;
 /// Sample keys using the attribute macro (`#[db_key]`)
 pub mod attrib;
 /// Sample keys using the derive macro (`DBKey`)
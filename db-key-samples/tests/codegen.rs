@@ -0,0 +1,544 @@
+//! Check that the C headers and the Python modules generated from the layouts of the sample keys
+//! encode and decode the keys bit for bit the same way as the Rust keys.
+//!
+//! The keys are encoded by Rust with random values, and the values are written into a C program
+//! and a Python script that encode the same values and decode the Rust keys with the generated
+//! code. The tests need a C compiler (`CC` or `cc`) and `python3`, so they are ignored by default
+//! and run with `cargo test --test codegen -- --ignored`, which fails when a tool is missing.
+
+use db_key::Decimal;
+use db_key_samples::attrib::*;
+use std::fmt::{Display, Write};
+use std::net::{Ipv4Addr, Ipv6Addr, SocketAddrV4, SocketAddrV6};
+use std::num::{NonZeroI16, NonZeroI32, NonZeroI8, NonZeroU64};
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::time::{Duration, UNIX_EPOCH};
+use uuid::Uuid;
+
+/// The number of random keys of each sample key.
+const KEYS: usize = 20;
+
+/// A SplitMix64 generator, so the keys are the same every time.
+struct Rng(u64);
+
+impl Rng {
+    fn next(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+
+    fn bits(&mut self, bits: u32) -> u64 {
+        self.next() >> (64 - bits)
+    }
+
+    fn signed(&mut self, bits: u32) -> i64 {
+        (self.next() as i64) >> (64 - bits)
+    }
+
+    fn chance(&mut self) -> bool {
+        0 == self.next() & 1
+    }
+
+    fn char(&mut self) -> char {
+        loop {
+            if let Some(c) = char::from_u32(self.bits(21) as u32) {
+                return c;
+            }
+        }
+    }
+
+    /// Return bytes made of letters, spaces, and zeros, to exercise the collations.
+    fn text<const N: usize>(&mut self) -> [u8; N] {
+        let mut bytes = [0_u8; N];
+        for byte in bytes.iter_mut() {
+            *byte = b"AbZz \t\0-"[self.bits(3) as usize];
+        }
+        bytes
+    }
+}
+
+/// How a field is accessed by the C functions.
+enum Kind {
+    /// A scalar value returned by the getter.
+    Scalar,
+    /// An array with the number of elements.
+    Array(usize),
+    /// A scalar `Some` value of an `Option` field.
+    SomeScalar,
+    /// An array `Some` value of an `Option` field with the number of elements.
+    SomeArray(usize),
+    /// `None`, where the getter takes an array with the number of elements, or a scalar.
+    None(Option<usize>),
+}
+
+/// The value of a field of a key in C and in Python, where the inputs are given to the setters
+/// and the outputs are returned by the getters, which are different when the field has a
+/// collation.
+struct Check {
+    field: &'static str,
+    c_type: &'static str,
+    kind: Kind,
+    c_in: String,
+    c_out: String,
+    py_in: String,
+    py_out: String,
+}
+
+impl Check {
+    fn new(field: &'static str, c_type: &'static str, kind: Kind, c: String, py: String) -> Self {
+        Self { field, c_type, kind, c_in: c.clone(), c_out: c, py_in: py.clone(), py_out: py }
+    }
+
+    /// A field with a value that is an integer in both languages.
+    fn int<T: Display>(field: &'static str, c_type: &'static str, value: T) -> Self {
+        Self::new(field, c_type, Kind::Scalar, c_int(c_type, &value), value.to_string())
+    }
+
+    /// A field of an `Option` integer.
+    fn option_int<T: Display>(field: &'static str, c_type: &'static str, value: Option<T>)
+        -> Self
+    {
+        match value {
+            Some(value) => Self::new(field, c_type, Kind::SomeScalar, c_int(c_type, &value),
+                value.to_string()),
+            None => Self::new(field, c_type, Kind::None(None), String::new(), "None".into()),
+        }
+    }
+
+    /// A field of an `Option` of a signed non-zero integer, which stores `None` as the minimum
+    /// value of the field in C.
+    fn option_nonzero<T: Display>(field: &'static str, c_type: &'static str, value: Option<T>,
+        none: i64) -> Self
+    {
+        match value {
+            Some(value) => Self::int(field, c_type, value),
+            None => Self { py_in: "None".into(), py_out: "None".into(),
+                ..Self::int(field, c_type, none) },
+        }
+    }
+
+    /// A byte array field, which is `bytes` in Python unless `py` is given.
+    fn bytes(field: &'static str, input: &[u8], output: &[u8], py: Option<String>) -> Self {
+        let py_in = py.clone().unwrap_or_else(|| py_bytes(input));
+        let py_out = py.unwrap_or_else(|| py_bytes(output));
+        Self { field, c_type: "uint8_t", kind: Kind::Array(input.len()), c_in: c_array(input),
+            c_out: c_array(output), py_in, py_out }
+    }
+
+    /// A field of an `Option` byte array.
+    fn option_bytes(field: &'static str, value: Option<(&[u8], &[u8])>, size: usize,
+        py: Option<String>) -> Self
+    {
+        match value {
+            Some((input, output)) => Self {
+                kind: Kind::SomeArray(input.len()),
+                ..Self::bytes(field, input, output, py)
+            },
+            None => Self::new(field, "uint8_t", Kind::None(Some(size)), String::new(),
+                "None".into()),
+        }
+    }
+
+    /// Write the C code that calls the setter of the field.
+    fn c_set(&self, c: &mut String, prefix: &str) {
+        let _ = match self.kind {
+            Kind::Scalar | Kind::SomeScalar => writeln!(c, "        {}_set_{}(key, {});", prefix,
+                self.field, self.c_in),
+            Kind::Array(_) | Kind::SomeArray(_) => writeln!(c, "        {{ static const {} v[] = \
+                {}; {}_set_{}(key, v); }}", self.c_type, self.c_in, prefix, self.field),
+            Kind::None(_) => writeln!(c, "        {}_clear_{}(key);", prefix, self.field),
+        };
+    }
+
+    /// Write the C code that checks the value returned by the getter of the field.
+    fn c_get(&self, c: &mut String, prefix: &str, what: &str) {
+        let (field, c_type) = (self.field, self.c_type);
+        let _ = match self.kind {
+            Kind::Scalar => writeln!(c, "        CHECK({}_get_{}(expected) == {}, \"{} {}\");",
+                prefix, field, self.c_out, what, field),
+            Kind::Array(count) => writeln!(c, "        {{ static const {0} v[] = {1}; {0} got[{2}]; \
+                {3}_get_{4}(expected, got); CHECK(0 == memcmp(got, v, sizeof(got)), \
+                \"{5} {4}\"); }}", c_type, self.c_out, count, prefix, field, what),
+            Kind::SomeScalar => writeln!(c, "        {{ {0} got; CHECK({1}_get_{2}(expected, &got) \
+                && got == {3}, \"{4} {2}\"); }}", c_type, prefix, field, self.c_out, what),
+            Kind::SomeArray(count) => writeln!(c, "        {{ static const {0} v[] = {1}; \
+                {0} got[{2}]; CHECK({3}_get_{4}(expected, got) && 0 == memcmp(got, v, \
+                sizeof(got)), \"{5} {4}\"); }}", c_type, self.c_out, count, prefix, field, what),
+            Kind::None(Some(count)) => writeln!(c, "        {{ {0} got[{1}]; \
+                CHECK(!{2}_get_{3}(expected, got), \"{4} {3}\"); }}", c_type, count, prefix,
+                field, what),
+            Kind::None(None) => writeln!(c, "        {{ {0} got; CHECK(!{1}_get_{2}(expected, \
+                &got), \"{3} {2}\"); }}", c_type, prefix, field, what),
+        };
+    }
+}
+
+fn c_int<T: Display>(c_type: &str, value: &T) -> String {
+    match c_type {
+        "int64_t" if "-9223372036854775808" == value.to_string() => "INT64_MIN".to_string(),
+        "int8_t" | "int16_t" | "int32_t" | "int64_t" => format!("INT64_C({})", value),
+        _ => format!("UINT64_C({})", value),
+    }
+}
+
+fn c_array<T: Copy + Into<u32>>(values: &[T]) -> String {
+    let values: Vec<String> = values.iter().map(|value| format!("{:#x}", (*value).into()))
+        .collect();
+    format!("{{{}}}", values.join(", "))
+}
+
+fn py_bytes(bytes: &[u8]) -> String {
+    let hex: String = bytes.iter().map(|byte| format!("{:02x}", byte)).collect();
+    format!("bytes.fromhex(\"{}\")", hex)
+}
+
+fn py_str(chars: &[char]) -> String {
+    let escaped: String = chars.iter().map(|c| format!("\\U{:08x}", *c as u32)).collect();
+    format!("\"{}\"", escaped)
+}
+
+fn py_ip<T: Display>(ip: T) -> String {
+    format!("ipaddress.ip_address(\"{}\")", ip)
+}
+
+fn py_uuid(uuid: Uuid) -> String {
+    format!("uuid.UUID(int={})", uuid.as_u128())
+}
+
+fn py_decimal(value: i64, scale: u32) -> String {
+    format!("decimal.Decimal(\"{}\")", Decimal::new(value as i128, scale))
+}
+
+fn socket_v4_bytes(socket: &SocketAddrV4) -> Vec<u8> {
+    let mut bytes = socket.ip().octets().to_vec();
+    bytes.extend_from_slice(&socket.port().to_be_bytes());
+    bytes
+}
+
+fn socket_v6_bytes(socket: &SocketAddrV6) -> Vec<u8> {
+    let mut bytes = socket.ip().octets().to_vec();
+    bytes.extend_from_slice(&socket.port().to_be_bytes());
+    bytes.extend_from_slice(&socket.flowinfo().to_be_bytes());
+    bytes.extend_from_slice(&socket.scope_id().to_be_bytes());
+    bytes
+}
+
+/// A sample key with its layout, and a function that returns a random key encoded by Rust with
+/// the values of its fields.
+struct Sample {
+    key: &'static str,
+    prefix: &'static str,
+    header: String,
+    module: String,
+    random: fn(&mut Rng) -> (Vec<u8>, Vec<Check>),
+}
+
+macro_rules! sample {
+    ($key:ident, $prefix:literal, $random:expr) => {
+        Sample {
+            key: stringify!($key),
+            prefix: $prefix,
+            header: $key::c_header(),
+            module: $key::python_module(),
+            random: $random,
+        }
+    };
+}
+
+fn samples() -> Vec<Sample> {
+    vec![
+        sample!(BitsKey, "bits_key", |rng| {
+            let (priority, shard, adjust) = (rng.bits(4) as u8, rng.bits(12) as u16,
+                rng.signed(3) as i8);
+            let (id, level) = (rng.bits(32) as u32, rng.signed(10) as i16);
+            let key = BitsKey::new(priority, shard, adjust, id, level);
+            (key.as_ref().to_vec(), vec![
+                Check::int("priority", "uint8_t", priority),
+                Check::int("shard", "uint16_t", shard),
+                Check::int("adjust", "int8_t", adjust),
+                Check::int("id", "uint32_t", id),
+                Check::int("level", "int16_t", level),
+            ])
+        }),
+        sample!(OptionKey, "option_key", |rng| {
+            let parent = Some(rng.next()).filter(|_| rng.chance());
+            let kind = Some(rng.signed(8) as i8).filter(|_| rng.chance());
+            let tag = Some([rng.bits(8) as u8, rng.bits(8) as u8, rng.bits(8) as u8])
+                .filter(|_| rng.chance());
+            let offset = Some(rng.signed(24) as i32).filter(|_| rng.chance());
+            let id = rng.bits(16) as u16;
+            let key = OptionKey::new(parent, kind, tag, offset, id);
+            (key.as_ref().to_vec(), vec![
+                Check::option_int("parent", "uint64_t", parent),
+                Check::option_int("kind", "int8_t", kind),
+                Check::option_bytes("tag", tag.as_ref().map(|tag| (&tag[..], &tag[..])), 3, None),
+                Check::option_int("offset", "int32_t", offset),
+                Check::int("id", "uint16_t", id),
+            ])
+        }),
+        sample!(AmountKey, "amount_key", |rng| {
+            let (amount, rate, entry) = (rng.next() as i64, rng.bits(24) as u32,
+                rng.bits(32) as u32);
+            let key = AmountKey::new(amount, rate, entry);
+            (key.as_ref().to_vec(), vec![
+                Check { py_in: py_decimal(amount, 4), py_out: py_decimal(amount, 4),
+                    ..Check::int("amount", "int64_t", amount) },
+                Check { py_in: py_decimal(rate as i64, 2), py_out: py_decimal(rate as i64, 2),
+                    ..Check::int("rate", "uint32_t", rate) },
+                Check::int("entry", "uint32_t", entry),
+            ])
+        }),
+        sample!(OrderKey, "order_key", |rng| {
+            let customer = NonZeroU64::new(rng.next() | 1).unwrap();
+            let parent = NonZeroU64::new(rng.bits(48)).filter(|_| rng.chance());
+            let delta = NonZeroI16::new(rng.signed(16) as i16 | 1).unwrap();
+            let key = OrderKey::new(customer, parent, delta);
+            let parent_check = match parent {
+                Some(parent) => Check::int("parent", "uint64_t", parent),
+                None => Check { py_in: "None".into(), py_out: "None".into(),
+                    ..Check::int("parent", "uint64_t", 0) },
+            };
+            (key.as_ref().to_vec(), vec![
+                Check::int("customer", "uint64_t", customer),
+                parent_check,
+                Check::int("delta", "int16_t", delta),
+            ])
+        }),
+        sample!(AdjustmentKey, "adjustment_key", |rng| {
+            let account = rng.bits(32) as u32;
+            let adjustment = NonZeroI32::new(rng.signed(32) as i32)
+                .filter(|adjustment| i32::MIN != adjustment.get() && rng.chance());
+            let correction = NonZeroI32::new(rng.signed(24) as i32)
+                .filter(|correction| -0x80_0000 != correction.get() && rng.chance());
+            let step = NonZeroI8::new(rng.signed(4) as i8)
+                .filter(|step| -8 != step.get() && rng.chance());
+            let key = AdjustmentKey::new(account, adjustment, correction, step);
+            (key.as_ref().to_vec(), vec![
+                Check::int("account", "uint32_t", account),
+                Check::option_nonzero("adjustment", "int32_t", adjustment, i32::MIN as i64),
+                Check::option_nonzero("correction", "int32_t", correction, -0x80_0000),
+                Check::option_nonzero("step", "int8_t", step, -8),
+            ])
+        }),
+        sample!(WordKey, "word_key", |rng| {
+            let (initial, letters) = (rng.char(), [rng.char(), rng.char(), rng.char()]);
+            let last = Some(rng.char()).filter(|_| rng.chance());
+            let key = WordKey::new(initial, letters, last);
+            let last_check = match last {
+                Some(last) => Check::new("last", "uint32_t", Kind::SomeScalar,
+                    format!("{:#x}", last as u32), py_str(&[last])),
+                None => Check::new("last", "uint32_t", Kind::None(None), String::new(),
+                    "None".into()),
+            };
+            (key.as_ref().to_vec(), vec![
+                Check::new("initial", "uint32_t", Kind::Scalar, format!("{:#x}", initial as u32),
+                    py_str(&[initial])),
+                Check::new("letters", "uint32_t", Kind::Array(3), c_array(&letters),
+                    py_str(&letters)),
+                last_check,
+            ])
+        }),
+        sample!(LoginKey, "login_key", |rng| {
+            let (user, tag) = (rng.text::<8>(), rng.text::<2>());
+            let domain = Some(rng.text::<6>()).filter(|_| rng.chance());
+            let key = LoginKey::new(user, domain, tag);
+            let stored_domain = key.domain();
+            (key.as_ref().to_vec(), vec![
+                Check::bytes("user", &user, key.user(), None),
+                Check::option_bytes("domain", domain.as_ref().zip(stored_domain.as_ref())
+                    .map(|(input, output)| (&input[..], &output[..])), 6, None),
+                Check::bytes("tag", &tag, key.tag(), None),
+            ])
+        }),
+        sample!(EventKey, "event_key", |rng| {
+            let (time, updated, ttl) = (rng.next(), rng.bits(48), rng.bits(32));
+            let expires = Some(rng.bits(40)).filter(|_| rng.chance());
+            let key = EventKey::new(UNIX_EPOCH + Duration::from_nanos(time),
+                UNIX_EPOCH + Duration::from_millis(updated), Duration::from_secs(ttl),
+                expires.map(|expires| UNIX_EPOCH + Duration::from_secs(expires)));
+            (key.as_ref().to_vec(), vec![
+                Check::int("time", "uint64_t", time),
+                Check::int("updated", "uint64_t", updated),
+                Check::int("ttl", "uint64_t", ttl),
+                Check::option_int("expires", "uint64_t", expires),
+            ])
+        }),
+        sample!(FlowKey, "flow_key", |rng| {
+            let tenant = rng.bits(16) as u16;
+            let source = Ipv4Addr::from(rng.bits(32) as u32);
+            let destination = Ipv6Addr::from(((rng.next() as u128) << 64) | rng.next() as u128);
+            let client = SocketAddrV4::new(Ipv4Addr::from(rng.bits(32) as u32),
+                rng.bits(16) as u16);
+            let server = SocketAddrV6::new(Ipv6Addr::from(rng.next() as u128),
+                rng.bits(16) as u16, rng.bits(32) as u32, rng.bits(32) as u32);
+            let gateway = Some(Ipv4Addr::from(rng.bits(32) as u32)).filter(|_| rng.chance());
+            let key = FlowKey::new(tenant, source, destination, client, server, gateway);
+            let client_py = format!("({}, {})", py_ip(client.ip()), client.port());
+            let server_py = format!("({}, {}, {}, {})", py_ip(server.ip()), server.port(),
+                server.flowinfo(), server.scope_id());
+            let gateway_octets = gateway.map(|gateway| gateway.octets());
+            (key.as_ref().to_vec(), vec![
+                Check::int("tenant", "uint16_t", tenant),
+                Check::bytes("source", &source.octets(), &source.octets(), Some(py_ip(source))),
+                Check::bytes("destination", &destination.octets(), &destination.octets(),
+                    Some(py_ip(destination))),
+                Check::bytes("client", &socket_v4_bytes(&client), &socket_v4_bytes(&client),
+                    Some(client_py)),
+                Check::bytes("server", &socket_v6_bytes(&server), &socket_v6_bytes(&server),
+                    Some(server_py)),
+                Check::option_bytes("gateway", gateway_octets.as_ref()
+                    .map(|octets| (&octets[..], &octets[..])), 4, gateway.map(py_ip)),
+            ])
+        }),
+        sample!(RecordKey, "record_key", |rng| {
+            let table = Uuid::from_u128(((rng.next() as u128) << 64) | rng.next() as u128);
+            let parent = Some(Uuid::from_u128(rng.next() as u128)).filter(|_| rng.chance());
+            let number = rng.bits(32) as u32;
+            let key = RecordKey::new(table, parent, number);
+            (key.as_ref().to_vec(), vec![
+                Check::bytes("table", table.as_bytes(), table.as_bytes(), Some(py_uuid(table))),
+                Check::option_bytes("parent", parent.as_ref()
+                    .map(|parent| (&parent.as_bytes()[..], &parent.as_bytes()[..])), 16,
+                    parent.map(py_uuid)),
+                Check::int("number", "uint32_t", number),
+            ])
+        }),
+        sample!(ActivityKey, "activity_key", |rng| {
+            let (user, tag) = (rng.next(), rng.text::<4>());
+            let parent = Some(rng.bits(32) as u32).filter(|_| rng.chance());
+            let (owner, kind) = (NonZeroU64::new(rng.next() | 1).unwrap(), rng.bits(8) as u8);
+            let key = ActivityKey::new(user, tag, parent, owner, kind);
+            (key.as_ref().to_vec(), vec![
+                Check::int("user", "uint64_t", user),
+                Check::bytes("tag", &tag, &tag, None),
+                Check::option_int("parent", "uint32_t", parent),
+                Check::int("owner", "uint64_t", owner),
+                Check::int("kind", "uint8_t", kind),
+            ])
+        }),
+        sample!(ReservedKey, "reserved_key", |rng| {
+            let (account, entry) = (rng.bits(32) as u32, rng.bits(16) as u16);
+            let key = ReservedKey::new(account, entry);
+            (key.as_ref().to_vec(), vec![
+                Check::int("account", "uint32_t", account),
+                Check::int("entry", "uint16_t", entry),
+            ])
+        }),
+    ]
+}
+
+/// Return the directory of the generated files.
+fn test_dir() -> PathBuf {
+    let dir = Path::new(env!("CARGO_TARGET_TMPDIR")).join("codegen");
+    std::fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+/// Return true if the program can be run.
+fn available(program: &str, arg: &str) -> bool {
+    matches!(Command::new(program).arg(arg).output(), Ok(output) if output.status.success())
+}
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|byte| format!("{:02x}", byte)).collect()
+}
+
+#[test]
+#[ignore = "needs a C compiler (CC or cc)"]
+fn c_header_round_trip() {
+    let cc = std::env::var("CC").unwrap_or_else(|_| "cc".to_string());
+    assert!(available(&cc, "--version"), "The C round trip test needs {}.", cc);
+    let dir = test_dir();
+    let mut rng = Rng(0xC0DE);
+    let mut header = String::new();
+    let mut program = String::from("#include <stdio.h>\n#include \"keys.h\"\n\n\
+        static int failures = 0;\n\
+        #define CHECK(condition, what) do { if (!(condition)) { \
+        printf(\"failed: %s\\n\", what); failures++; } } while (0)\n\n\
+        int main(void) {\n");
+    for sample in samples() {
+        header.push_str(&sample.header);
+        let upper = sample.prefix.to_ascii_uppercase();
+        for i in 0..KEYS {
+            let (key, checks) = (sample.random)(&mut rng);
+            let what = format!("{} {}", sample.key, i);
+            let _ = writeln!(program, "    {{\n        static const uint8_t expected[] = {};",
+                c_array(&key));
+            let _ = writeln!(program, "        uint8_t key[{}_LENGTH];\n        {}_init(key);",
+                upper, sample.prefix);
+            for check in checks.iter() {
+                check.c_set(&mut program, sample.prefix);
+            }
+            let _ = writeln!(program, "        CHECK(sizeof(expected) == {0}_LENGTH && \
+                0 == memcmp(key, expected, {0}_LENGTH), \"{1} encode\");", upper, what);
+            for check in checks.iter() {
+                check.c_get(&mut program, sample.prefix, &what);
+            }
+            program.push_str("    }\n");
+        }
+    }
+    program.push_str("    CHECK(2 == ACTIVITY_KEY_VERSION, \"ActivityKey version\");\n");
+    program.push_str("    printf(\"%d failures\\n\", failures);\n    return 0 != failures;\n}\n");
+    std::fs::write(dir.join("keys.h"), &header).unwrap();
+    std::fs::write(dir.join("round_trip.c"), &program).unwrap();
+    let binary = dir.join("round_trip");
+    let output = Command::new(&cc)
+        .args(["-std=c99", "-Wall", "-Wextra", "-Werror", "-o"])
+        .arg(&binary)
+        .arg(dir.join("round_trip.c"))
+        .output()
+        .unwrap();
+    assert!(output.status.success(), "Failed to compile the C round trip test:\n{}",
+        String::from_utf8_lossy(&output.stderr));
+    let output = Command::new(&binary).output().unwrap();
+    assert!(output.status.success(), "The C round trip test failed:\n{}",
+        String::from_utf8_lossy(&output.stdout));
+}
+
+#[test]
+#[ignore = "needs python3"]
+fn python_module_round_trip() {
+    assert!(available("python3", "--version"), "The Python round trip test needs python3.");
+    let dir = test_dir();
+    let mut rng = Rng(0x5EED);
+    let mut module = String::new();
+    let mut script = String::from("import decimal\nimport ipaddress\nimport uuid\n\
+        import keys\n\nfailures = 0\n\n\n\
+        def check(condition, what):\n    global failures\n    if not condition:\n        \
+        print(\"failed: \" + what)\n        failures += 1\n\n\n");
+    for sample in samples() {
+        module.push_str(&sample.module);
+        module.push('\n');
+        for i in 0..KEYS {
+            let (key, checks) = (sample.random)(&mut rng);
+            let what = format!("{} {}", sample.key, i);
+            let values: Vec<String> = checks.iter()
+                .map(|check| format!("{}={}", check.field, check.py_in))
+                .collect();
+            let _ = writeln!(script, "key = keys.{}.encode({})", sample.key, values.join(", "));
+            let _ = writeln!(script, "check(key == bytes.fromhex(\"{}\"), \"{} encode\")",
+                hex(&key), what);
+            let _ = writeln!(script, "values = keys.{}.decode(key)", sample.key);
+            for check in checks.iter() {
+                let _ = writeln!(script, "check(values[\"{0}\"] == {1}, \"{2} {0}\")",
+                    check.field, check.py_out, what);
+            }
+        }
+    }
+    script.push_str("check(keys.ActivityKey.decode(keys.ActivityKey.DEFAULT_KEY)[\"_version\"] \
+        == 2, \"ActivityKey version\")\n");
+    script.push_str("print(\"%d failures\" % failures)\nraise SystemExit(failures != 0)\n");
+    std::fs::write(dir.join("keys.py"), &module).unwrap();
+    std::fs::write(dir.join("round_trip.py"), &script).unwrap();
+    let output = Command::new("python3")
+        .arg("round_trip.py")
+        .current_dir(&dir)
+        .output()
+        .unwrap();
+    assert!(output.status.success(), "The Python round trip test failed:\n{}{}",
+        String::from_utf8_lossy(&output.stdout), String::from_utf8_lossy(&output.stderr));
+}
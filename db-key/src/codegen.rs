@@ -0,0 +1,666 @@
+use std::fmt::Write;
+use crate::{FieldSchema, KeySchema};
+
+/// The functions shared by the C headers of all the keys, which are only defined once when the
+/// headers of several keys are included.
+const C_HELPERS: &str = r#"#ifndef DB_KEY_HELPERS
+#define DB_KEY_HELPERS
+
+/* Return the bits bits starting bit_offset bits from the start of the key. */
+static inline uint64_t db_key_load(const uint8_t *key, size_t bit_offset, size_t bits) {
+    uint64_t value = 0;
+    for (size_t i = 0; i < bits; i++) {
+        size_t bit = bit_offset + i;
+        value = (value << 1) | (uint64_t)((key[bit / 8] >> (7 - bit % 8)) & 1);
+    }
+    return value;
+}
+
+/* Store the bits low bits of the value starting bit_offset bits from the start of the key. */
+static inline void db_key_store(uint8_t *key, size_t bit_offset, size_t bits, uint64_t value) {
+    for (size_t i = 0; i < bits; i++) {
+        size_t bit = bit_offset + i;
+        uint8_t mask = (uint8_t)(0x80 >> (bit % 8));
+        if ((value >> (bits - 1 - i)) & 1) {
+            key[bit / 8] |= mask;
+        }
+        else {
+            key[bit / 8] &= (uint8_t)~mask;
+        }
+    }
+}
+
+/* Copy the bits bits starting at bit_offset into the right-aligned bits of value, which has
+ * (bits + 7) / 8 bytes. */
+static inline void db_key_load_bytes(const uint8_t *key, size_t bit_offset, size_t bits,
+    uint8_t *value)
+{
+    size_t skip = (bits + 7) / 8 * 8 - bits;
+    memset(value, 0, (bits + 7) / 8);
+    for (size_t i = 0; i < bits; i++) {
+        size_t bit = bit_offset + i;
+        if ((key[bit / 8] >> (7 - bit % 8)) & 1) {
+            value[(skip + i) / 8] |= (uint8_t)(0x80 >> ((skip + i) % 8));
+        }
+    }
+}
+
+/* Copy the right-aligned bits of value, which has (bits + 7) / 8 bytes, into the bits bits
+ * starting at bit_offset. */
+static inline void db_key_store_bytes(uint8_t *key, size_t bit_offset, size_t bits,
+    const uint8_t *value)
+{
+    size_t skip = (bits + 7) / 8 * 8 - bits;
+    for (size_t i = 0; i < bits; i++) {
+        db_key_store(key, bit_offset + i, 1, (value[(skip + i) / 8] >> (7 - (skip + i) % 8)) & 1);
+    }
+}
+
+/* Return the character of a stored scalar value, or U+FFFD if it isn't a valid scalar value. */
+static inline uint32_t db_key_char(uint64_t value) {
+    if (value > 0x10FFFF || (value >= 0xD800 && value <= 0xDFFF)) {
+        return 0xFFFD;
+    }
+    return (uint32_t)value;
+}
+
+static inline bool db_key_is_space(uint8_t byte) {
+    return ' ' == byte || '\t' == byte || '\n' == byte || '\f' == byte || '\r' == byte;
+}
+
+/* Normalize a byte array field with the ascii_ci collation. */
+static inline void db_key_ascii_ci(uint8_t *value, size_t size) {
+    for (size_t i = 0; i < size; i++) {
+        if (value[i] >= 'A' && value[i] <= 'Z') {
+            value[i] = (uint8_t)(value[i] + ('a' - 'A'));
+        }
+    }
+}
+
+/* Normalize a byte array field with the ascii_ci_trim collation. */
+static inline void db_key_ascii_ci_trim(uint8_t *value, size_t size) {
+    size_t start = 0;
+    size_t end = size;
+    while (start < size && db_key_is_space(value[start])) {
+        start++;
+    }
+    while (start < end && (db_key_is_space(value[end - 1]) || 0 == value[end - 1])) {
+        end--;
+    }
+    memmove(value, value + start, end - start);
+    memset(value + (end - start), 0, size - (end - start));
+    db_key_ascii_ci(value, size);
+}
+
+#endif /* DB_KEY_HELPERS */
+"#;
+
+/// The functions shared by the Python modules of all the keys.
+const PYTHON_HELPERS: &str = r#"import decimal as _decimal
+import ipaddress as _ipaddress
+import uuid as _uuid
+
+# The presence byte of Some values of Option fields.
+_DB_KEY_SOME = {"none_first": 1, "none_last": 0}
+_DB_KEY_SPACES = b" \t\n\x0c\r"
+
+
+def _db_key_load(key, bit_offset, bits):
+    shift = len(key) * 8 - bit_offset - bits
+    return (int.from_bytes(key, "big") >> shift) & ((1 << bits) - 1)
+
+
+def _db_key_store(key, bit_offset, bits, value):
+    if value < 0 or value >> bits:
+        raise ValueError("%d doesn't fit in %d bits" % (value, bits))
+    shift = len(key) * 8 - bit_offset - bits
+    mask = ((1 << bits) - 1) << shift
+    stored = (int.from_bytes(key, "big") & ~mask) | (value << shift)
+    return stored.to_bytes(len(key), "big")
+
+
+def _db_key_decode_value(field, stored, bits):
+    encoding = field["encoding"]
+    if encoding in ("uint", "int"):
+        value = stored - (1 << (bits - 1)) if field["sign_flip"] else stored
+        if field["decimal_scale"] is not None:
+            return _decimal.Decimal(value).scaleb(-field["decimal_scale"])
+        return value
+    if encoding == "char":
+        width = field["char_size"] * 8
+        chars = []
+        for shift in range(bits - width, -1, -width):
+            code = (stored >> shift) & ((1 << width) - 1)
+            valid = code < 0x110000 and not 0xD800 <= code <= 0xDFFF
+            chars.append(chr(code) if valid else "\ufffd")
+        return "".join(chars)
+    if encoding == "ipv4":
+        return _ipaddress.IPv4Address(stored)
+    if encoding == "ipv6":
+        return _ipaddress.IPv6Address(stored)
+    if encoding == "socket_v4":
+        return (_ipaddress.IPv4Address(stored >> 16), stored & 0xFFFF)
+    if encoding == "socket_v6":
+        return (_ipaddress.IPv6Address(stored >> 80), (stored >> 64) & 0xFFFF,
+                (stored >> 32) & 0xFFFFFFFF, stored & 0xFFFFFFFF)
+    if encoding == "uuid":
+        return _uuid.UUID(int=stored)
+    if encoding in ("duration", "system_time"):
+        return stored
+    return stored.to_bytes(bits // 8, "big")
+
+
+def _db_key_encode_value(field, value, bits):
+    encoding = field["encoding"]
+    if encoding in ("uint", "int"):
+        scale = field["decimal_scale"]
+        if scale is not None:
+            scaled = _decimal.Decimal(value).scaleb(scale)
+            if scaled != scaled.to_integral_value():
+                raise ValueError("%s has more than %d fractional digits" % (value, scale))
+            value = int(scaled)
+        if field["sign_flip"]:
+            sign = 1 << (bits - 1)
+            if not -sign <= value < sign:
+                raise ValueError("%d doesn't fit in %d bits" % (value, bits))
+            return value + sign
+        return value
+    if encoding == "char":
+        width = field["char_size"] * 8
+        if len(value) * width != bits:
+            raise ValueError("expected %d characters" % (bits // width))
+        stored = 0
+        for char in value:
+            stored = (stored << width) | ord(char)
+        return stored
+    if encoding == "ipv4":
+        return int(_ipaddress.IPv4Address(value))
+    if encoding == "ipv6":
+        return int(_ipaddress.IPv6Address(value))
+    if encoding == "socket_v4":
+        address, port = value
+        return (int(_ipaddress.IPv4Address(address)) << 16) | port
+    if encoding == "socket_v6":
+        address, port, flowinfo, scope_id = value
+        return ((int(_ipaddress.IPv6Address(address)) << 80) | (port << 64) | (flowinfo << 32)
+                | scope_id)
+    if encoding == "uuid":
+        return _uuid.UUID(str(value)).int
+    if encoding in ("duration", "system_time"):
+        return value
+    if isinstance(value, str):
+        value = value.encode()
+    size = bits // 8
+    if len(value) > size:
+        raise ValueError("%r is longer than %d bytes" % (value, size))
+    value = bytes(value).ljust(size, b"\0")
+    if field["collation"] == "ascii_ci_trim":
+        value = value.lstrip(_DB_KEY_SPACES).rstrip(_DB_KEY_SPACES + b"\0").ljust(size, b"\0")
+    if field["collation"] is not None:
+        value = value.lower()
+    return int.from_bytes(value, "big")
+
+
+def _db_key_decode(layout, key):
+    key = bytes(key)
+    if len(key) != layout.LENGTH:
+        raise ValueError("a %s has %d bytes" % (layout.__name__, layout.LENGTH))
+    values = {}
+    for name, field in layout.FIELDS.items():
+        if field["padding"]:
+            continue
+        offset, bits = field["bit_offset"], field["bits"]
+        if field["option"] in _DB_KEY_SOME:
+            if _db_key_load(key, offset, 8) != _DB_KEY_SOME[field["option"]]:
+                values[name] = None
+                continue
+            offset, bits = offset + 8, bits - 8
+        stored = _db_key_load(key, offset, bits)
+        value = _db_key_decode_value(field, stored, bits)
+        if field["option"] == "zero" and (stored == 0 or value == 0):
+            value = None
+        values[name] = value
+    return values
+
+
+def _db_key_encode(layout, values):
+    key = layout.DEFAULT_KEY
+    for name, value in values.items():
+        field = layout.FIELDS.get(name)
+        if field is None:
+            raise ValueError("%s doesn't have the %s field" % (layout.__name__, name))
+        if field["padding"] or field["version"]:
+            raise ValueError("the %s field can't be set" % name)
+        offset, bits = field["bit_offset"], field["bits"]
+        if field["option"] in _DB_KEY_SOME:
+            some = _DB_KEY_SOME[field["option"]]
+            if value is None:
+                key = _db_key_store(key, offset, bits, (1 - some) << (bits - 8))
+                continue
+            key = _db_key_store(key, offset, 8, some)
+            offset, bits = offset + 8, bits - 8
+        elif value is None and field["option"] == "zero":
+            key = _db_key_store(key, offset, bits, 0)
+            continue
+        elif field["nonzero"] and value == 0:
+            raise ValueError("the %s field can't be zero" % name)
+        stored = _db_key_encode_value(field, value, bits)
+        if field["option"] == "zero" and stored == 0:
+            raise ValueError("the minimum value of the %s field is reserved for None" % name)
+        key = _db_key_store(key, offset, bits, stored)
+    return key
+"#;
+
+/// Return the name of the key in snake case (e.g. `user_key` for `UserKey`).
+fn snake_case(name: &str) -> String {
+    let chars: Vec<char> = name.chars().collect();
+    let mut snake = String::with_capacity(name.len() + 4);
+    for (i, c) in chars.iter().enumerate() {
+        if c.is_ascii_uppercase() {
+            // Acronyms stay together (e.g. `uuid_key` for `UUIDKey`).
+            let after_lower = 0 < i && !chars[i - 1].is_ascii_uppercase() && '_' != chars[i - 1];
+            let acronym_end = 0 < i && chars[i - 1].is_ascii_uppercase()
+                && chars.get(i + 1).map_or(false, |next| next.is_ascii_lowercase());
+            if after_lower || acronym_end {
+                snake.push('_');
+            }
+            snake.push(c.to_ascii_lowercase());
+        }
+        else {
+            snake.push(*c);
+        }
+    }
+    snake
+}
+
+/// Return the text without the sequences that would end a C comment.
+fn c_comment(text: &str) -> String {
+    text.replace("*/", "* /")
+}
+
+impl FieldSchema {
+    /// Return the type of the value of the field without `Option` and with the primitive type of
+    /// non-zero integers (e.g. `u64` for `Option<NonZeroU64>`).
+    fn value_type(&self) -> String {
+        let rust_type = self.rust_type.strip_prefix("Option<")
+            .and_then(|inner| inner.strip_suffix('>'))
+            .unwrap_or(self.rust_type);
+        match rust_type.strip_prefix("NonZero") {
+            Some(integer) => integer.to_ascii_lowercase(),
+            None => rust_type.to_string(),
+        }
+    }
+
+    /// Return true if the field is a non-zero integer.
+    fn is_nonzero(&self) -> bool {
+        self.rust_type.contains("NonZero")
+    }
+
+    /// Return the presence byte of `Some` values of `Option` fields with a presence byte.
+    fn some_marker(&self) -> Option<u8> {
+        match self.option {
+            Some("none_first") => Some(1),
+            Some("none_last") => Some(0),
+            _ => None,
+        }
+    }
+
+    /// Return the offset and the number of bits of the value of the field, which is after the
+    /// presence byte of `Option` fields.
+    fn value_bits(&self) -> (usize, usize) {
+        match self.some_marker() {
+            Some(_) => (self.bit_offset + 8, self.bits - 8),
+            None => (self.bit_offset, self.bits),
+        }
+    }
+
+    /// Return the C type of the fields that are accessed as a scalar value, which are the
+    /// integers of up to 64 bits, the times, and single characters.
+    fn c_scalar_type(&self) -> Option<&'static str> {
+        match self.encoding {
+            "uint" | "int" => match self.value_type().as_str() {
+                "u8" => Some("uint8_t"),
+                "u16" => Some("uint16_t"),
+                "u32" => Some("uint32_t"),
+                "u64" => Some("uint64_t"),
+                "i8" => Some("int8_t"),
+                "i16" => Some("int16_t"),
+                "i32" => Some("int32_t"),
+                "i64" => Some("int64_t"),
+                _ => None,
+            },
+            "duration" | "system_time" => Some("uint64_t"),
+            "char" if "char" == self.value_type() => Some("uint32_t"),
+            _ => None,
+        }
+    }
+}
+
+impl KeySchema {
+    /// Return a C header with the layout of the key and the functions that encode and decode
+    /// its fields, which match the Rust encoding bit for bit. The `default` key is copied by the
+    /// `<key>_init()` function, so it should be the default key of the Rust key.
+    ///
+    /// The header defines the `<KEY>_LENGTH` and `<KEY>_SCHEMA_HASH` of the key, and the
+    /// `_OFFSET`, `_SIZE`, `_BIT_OFFSET`, and `_BITS` of each field, where `<KEY>` is the name of
+    /// the key in upper snake case. The functions take a pointer to the bytes of the key:
+    ///
+    /// - Integers of up to 64 bits, times (as a count of their unit), and single characters
+    ///   (as a `uint32_t` scalar value) have `<key>_get_<field>()` and `<key>_set_<field>()`
+    ///   functions that return and take the value. The values are truncated to the size of the
+    ///   field instead of panicking.
+    /// - The other fields are copied to and from arrays of their stored bytes (e.g. the octets
+    ///   of an address followed by the port), or of `uint32_t` for character arrays.
+    /// - The getters of `Option` fields with a presence byte return whether the value is `Some`,
+    ///   and they have a `<key>_clear_<field>()` function that stores `None`. Optional non-zero
+    ///   integers are `None` when they are zero.
+    /// - Padding fields don't have functions, and version fields only have a getter.
+    ///
+    /// The header can be included with the headers of other keys. It can be generated by a
+    /// build script with the `c_header()` function of keys with the `schema_json` option.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use db_key::{FieldSchema, KeySchema};
+    ///
+    /// const SCHEMA: KeySchema = KeySchema {
+    ///     key: "UserKey",
+    ///     length: 3,
+    ///     hash: 0x1234,
+    ///     raw_debug: "compact",
+    ///     custom_debug: false,
+    ///     fields: &[
+    ///         FieldSchema {
+    ///             name: "user",
+    ///             label: "User",
+    ///             rust_type: "u16",
+    ///             encoding: "uint",
+    ///             bit_offset: 0,
+    ///             bits: 16,
+    ///             ..FieldSchema::DEFAULT
+    ///         },
+    ///         FieldSchema {
+    ///             name: "level",
+    ///             label: "Level",
+    ///             rust_type: "i8",
+    ///             encoding: "int",
+    ///             bit_offset: 16,
+    ///             bits: 8,
+    ///             sign_flip: true,
+    ///             ..FieldSchema::DEFAULT
+    ///         },
+    ///     ],
+    /// };
+    ///
+    /// let header = SCHEMA.c_header(&[0x00, 0x00, 0x80]);
+    ///
+    /// assert!(header.contains("#define USER_KEY_LENGTH 3\n"));
+    /// assert!(header.contains("#define USER_KEY_LEVEL_OFFSET 2\n"));
+    /// assert!(header.contains("static inline int8_t user_key_get_level(const uint8_t *key)"));
+    /// assert!(header.contains("static inline void user_key_set_level(uint8_t *key, int8_t value)"));
+    /// ```
+    pub fn c_header(&self, default: &[u8]) -> String {
+        let snake = snake_case(self.key);
+        let upper = snake.to_ascii_uppercase();
+        let mut c = String::new();
+        let _ = writeln!(c, "/* The layout of {}, generated by db-key. Don't edit it. */", self.key);
+        let _ = writeln!(c, "#ifndef DB_KEY_{}_H\n#define DB_KEY_{}_H\n", upper, upper);
+        c.push_str("#include <stdbool.h>\n#include <stddef.h>\n#include <stdint.h>\n");
+        c.push_str("#include <string.h>\n\n");
+        c.push_str(C_HELPERS);
+        let _ = writeln!(c, "\n#define {}_LENGTH {}", upper, self.length);
+        let _ = writeln!(c, "#define {}_SCHEMA_HASH UINT64_C({:#018x})", upper, self.hash);
+        for field in self.fields.iter() {
+            let prefix = format!("{}_{}", upper, field.name.to_ascii_uppercase());
+            let _ = writeln!(c, "\n/* {}: {} */", c_comment(field.label),
+                c_comment(field.rust_type));
+            let _ = writeln!(c, "#define {}_OFFSET {}", prefix, field.bit_offset / 8);
+            let _ = writeln!(c, "#define {}_SIZE {}", prefix,
+                (field.bit_offset + field.bits + 7) / 8 - field.bit_offset / 8);
+            let _ = writeln!(c, "#define {}_BIT_OFFSET {}", prefix, field.bit_offset);
+            let _ = writeln!(c, "#define {}_BITS {}", prefix, field.bits);
+            if let Some(scale) = field.decimal_scale {
+                let _ = writeln!(c, "#define {}_DECIMAL_SCALE {}", prefix, scale);
+            }
+            if field.version && field.bit_offset / 8 < default.len() {
+                let _ = writeln!(c, "#define {}_VERSION {}", upper, default[field.bit_offset / 8]);
+            }
+        }
+        let _ = write!(c, "\n/* Initialize the key with the default key. */\n\
+            static inline void {0}_init(uint8_t *key) {{\n    \
+            static const uint8_t DEFAULT_KEY[{1}_LENGTH] = {{", snake, upper);
+        for (i, byte) in default.iter().enumerate() {
+            let _ = write!(c, "{}{:#04x}", if 0 == i { "" } else { ", " }, byte);
+        }
+        let _ = writeln!(c, "}};\n    memcpy(key, DEFAULT_KEY, {}_LENGTH);\n}}", upper);
+        for field in self.fields.iter().filter(|field| !field.padding) {
+            c_accessors(&mut c, &snake, field);
+        }
+        let _ = writeln!(c, "\n#endif /* DB_KEY_{}_H */", upper);
+        c
+    }
+
+    /// Return a Python module with a class for the key, which has the layout of the key and
+    /// `decode()` and `encode()` class methods that match the Rust encoding bit for bit.
+    ///
+    /// `decode(key)` returns a dictionary with the values of the fields other than padding.
+    /// `encode(**values)` returns the bytes of the key with the values of the fields, where the
+    /// other fields keep their values in the `default` key, so it should be the default key of
+    /// the Rust key. The fields can't be stored with the `struct` module, because they can be
+    /// bit fields or have odd sizes, so the values are converted with Python integers:
+    ///
+    /// - Integers are `int`, or `decimal.Decimal` for fields with the `decimal` attribute.
+    /// - Characters are a `str` of the characters of the field.
+    /// - Addresses are `ipaddress` addresses, and socket addresses are tuples of the address,
+    ///   the port, and the flow info and scope ID of IPv6 sockets.
+    /// - UUIDs are `uuid.UUID`, and times are an `int` count of the `time_unit` of the field.
+    /// - Byte arrays and `codec` fields are `bytes`. Shorter values (or `str`) are padded with
+    ///   zeros, and the collation of the field is applied.
+    /// - `None` is `None`.
+    ///
+    /// The module can be concatenated with the modules of other keys. It can be generated by a
+    /// build script with the `python_module()` function of keys with the `schema_json` option.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use db_key::{FieldSchema, KeySchema};
+    ///
+    /// const SCHEMA: KeySchema = KeySchema {
+    ///     key: "UserKey",
+    ///     length: 2,
+    ///     hash: 0x1234,
+    ///     raw_debug: "compact",
+    ///     custom_debug: false,
+    ///     fields: &[
+    ///         FieldSchema {
+    ///             name: "user",
+    ///             label: "User",
+    ///             rust_type: "u16",
+    ///             encoding: "uint",
+    ///             bit_offset: 0,
+    ///             bits: 16,
+    ///             ..FieldSchema::DEFAULT
+    ///         },
+    ///     ],
+    /// };
+    ///
+    /// let module = SCHEMA.python_module(&[0x00, 0x01]);
+    ///
+    /// assert!(module.contains("class UserKey:\n"));
+    /// assert!(module.contains("    DEFAULT_KEY = bytes.fromhex(\"0001\")\n"));
+    /// assert!(module.contains("        \"user\": {\"encoding\": \"uint\", \"bit_offset\": 0, "));
+    /// ```
+    pub fn python_module(&self, default: &[u8]) -> String {
+        let mut py = String::new();
+        let _ = writeln!(py, "\"\"\"The layout of {}, generated by db-key. Don't edit it.\"\"\"\n",
+            self.key);
+        py.push_str(PYTHON_HELPERS);
+        let _ = writeln!(py, "\n\nclass {}:", self.key);
+        let _ = writeln!(py, "    \"\"\"The layout of the {} key.\"\"\"\n", self.key);
+        let _ = writeln!(py, "    LENGTH = {}", self.length);
+        let _ = writeln!(py, "    SCHEMA_HASH = {:#018x}", self.hash);
+        py.push_str("    DEFAULT_KEY = bytes.fromhex(\"");
+        for byte in default.iter() {
+            let _ = write!(py, "{:02x}", byte);
+        }
+        py.push_str("\")\n    FIELDS = {\n");
+        let py_str = |value: Option<&str>| match value {
+            Some(value) => format!("\"{}\"", value),
+            None => "None".to_string(),
+        };
+        let py_bool = |value: bool| if value { "True" } else { "False" };
+        for field in self.fields.iter() {
+            let _ = writeln!(py, concat!("        \"{}\": {{\"encoding\": \"{}\", ",
+                    "\"bit_offset\": {}, \"bits\": {}, \"sign_flip\": {}, \"option\": {}, ",
+                    "\"nonzero\": {}, \"time_unit\": {}, \"char_size\": {}, \"collation\": {}, ",
+                    "\"decimal_scale\": {}, \"computed\": {}, \"padding\": {}, \"version\": {}}},"),
+                field.name, field.encoding, field.bit_offset, field.bits,
+                py_bool(field.sign_flip), py_str(field.option), py_bool(field.is_nonzero()),
+                py_str(field.time_unit),
+                field.char_size.map_or("None".to_string(), |size| size.to_string()),
+                py_str(field.collation),
+                field.decimal_scale.map_or("None".to_string(), |scale| scale.to_string()),
+                py_bool(field.computed), py_bool(field.padding), py_bool(field.version));
+        }
+        py.push_str("    }\n\n");
+        py.push_str("    @classmethod\n    def decode(cls, key):\n");
+        py.push_str("        \"\"\"Return the values of the fields of the key.\"\"\"\n");
+        py.push_str("        return _db_key_decode(cls, key)\n\n");
+        py.push_str("    @classmethod\n    def encode(cls, **values):\n");
+        py.push_str("        \"\"\"Return the key with the values of the fields.\"\"\"\n");
+        py.push_str("        return _db_key_encode(cls, values)\n");
+        py
+    }
+}
+
+/// Write the functions that get and set the value of the field to the C header.
+fn c_accessors(c: &mut String, snake: &str, field: &FieldSchema) {
+    let name = field.name;
+    let (offset, bits) = field.value_bits();
+    let _ = write!(c, "\n/* The {} field", c_comment(field.label));
+    if field.computed {
+        c.push_str(", which is computed by the Rust code from the other fields");
+    }
+    if Some("zero") == field.option {
+        c.push_str(", which is None when its bits are zero");
+    }
+    else if field.is_nonzero() {
+        c.push_str(", which can't be zero");
+    }
+    if let Some(unit) = field.time_unit {
+        let _ = write!(c, ", as a count of {}", unit);
+    }
+    c.push_str(". */\n");
+    let (value_type, get, set) = match field.c_scalar_type() {
+        Some(scalar) => {
+            let load = format!("db_key_load(key, {}, {})", offset, bits);
+            let (get, set) = if field.sign_flip {
+                let sign = format!("UINT64_C({:#x})", 1_u64 << (bits - 1));
+                (format!("({})(int64_t)({} - {})", scalar, load, sign),
+                    format!("db_key_store(key, {}, {}, (uint64_t)(int64_t)value + {});", offset,
+                        bits, sign))
+            }
+            else if "char" == field.encoding {
+                (format!("db_key_char({})", load),
+                    format!("db_key_store(key, {}, {}, value);", offset, bits))
+            }
+            else {
+                (format!("({}){}", scalar, load),
+                    format!("db_key_store(key, {}, {}, (uint64_t)value);", offset, bits))
+            };
+            (scalar, get, set)
+        }
+        None if "char" == field.encoding => {
+            let width = field.char_size.unwrap_or(4) * 8;
+            let count = bits / width;
+            (
+                "uint32_t",
+                format!("for (size_t i = 0; i < {0}; i++) {{\n        \
+                    value[i] = db_key_char(db_key_load(key, {1} + i * {2}, {2}));\n    }}",
+                    count, offset, width),
+                format!("for (size_t i = 0; i < {0}; i++) {{\n        \
+                    db_key_store(key, {1} + i * {2}, {2}, value[i]);\n    }}",
+                    count, offset, width),
+            )
+        }
+        None => {
+            let size = (bits + 7) / 8;
+            let mut get = format!("db_key_load_bytes(key, {}, {}, value);", offset, bits);
+            let mut set = format!("db_key_store_bytes(key, {}, {}, value);", offset, bits);
+            if field.sign_flip {
+                // The bytes are the big-endian two's complement value.
+                let skip = size * 8 - bits;
+                let sign = format!("value[{}] ^= {:#04x};", skip / 8, 0x80 >> (skip % 8));
+                let _ = write!(get, "\n    {}", sign);
+                if 0 != skip {
+                    let _ = write!(get, "\n    if (value[0] & {0:#04x}) {{\n        \
+                        value[0] |= {1:#04x};\n    }}", 0x80 >> skip, !(0xFF_u8 >> skip));
+                }
+                set = format!("uint8_t copy[{}];\n    memcpy(copy, value, {});\n    \
+                    copy[{}] ^= {:#04x};\n    db_key_store_bytes(key, {}, {}, copy);",
+                    size, size, skip / 8, 0x80 >> (skip % 8), offset, bits);
+            }
+            let normalize = match field.collation {
+                Some("ascii_ci") => Some("db_key_ascii_ci"),
+                Some("ascii_ci_trim") => Some("db_key_ascii_ci_trim"),
+                _ => None,
+            };
+            if let Some(normalize) = normalize {
+                set = format!("uint8_t copy[{0}];\n    memcpy(copy, value, {0});\n    \
+                    {1}(copy, {0});\n    db_key_store_bytes(key, {2}, {3}, copy);",
+                    size, normalize, offset, bits);
+            }
+            ("uint8_t", get, set)
+        }
+    };
+    let array = match field.c_scalar_type() {
+        Some(_) => None,
+        None if "char" == field.encoding => Some(bits / (field.char_size.unwrap_or(4) * 8)),
+        None => Some((bits + 7) / 8),
+    };
+    match (field.some_marker(), array) {
+        (Some(some), Some(count)) => {
+            let _ = writeln!(c, "static inline bool {0}_get_{1}(const uint8_t *key, \
+                {2} value[{3}]) {{\n    if ({4} != db_key_load(key, {5}, 8)) {{\n        \
+                return false;\n    }}\n    {6}\n    return true;\n}}",
+                snake, name, value_type, count, some, field.bit_offset, get);
+            let _ = writeln!(c, "static inline void {0}_set_{1}(uint8_t *key, \
+                const {2} value[{3}]) {{\n    db_key_store(key, {4}, 8, {5});\n    {6}\n}}",
+                snake, name, value_type, count, field.bit_offset, some, set);
+        }
+        (Some(some), None) => {
+            let _ = writeln!(c, "static inline bool {0}_get_{1}(const uint8_t *key, \
+                {2} *value) {{\n    if ({3} != db_key_load(key, {4}, 8)) {{\n        \
+                return false;\n    }}\n    *value = {5};\n    return true;\n}}",
+                snake, name, value_type, some, field.bit_offset, get);
+            let _ = writeln!(c, "static inline void {0}_set_{1}(uint8_t *key, {2} value) {{\n    \
+                db_key_store(key, {3}, 8, {4});\n    {5}\n}}",
+                snake, name, value_type, field.bit_offset, some, set);
+        }
+        (None, Some(count)) => {
+            let _ = writeln!(c, "static inline void {0}_get_{1}(const uint8_t *key, \
+                {2} value[{3}]) {{\n    {4}\n}}", snake, name, value_type, count, get);
+            if !field.version {
+                let _ = writeln!(c, "static inline void {0}_set_{1}(uint8_t *key, \
+                    const {2} value[{3}]) {{\n    {4}\n}}", snake, name, value_type, count, set);
+            }
+        }
+        (None, None) => {
+            let _ = writeln!(c, "static inline {0} {1}_get_{2}(const uint8_t *key) {{\n    \
+                return {3};\n}}", value_type, snake, name, get);
+            if !field.version {
+                let _ = writeln!(c, "static inline void {0}_set_{1}(uint8_t *key, {2} value) \
+                    {{\n    {3}\n}}", snake, name, value_type, set);
+            }
+        }
+    }
+    if let Some(some) = field.some_marker() {
+        // None is the other presence byte followed by a zero value.
+        let _ = writeln!(c, "static inline void {0}_clear_{1}(uint8_t *key) {{\n    \
+            db_key_store(key, {2}, 8, {3});\n    \
+            for (size_t i = 0; i < {5}; i++) {{\n        db_key_store(key, {4} + i, 1, 0);\n    \
+            }}\n}}", snake, name, field.bit_offset, 1 - some, offset, bits);
+    }
+}
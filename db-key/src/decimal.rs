@@ -0,0 +1,147 @@
+use std::fmt::{Debug, Display, Formatter, Result};
+
+/// A fixed-point decimal number stored as an integer scaled by a power of ten, which is the value
+/// of a field with the `decimal` attribute.
+///
+/// The `Debug` and `Display` implementations both display the number with `scale` fractional
+/// digits.
+///
+/// # Examples
+///
+/// ```rust
+/// use db_key::Decimal;
+///
+/// let amount = Decimal::new(-123_450, 4);
+///
+/// assert_eq!(amount.to_string(), "-12.3450");
+/// assert_eq!(format!("{:?}", amount), "-12.3450");
+/// assert_eq!(amount.to_f64(), -12.345);
+/// assert_eq!(Decimal::parse("-12.345", 4), Ok(amount));
+/// assert_eq!(Decimal::from_f64(-12.345, 4), amount);
+/// ```
+#[derive(Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Decimal {
+    value: i128,
+    scale: u32,
+}
+
+impl Decimal {
+    /// Create a decimal number from the scaled integer value and the number of fractional digits.
+    pub const fn new(value: i128, scale: u32) -> Self {
+        Self { value, scale }
+    }
+
+    /// Return the scaled integer value of the number.
+    pub const fn value(&self) -> i128 {
+        self.value
+    }
+
+    /// Return the number of fractional digits of the number.
+    pub const fn scale(&self) -> u32 {
+        self.scale
+    }
+
+    /// Return the closest `f64` to the number.
+    pub fn to_f64(&self) -> f64 {
+        self.value as f64 / 10_f64.powi(self.scale as i32)
+    }
+
+    /// Create a decimal number from the `f64` value rounded to `scale` fractional digits. Values
+    /// that are out of range saturate, and NaN is zero.
+    pub fn from_f64(value: f64, scale: u32) -> Self {
+        Self::new((value * 10_f64.powi(scale as i32)).round() as i128, scale)
+    }
+
+    /// Parse a decimal number with up to `scale` fractional digits (e.g. `-12.345`).
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use db_key::{Decimal, ParseDecimalError};
+    ///
+    /// assert_eq!(Decimal::parse("+7", 2).unwrap().value(), 700);
+    /// assert_eq!(Decimal::parse(".5", 2).unwrap().value(), 50);
+    /// assert_eq!(Decimal::parse("1.234", 2), Err(ParseDecimalError::TooPrecise));
+    /// assert_eq!(Decimal::parse("1.2x", 2), Err(ParseDecimalError::InvalidDigit));
+    /// assert_eq!(Decimal::parse("-", 2), Err(ParseDecimalError::Empty));
+    /// ```
+    pub fn parse(s: &str, scale: u32) -> std::result::Result<Self, ParseDecimalError> {
+        let (negative, digits) = match s.as_bytes().first() {
+            Some(b'-') => (true, &s[1..]),
+            Some(b'+') => (false, &s[1..]),
+            _ => (false, s),
+        };
+        let (integer, fraction) = match digits.find('.') {
+            Some(point) => (&digits[..point], &digits[point + 1..]),
+            None => (digits, ""),
+        };
+        if integer.is_empty() && fraction.is_empty() {
+            return Err(ParseDecimalError::Empty);
+        }
+        if fraction.len() > scale as usize {
+            if fraction.bytes().all(|byte| byte.is_ascii_digit()) {
+                return Err(ParseDecimalError::TooPrecise);
+            }
+            return Err(ParseDecimalError::InvalidDigit);
+        }
+        let mut value = 0_i128;
+        for byte in integer.bytes().chain(fraction.bytes()) {
+            if !byte.is_ascii_digit() {
+                return Err(ParseDecimalError::InvalidDigit);
+            }
+            let digit = (byte - b'0') as i128;
+            value = value.checked_mul(10)
+                .and_then(|value| if negative { value.checked_sub(digit) } else { value.checked_add(digit) })
+                .ok_or(ParseDecimalError::OutOfRange)?;
+        }
+        for _ in fraction.len()..scale as usize {
+            value = value.checked_mul(10).ok_or(ParseDecimalError::OutOfRange)?;
+        }
+        Ok(Self::new(value, scale))
+    }
+}
+
+impl Display for Decimal {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        let sign = if self.value < 0 { "-" } else { "" };
+        let magnitude = self.value.unsigned_abs();
+        if 0 == self.scale {
+            return write!(f, "{}{}", sign, magnitude);
+        }
+        let divisor = 10_u128.pow(self.scale);
+        write!(f, "{}{}.{:0width$}", sign, magnitude / divisor, magnitude % divisor,
+            width = self.scale as usize)
+    }
+}
+
+impl Debug for Decimal {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        Display::fmt(self, f)
+    }
+}
+
+/// The error returned when a string can't be parsed as a decimal number.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum ParseDecimalError {
+    /// The string doesn't contain any digits.
+    Empty,
+    /// The string contains a character that isn't a digit, or more than one decimal point.
+    InvalidDigit,
+    /// The number has more fractional digits than the scale of the field.
+    TooPrecise,
+    /// The number is too large for the field.
+    OutOfRange,
+}
+
+impl Display for ParseDecimalError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        f.write_str(match self {
+            ParseDecimalError::Empty => "cannot parse a decimal number from an empty string",
+            ParseDecimalError::InvalidDigit => "invalid digit found in the decimal number",
+            ParseDecimalError::TooPrecise => "too many fractional digits in the decimal number",
+            ParseDecimalError::OutOfRange => "decimal number is out of the range of the field",
+        })
+    }
+}
+
+impl std::error::Error for ParseDecimalError {}
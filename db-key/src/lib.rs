@@ -0,0 +1,99 @@
+#![doc = include_str!("../README.md")]
+#![forbid(future_incompatible)]
+#![warn(missing_docs, missing_debug_implementations, bare_trait_objects)]
+
+mod codegen;
+mod decimal;
+mod order;
+mod schema;
+mod snapshot;
+mod validate;
+mod version;
+
+pub use decimal::{Decimal, ParseDecimalError};
+#[cfg(feature = "proptest")]
+pub use order::assert_order_preserving;
+pub use order::KeyWithArgs;
+pub use schema::{FieldSchema, KeySchema};
+pub use snapshot::{LayoutSnapshot, UPDATE_SNAPSHOTS_VAR};
+pub use validate::ValidationError;
+pub use version::DecodeError;
+// The conversion from the previous version of a key uses these items in the generated code.
+#[doc(hidden)]
+pub use version::{
+    contains_all, Migration, MigrateExact, MigrateFrom, MigrateNonZero, MigrateOption,
+    MissingField, NonZeroInteger,
+};
+
+/// The encoding of the value of a field with the `codec` attribute.
+///
+/// A codec lets a key field store a type that the macros don't know about (money, geohashes,
+/// custom identifiers, etc.) while the macros still handle the layout of the key, the field
+/// constants, the documentation, and the `MIN_KEY` and `MAX_KEY` constants.
+///
+/// The encoded bytes should sort in the same order as the values, so that the key order matches
+/// the order of the argument structure.
+///
+/// The macros lay out the key before the codec is compiled, so the field must also have a `bytes`
+/// attribute with the same size as `SIZE`, which is checked at compile time. The keys are built
+/// in constants, which can't call trait functions, so the codec also provides the encoded bytes
+/// of its minimum and maximum values.
+///
+/// # Examples
+///
+/// ```rust
+/// use db_key::KeyFieldCodec;
+///
+/// /// An amount of money in cents.
+/// #[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
+/// pub struct Cents(pub i64);
+///
+/// /// Store the cents as a big-endian `i64` with the sign bit toggled.
+/// pub struct CentsCodec;
+///
+/// impl KeyFieldCodec for CentsCodec {
+///     type Value = Cents;
+///     const SIZE: usize = 8;
+///     const MIN: &'static [u8] = &[0x00; 8];
+///     const MAX: &'static [u8] = &[0xFF; 8];
+///
+///     fn encode(value: &Cents, bytes: &mut [u8]) {
+///         bytes.copy_from_slice(&((value.0 as u64) ^ (1 << 63)).to_be_bytes());
+///     }
+///
+///     fn decode(bytes: &[u8]) -> Cents {
+///         let mut buf = [0_u8; 8];
+///         buf.copy_from_slice(bytes);
+///         Cents((u64::from_be_bytes(buf) ^ (1 << 63)) as i64)
+///     }
+/// }
+///
+/// let mut bytes = [0_u8; 8];
+/// CentsCodec::encode(&Cents(-1), &mut bytes);
+/// assert!(&bytes[..] < CentsCodec::encode_to_vec(&Cents(1)).as_slice());
+/// assert_eq!(CentsCodec::decode(&bytes), Cents(-1));
+/// assert_eq!(CentsCodec::decode(CentsCodec::MIN), Cents(i64::MIN));
+/// ```
+pub trait KeyFieldCodec {
+    /// The type of the value of the field.
+    type Value;
+    /// The number of bytes of the encoded value.
+    const SIZE: usize;
+    /// The encoded bytes of the minimum value of the field, which must be `SIZE` bytes long.
+    const MIN: &'static [u8];
+    /// The encoded bytes of the maximum value of the field, which must be `SIZE` bytes long.
+    const MAX: &'static [u8];
+
+    /// Encode the value into `bytes`, which is always `SIZE` bytes long.
+    fn encode(value: &Self::Value, bytes: &mut [u8]);
+
+    /// Decode the value from `bytes`, which is always `SIZE` bytes long.
+    fn decode(bytes: &[u8]) -> Self::Value;
+
+    /// Return the encoded bytes of the value in a new vector.
+    fn encode_to_vec(value: &Self::Value) -> Vec<u8> {
+        let mut bytes = vec![0_u8; Self::SIZE];
+        Self::encode(value, &mut bytes);
+        bytes
+    }
+}
@@ -0,0 +1,61 @@
+/// A key created from an argument structure, which is implemented by the keys with the `proptest`
+/// or `arbitrary` option so that test helpers and fuzz targets can generate the arguments of the
+/// key.
+pub trait KeyWithArgs {
+    /// The argument structure that creates the key.
+    type Args;
+}
+
+/// Check that the order of the keys created from random arguments is the same as the order of
+/// the arguments, and panic with the arguments and the keys that aren't in the same order.
+///
+/// The arguments are generated by the `proptest::arbitrary::Arbitrary` implementation of the
+/// arguments of a key with the `proptest` option, so this function is only available with the
+/// `proptest` feature. A key doesn't preserve the order of its arguments when it has an `Option`
+/// field with the `none_last` attribute (the arguments sort `None` first), or computed fields that
+/// are stored before the fields they're computed from.
+///
+/// # Examples
+///
+/// ```rust
+/// use db_key::{assert_order_preserving, KeyWithArgs};
+///
+/// /// A key that stores a `u16` identifier in big-endian order.
+/// #[derive(Debug, PartialEq, Eq, PartialOrd, Ord)]
+/// struct IdKey([u8; 2]);
+///
+/// impl From<&u16> for IdKey {
+///     fn from(id: &u16) -> Self {
+///         Self(id.to_be_bytes())
+///     }
+/// }
+///
+/// impl KeyWithArgs for IdKey {
+///     type Args = u16;
+/// }
+///
+/// assert_order_preserving::<IdKey>();
+/// ```
+#[cfg(feature = "proptest")]
+pub fn assert_order_preserving<K>()
+where
+    K: KeyWithArgs + Ord + std::fmt::Debug + for<'a> From<&'a K::Args>,
+    K::Args: proptest::arbitrary::Arbitrary + Ord,
+{
+    use proptest::{arbitrary::any, collection::vec, prop_assert_eq, test_runner::TestRunner};
+
+    let mut runner = TestRunner::default();
+    let result = runner.run(&vec(any::<K::Args>(), 2..=8), |mut args| {
+        args.sort();
+        let keys: Vec<K> = args.iter().map(K::from).collect();
+        for (args, keys) in args.windows(2).zip(keys.windows(2)) {
+            prop_assert_eq!(args[0].cmp(&args[1]), keys[0].cmp(&keys[1]),
+                "the keys {:?} and {:?} of the arguments {:?} and {:?} aren't in the same order",
+                keys[0], keys[1], args[0], args[1]);
+        }
+        Ok(())
+    });
+    if let Err(error) = result {
+        panic!("{}", error);
+    }
+}
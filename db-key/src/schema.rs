@@ -0,0 +1,244 @@
+use std::fmt::Write;
+
+/// The layout of a key with the `schema_json` option, which is the `SCHEMA` constant of the key.
+///
+/// The JSON returned by `to_json()` lets programs in other languages decode and create the keys
+/// stored by Rust programs (e.g. in a database) without maintaining a parser of their own.
+///
+/// # Examples
+///
+/// ```rust
+/// use db_key::{FieldSchema, KeySchema};
+///
+/// const SCHEMA: KeySchema = KeySchema {
+///     key: "UserKey",
+///     length: 3,
+///     hash: 0x1234,
+///     raw_debug: "compact",
+///     custom_debug: false,
+///     fields: &[
+///         FieldSchema {
+///             name: "user",
+///             label: "User",
+///             rust_type: "u16",
+///             encoding: "uint",
+///             bit_offset: 0,
+///             bits: 16,
+///             ..FieldSchema::DEFAULT
+///         },
+///         FieldSchema {
+///             name: "level",
+///             label: "Level",
+///             rust_type: "i8",
+///             encoding: "int",
+///             bit_offset: 16,
+///             bits: 8,
+///             sign_flip: true,
+///             ..FieldSchema::DEFAULT
+///         },
+///     ],
+/// };
+///
+/// let json = SCHEMA.to_json(&[0x12, 0x34, 0x80], None, None);
+///
+/// assert!(json.starts_with(r#"{"key":"UserKey","length":3,"hash":"0x0000000000001234","#));
+/// assert!(json.contains(r#""name":"level","label":"Level","type":"i8","encoding":"int","#));
+/// assert!(json.contains(r#""default":"0x80","min":null,"max":null}"#));
+/// ```
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct KeySchema {
+    /// The name of the key.
+    pub key: &'static str,
+    /// The length of the key in bytes.
+    pub length: usize,
+    /// The `SCHEMA_HASH` of the key.
+    pub hash: u64,
+    /// The format of the raw key displayed by `Debug` (the `raw_debug` option), which is one of
+    /// `compact`, `std`, `lower_hex`, `upper_hex`, `pretty_lower_hex`, or `pretty_upper_hex`.
+    pub raw_debug: &'static str,
+    /// The key implements `Debug` itself (the `custom_debug` option), so the format of its
+    /// fields isn't known.
+    pub custom_debug: bool,
+    /// The fields in the order they are stored in the key.
+    pub fields: &'static [FieldSchema],
+}
+
+/// The layout of a field of a key in a `KeySchema`.
+///
+/// A field is stored in `bits` bits starting `bit_offset` bits from the start of the key, which
+/// are whole bytes unless the field is a bit field. The bits of `Option` fields with a presence
+/// byte start with the presence byte, which is followed by the value.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct FieldSchema {
+    /// The name of the field in the structure.
+    pub name: &'static str,
+    /// The name of the field used by `Debug` and the documentation (the `name` attribute).
+    pub label: &'static str,
+    /// The type the field is stored as, ignoring how it was written and the `via` newtype (e.g.
+    /// `Option<u64>`).
+    pub rust_type: &'static str,
+    /// How the value is stored, which is one of:
+    ///
+    /// - `uint`: a big-endian unsigned integer.
+    /// - `int`: a big-endian two's complement integer with the sign bit flipped.
+    /// - `bytes`: the bytes of a byte array.
+    /// - `char`: the big-endian scalar values of the characters in `char_size` bytes each.
+    /// - `ipv4`, `ipv6`: the octets of the address.
+    /// - `socket_v4`, `socket_v6`: the octets of the address followed by the big-endian port,
+    ///   and the flow info and scope ID of `socket_v6`.
+    /// - `uuid`: the 16 bytes of the UUID.
+    /// - `duration`, `system_time`: a big-endian unsigned count of the `time_unit`, which is
+    ///   since the Unix epoch for `system_time`.
+    /// - `codec`: the encoding of the `codec` type.
+    pub encoding: &'static str,
+    /// The offset of the field from the start of the key in bits.
+    pub bit_offset: usize,
+    /// The number of bits of the field, including the presence byte of `Option` fields.
+    pub bits: usize,
+    /// The sign bit of the stored value is flipped so that negative values sort first.
+    pub sign_flip: bool,
+    /// How `None` is stored for `Option` fields: `none_first` (a presence byte of 0 for `None`
+    /// and 1 for `Some`), `none_last` (a presence byte of 1 for `None` and 0 for `Some`), or
+    /// `zero` (zero bytes for `None` for non-zero integers, which are the minimum value of signed
+    /// types).
+    pub option: Option<&'static str>,
+    /// The values are stored in descending order. The macros always store the values in
+    /// ascending order, so this is for the decoders of the JSON.
+    pub descending: bool,
+    /// The unit of the count of `duration` and `system_time` fields (e.g. `millis`).
+    pub time_unit: Option<&'static str>,
+    /// The number of bytes of each character of `char` fields.
+    pub char_size: Option<usize>,
+    /// The normalization of byte array fields (the `collate` attribute).
+    pub collation: Option<&'static str>,
+    /// The number of fractional digits of a field with the `decimal` attribute.
+    pub decimal_scale: Option<u32>,
+    /// The path of the type of `codec` fields.
+    pub codec: Option<&'static str>,
+    /// The field is computed from the other fields of the key.
+    pub computed: bool,
+    /// The field is zero-filled padding.
+    pub padding: bool,
+    /// The field is the version byte of a key with the `version` option.
+    pub version: bool,
+    /// The field has the `hidden` attribute, so `Debug` only displays it in the raw key.
+    pub hidden: bool,
+}
+
+impl FieldSchema {
+    /// A field without options, which is used to initialize the options that aren't set.
+    pub const DEFAULT: FieldSchema = FieldSchema {
+        name: "",
+        label: "",
+        rust_type: "",
+        encoding: "bytes",
+        bit_offset: 0,
+        bits: 0,
+        sign_flip: false,
+        option: None,
+        descending: false,
+        time_unit: None,
+        char_size: None,
+        collation: None,
+        decimal_scale: None,
+        codec: None,
+        computed: false,
+        padding: false,
+        version: false,
+        hidden: false,
+    };
+
+    /// Return the bits of the field in the key, right-aligned in the smallest number of bytes.
+    pub fn stored_bits(&self, key: &[u8]) -> Vec<u8> {
+        let size = (self.bits + 7) / 8;
+        let mut bytes = vec![0_u8; size];
+        for i in 0..self.bits {
+            let bit = self.bit_offset + i;
+            if 0 != key[bit / 8] & (0x80 >> (bit % 8)) {
+                let target = size * 8 - self.bits + i;
+                bytes[target / 8] |= 0x80 >> (target % 8);
+            }
+        }
+        bytes
+    }
+}
+
+impl KeySchema {
+    /// Return the schema as JSON, with the stored bits of each field in the default key and in
+    /// the minimum and maximum keys (`null` when the key doesn't have them) as hexadecimal
+    /// strings.
+    pub fn to_json(&self, default: &[u8], min: Option<&[u8]>, max: Option<&[u8]>) -> String {
+        let mut json = String::new();
+        let _ = write!(json, concat!("{{\"key\":{},\"length\":{},\"hash\":\"{:#018x}\",",
+                "\"raw_debug\":{},\"custom_debug\":{},\"fields\":["),
+            JsonStr(self.key), self.length, self.hash, JsonStr(self.raw_debug), self.custom_debug);
+        for (i, field) in self.fields.iter().enumerate() {
+            if 0 < i {
+                json.push(',');
+            }
+            let _ = write!(json, concat!("{{\"name\":{},\"label\":{},\"type\":{},",
+                    "\"encoding\":{},\"offset\":{},\"size\":{},\"bit_offset\":{},\"bits\":{},",
+                    "\"sign_flip\":{},\"option\":{},\"descending\":{},\"time_unit\":{},",
+                    "\"char_size\":{},\"collation\":{},\"decimal_scale\":{},\"codec\":{},",
+                    "\"computed\":{},\"padding\":{},\"version\":{},\"hidden\":{},"),
+                JsonStr(field.name), JsonStr(field.label), JsonStr(field.rust_type),
+                JsonStr(field.encoding), field.bit_offset / 8,
+                (field.bit_offset + field.bits + 7) / 8 - field.bit_offset / 8,
+                field.bit_offset, field.bits, field.sign_flip, JsonOption(field.option.map(JsonStr)),
+                field.descending, JsonOption(field.time_unit.map(JsonStr)),
+                JsonOption(field.char_size), JsonOption(field.collation.map(JsonStr)),
+                JsonOption(field.decimal_scale), JsonOption(field.codec.map(JsonStr)),
+                field.computed, field.padding, field.version, field.hidden);
+            let _ = write!(json, "\"default\":{},\"min\":{},\"max\":{}}}",
+                JsonHex(&field.stored_bits(default)),
+                JsonOption(min.map(|min| JsonHex(&field.stored_bits(min)).to_string())),
+                JsonOption(max.map(|max| JsonHex(&field.stored_bits(max)).to_string())));
+        }
+        json.push_str("]}");
+        json
+    }
+}
+
+/// A string written as a JSON string.
+struct JsonStr<'a>(&'a str);
+
+impl std::fmt::Display for JsonStr<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_char('"')?;
+        for c in self.0.chars() {
+            match c {
+                '"' => f.write_str("\\\"")?,
+                '\\' => f.write_str("\\\\")?,
+                '\n' => f.write_str("\\n")?,
+                c if c < ' ' => write!(f, "\\u{:04x}", c as u32)?,
+                c => f.write_char(c)?,
+            }
+        }
+        f.write_char('"')
+    }
+}
+
+/// A value written as JSON, or `null` for `None`.
+struct JsonOption<T>(Option<T>);
+
+impl<T: std::fmt::Display> std::fmt::Display for JsonOption<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match &self.0 {
+            Some(value) => value.fmt(f),
+            None => f.write_str("null"),
+        }
+    }
+}
+
+/// Bytes written as a JSON string of hexadecimal digits starting with `0x`.
+struct JsonHex<'a>(&'a [u8]);
+
+impl std::fmt::Display for JsonHex<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("\"0x")?;
+        for byte in self.0.iter() {
+            write!(f, "{:02x}", byte)?;
+        }
+        f.write_char('"')
+    }
+}
@@ -0,0 +1,128 @@
+use std::fmt::{Display, Formatter, Result};
+use std::fs;
+use std::path::Path;
+
+/// The environment variable that makes the layout snapshot tests write the snapshot files
+/// instead of comparing them.
+pub const UPDATE_SNAPSHOTS_VAR: &str = "DB_KEY_UPDATE_SNAPSHOTS";
+
+/// The layout of a key and the bytes of some reference keys, which the test generated for a key
+/// with the `layout_snapshot` option compares with a snapshot file checked in with the crate.
+///
+/// The snapshot changes when a change to the key would change the bytes stored for the same
+/// values, so keys already stored (e.g. in a database) couldn't be read any more.
+///
+/// # Examples
+///
+/// ```rust
+/// use db_key::LayoutSnapshot;
+///
+/// let snapshot = LayoutSnapshot {
+///     key: "UserKey",
+///     length: 4,
+///     description: "user: u32 @0+32",
+///     hash: 0x1234,
+///     keys: &[("default", &[0, 0, 0, 0]), ("reference 1", &[0x12, 0x34, 0x56, 0x78])],
+/// };
+///
+/// // The first line is a comment that explains how to update the snapshot.
+/// assert!(snapshot.to_string().ends_with("
+/// key: UserKey
+/// length: 4
+/// hash: 0x0000000000001234
+///
+/// [fields]
+/// user: u32 @0+32
+///
+/// [keys]
+/// default: 0x00000000
+/// reference 1: 0x12345678
+/// "));
+/// ```
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct LayoutSnapshot<'a> {
+    /// The name of the key.
+    pub key: &'a str,
+    /// The length of the key in bytes.
+    pub length: usize,
+    /// The `SCHEMA_DESCRIPTION` of the key.
+    pub description: &'a str,
+    /// The `SCHEMA_HASH` of the key.
+    pub hash: u64,
+    /// The names and the bytes of the reference keys.
+    pub keys: &'a [(&'a str, &'a [u8])],
+}
+
+impl LayoutSnapshot<'_> {
+    /// Compare the snapshot with the snapshot file at `path`, and panic with the lines that are
+    /// different if they don't match. When the `DB_KEY_UPDATE_SNAPSHOTS` environment variable is
+    /// set, the file is written instead.
+    pub fn check(&self, path: &str) {
+        let snapshot = self.to_string();
+        if std::env::var_os(UPDATE_SNAPSHOTS_VAR).is_some() {
+            if let Some(parent) = Path::new(path).parent() {
+                fs::create_dir_all(parent).unwrap_or_else(|error| {
+                    panic!("Failed to create the directory of the layout snapshot {}: {}", path,
+                        error)
+                });
+            }
+            fs::write(path, &snapshot).unwrap_or_else(|error| {
+                panic!("Failed to write the layout snapshot {}: {}", path, error)
+            });
+            return;
+        }
+        let expected = match fs::read_to_string(path) {
+            Ok(expected) => expected.replace("\r\n", "\n"),
+            Err(error) => panic!(concat!("Failed to read the layout snapshot {} of {}: {}. Run ",
+                "the test with {}=1 to create it.\n\n{}"), path, self.key, error,
+                UPDATE_SNAPSHOTS_VAR, snapshot),
+        };
+        if expected != snapshot {
+            panic!(concat!("The layout of {} doesn't match the snapshot {}, so the keys already ",
+                "stored with this layout would be read incorrectly. If the change is intended, ",
+                "run the test with {}=1 to update the snapshot.\n\n{}"), self.key, path,
+                UPDATE_SNAPSHOTS_VAR, line_diff(&expected, &snapshot));
+        }
+    }
+}
+
+impl Display for LayoutSnapshot<'_> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        writeln!(f, "# The layout snapshot of {}. Set {}=1 to update it.", self.key,
+            UPDATE_SNAPSHOTS_VAR)?;
+        writeln!(f, "key: {}", self.key)?;
+        writeln!(f, "length: {}", self.length)?;
+        writeln!(f, "hash: {:#018x}", self.hash)?;
+        writeln!(f, "\n[fields]")?;
+        writeln!(f, "{}", self.description)?;
+        writeln!(f, "\n[keys]")?;
+        for (name, bytes) in self.keys.iter() {
+            write!(f, "{}: 0x", name)?;
+            for byte in bytes.iter() {
+                write!(f, "{:02x}", byte)?;
+            }
+            writeln!(f)?;
+        }
+        Ok(())
+    }
+}
+
+/// Return the lines of the snapshot file that are different, prefixed by `-`, and the lines of
+/// the current layout, prefixed by `+`.
+fn line_diff(expected: &str, found: &str) -> String {
+    let expected: Vec<&str> = expected.lines().collect();
+    let found: Vec<&str> = found.lines().collect();
+    let mut diff = String::new();
+    for i in 0..std::cmp::max(expected.len(), found.len()) {
+        let (old, new) = (expected.get(i), found.get(i));
+        if old != new {
+            if let Some(old) = old {
+                diff.push_str(&format!("-{}\n", old));
+            }
+            if let Some(new) = new {
+                diff.push_str(&format!("+{}\n", new));
+            }
+        }
+    }
+    diff
+}
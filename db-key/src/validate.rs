@@ -0,0 +1,53 @@
+use std::fmt::{Display, Formatter, Result};
+
+/// The error returned by the `validate()` function of a key when the raw bytes of the key (e.g.
+/// read from a database) aren't a key that the key functions create.
+///
+/// # Examples
+///
+/// ```rust
+/// use db_key::ValidationError;
+///
+/// let error = ValidationError::Computed { field: "shard" };
+///
+/// assert_eq!(error.field(), "shard");
+/// assert_eq!(error.to_string(), "the computed field shard doesn't match the key");
+/// ```
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum ValidationError {
+    /// The stored value of a computed field doesn't match the value computed from the key.
+    Computed {
+        /// The name of the field.
+        field: &'static str,
+    },
+    /// A padding field of a key with the `strict` attribute isn't zero.
+    Padding {
+        /// The name of the field.
+        field: &'static str,
+    },
+}
+
+impl ValidationError {
+    /// Return the name of the field that isn't valid.
+    pub fn field(&self) -> &'static str {
+        match self {
+            ValidationError::Computed { field } | ValidationError::Padding { field } => field,
+        }
+    }
+}
+
+impl Display for ValidationError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        match self {
+            ValidationError::Computed { field } => {
+                write!(f, "the computed field {} doesn't match the key", field)
+            }
+            ValidationError::Padding { field } => {
+                write!(f, "the padding field {} isn't zero", field)
+            }
+        }
+    }
+}
+
+impl std::error::Error for ValidationError {}
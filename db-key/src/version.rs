@@ -0,0 +1,232 @@
+use std::cell::Cell;
+use std::fmt::{Display, Formatter, Result};
+use std::num::{
+    NonZeroI8, NonZeroI16, NonZeroI32, NonZeroI64, NonZeroI128,
+    NonZeroU8, NonZeroU16, NonZeroU32, NonZeroU64, NonZeroU128,
+};
+use crate::ValidationError;
+
+/// The error returned by the `decode_any()` function of a key with the `version` attribute when
+/// the bytes aren't a key of the version or one of its previous versions, and by the conversion
+/// from a slice of a key with the `strict` option when the slice isn't a valid key.
+///
+/// # Examples
+///
+/// ```rust
+/// use db_key::{DecodeError, ValidationError};
+///
+/// let error = DecodeError::Version { found: 7 };
+///
+/// assert_eq!(error.to_string(), "unknown key version 7");
+/// assert_eq!(DecodeError::Length { expected: 9, found: 4 }.to_string(),
+///     "the key has 4 bytes instead of 9");
+/// assert_eq!(DecodeError::from(ValidationError::Padding { field: "_reserved" }).to_string(),
+///     "the padding field _reserved isn't zero");
+/// ```
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum DecodeError {
+    /// The bytes don't have the length of the key of their version.
+    Length {
+        /// The length of the key of the version.
+        expected: usize,
+        /// The number of bytes.
+        found: usize,
+    },
+    /// The version byte isn't the version of the key or one of its previous versions.
+    Version {
+        /// The version byte.
+        found: u8,
+    },
+    /// The bytes are a key that doesn't pass `validate()`.
+    Invalid(ValidationError),
+}
+
+impl Display for DecodeError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        match self {
+            DecodeError::Length { expected, found } => {
+                write!(f, "the key has {} bytes instead of {}", found, expected)
+            }
+            DecodeError::Version { found } => write!(f, "unknown key version {}", found),
+            DecodeError::Invalid(error) => error.fmt(f),
+        }
+    }
+}
+
+impl std::error::Error for DecodeError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            DecodeError::Invalid(error) => Some(error),
+            _ => None,
+        }
+    }
+}
+
+impl From<ValidationError> for DecodeError {
+    fn from(error: ValidationError) -> Self {
+        DecodeError::Invalid(error)
+    }
+}
+
+// The rest of this file is used by the conversion from the previous version of a key, which the
+// macros generate without knowing the fields of the previous version. The value of each field is
+// the value returned by the getter of the previous version, or `MissingField` when it doesn't
+// have the field, and the conversion to the type of the field is selected by the number of
+// references the `migrate()` method is called through (autoref specialization).
+
+/// The value of a field that isn't in the previous version of a key.
+#[doc(hidden)]
+#[derive(Copy, Clone, Debug)]
+pub struct MissingField;
+
+/// The value of a field of the previous version of a key, and the default value of the field.
+#[doc(hidden)]
+pub struct Migration<S, T> {
+    value: Cell<Option<S>>,
+    default: Cell<Option<T>>,
+}
+
+impl<S, T> Migration<S, T> {
+    pub fn new(value: S, default: T) -> Self {
+        Self { value: Cell::new(Some(value)), default: Cell::new(Some(default)) }
+    }
+
+    fn value(&self) -> S {
+        self.value.take().expect("The value of the field was already migrated.")
+    }
+
+    fn default(&self) -> T {
+        self.default.take().expect("The value of the field was already migrated.")
+    }
+}
+
+impl<S, T> std::fmt::Debug for Migration<S, T> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        f.debug_struct("Migration").finish_non_exhaustive()
+    }
+}
+
+/// Copy a slice into the start of a zero-filled array. The macros check that the array field of
+/// the previous version isn't larger at compile time.
+fn widen_array<const N: usize>(value: &[u8]) -> [u8; N] {
+    let mut array = [0_u8; N];
+    array[..value.len()].copy_from_slice(value);
+    array
+}
+
+/// The conversions that take priority: missing fields, and array fields.
+#[doc(hidden)]
+pub trait MigrateExact<T> {
+    fn migrate(&self) -> T;
+}
+
+impl<T> MigrateExact<T> for &&&Migration<MissingField, T> {
+    fn migrate(&self) -> T {
+        self.default()
+    }
+}
+
+impl<const N: usize> MigrateExact<[u8; N]> for &&&Migration<&[u8], [u8; N]> {
+    fn migrate(&self) -> [u8; N] {
+        widen_array(self.value())
+    }
+}
+
+impl<const M: usize, const N: usize> MigrateExact<Option<[u8; N]>>
+    for &&&Migration<Option<[u8; M]>, Option<[u8; N]>>
+{
+    fn migrate(&self) -> Option<[u8; N]> {
+        self.value().map(|value| widen_array(&value))
+    }
+}
+
+/// The conversion of `Option` fields.
+#[doc(hidden)]
+pub trait MigrateOption<T> {
+    fn migrate(&self) -> T;
+}
+
+impl<S, T: From<S>> MigrateOption<Option<T>> for &&Migration<Option<S>, Option<T>> {
+    fn migrate(&self) -> Option<T> {
+        self.value().map(T::from)
+    }
+}
+
+/// The non-zero integer types, which are returned by the getters in an `Option`.
+#[doc(hidden)]
+pub trait NonZeroInteger {}
+
+macro_rules! impl_non_zero_integer {
+    ($($ty: ty),*) => {
+        $(impl NonZeroInteger for $ty {})*
+    };
+}
+
+impl_non_zero_integer!(NonZeroI8, NonZeroI16, NonZeroI32, NonZeroI64, NonZeroI128,
+    NonZeroU8, NonZeroU16, NonZeroU32, NonZeroU64, NonZeroU128);
+
+/// The conversion of non-zero integer fields, which are only zero in invalid raw keys.
+#[doc(hidden)]
+pub trait MigrateNonZero<T> {
+    fn migrate(&self) -> T;
+}
+
+impl<S, T: From<S> + NonZeroInteger> MigrateNonZero<T> for &Migration<Option<S>, T> {
+    fn migrate(&self) -> T {
+        match self.value() {
+            Some(value) => T::from(value),
+            None => self.default(),
+        }
+    }
+}
+
+/// The conversion of all other fields, which is the lossless `From` conversion.
+#[doc(hidden)]
+pub trait MigrateFrom<T> {
+    fn migrate(&self) -> T;
+}
+
+impl<S, T: From<S>> MigrateFrom<T> for Migration<S, T> {
+    fn migrate(&self) -> T {
+        T::from(self.value())
+    }
+}
+
+/// Return true if the strings are equal in a constant.
+const fn str_eq(a: &str, b: &str) -> bool {
+    let a = a.as_bytes();
+    let b = b.as_bytes();
+    if a.len() != b.len() {
+        return false;
+    }
+    let mut i = 0;
+    while i < a.len() {
+        if a[i] != b[i] {
+            return false;
+        }
+        i += 1;
+    }
+    true
+}
+
+/// Return true if every string in `previous` is in `current` in a constant.
+#[doc(hidden)]
+pub const fn contains_all(current: &[&str], previous: &[&str]) -> bool {
+    let mut i = 0;
+    while i < previous.len() {
+        let mut found = false;
+        let mut j = 0;
+        while j < current.len() {
+            if str_eq(current[j], previous[i]) {
+                found = true;
+            }
+            j += 1;
+        }
+        if !found {
+            return false;
+        }
+        i += 1;
+    }
+    true
+}
@@ -0,0 +1,70 @@
+#![no_main]
+
+//! Decode arbitrary bytes as the sample keys, which must never panic: the keys read every field
+//! with its getter when they're formatted with `Debug`, and the keys with computed or padding
+//! fields are checked by `validate()`.
+
+use db_key_samples::{attrib, derive};
+use libfuzzer_sys::fuzz_target;
+
+/// Decode the bytes as each key with `From<&[u8]>` and format the key with `Debug`.
+macro_rules! decode {
+    ($data:expr, $($key:ty),* $(,)?) => {
+        $(
+            let key = <$key>::from($data);
+            let _ = format!("{:?}", key);
+        )*
+    };
+}
+
+/// Decode the bytes as each key with `From<&[u8]>`, validate it, and format the key with `Debug`
+/// and the validation error with `Display`.
+macro_rules! validate {
+    ($data:expr, $($key:ty),* $(,)?) => {
+        $(
+            let key = <$key>::from($data);
+            let _ = format!("{:?}", key);
+            if let Err(error) = key.validate() {
+                let _ = error.to_string();
+            }
+        )*
+    };
+}
+
+/// Decode the bytes as each key with `TryFrom<&[u8]>`, which validates the key, and format the
+/// key with `Debug` or the error with `Display`.
+macro_rules! try_decode {
+    ($data:expr, $($key:ty),* $(,)?) => {
+        $(
+            match <$key>::try_from($data) {
+                Ok(key) => {
+                    assert!(key.validate().is_ok());
+                    let _ = format!("{:?}", key);
+                }
+                Err(error) => {
+                    let _ = error.to_string();
+                }
+            }
+        )*
+    };
+}
+
+fuzz_target!(|data: &[u8]| {
+    decode!(data, attrib::SampleKey, attrib::MinMaxKey, attrib::SignedKey, attrib::NarrowKey,
+        attrib::BitsKey, attrib::OptionKey, attrib::FlowKey, attrib::EventKey, attrib::RecordKey,
+        attrib::LedgerKey, attrib::MemberKey, attrib::WordKey, attrib::LoginKey,
+        attrib::OrderKey, attrib::ActivityKey);
+    decode!(data, derive::SampleKey, derive::MinMaxKey, derive::OptionalKey, derive::FlowKey,
+        derive::EventKey, derive::LedgerKey, derive::MemberKey, derive::LoginKey,
+        derive::OrderKey, derive::ActivityKey);
+    validate!(data, attrib::ShardedKey, derive::ShardedKey);
+    try_decode!(data, attrib::ReservedKey, derive::ReservedKey);
+
+    // The decimal fields are formatted with `Display`, which parses back to the same value.
+    let mut key = attrib::AmountKey::from(data);
+    let (amount, rate) = (key.amount_decimal().to_string(), key.rate_decimal().to_string());
+    let expected = key;
+    key.set_amount_str(&amount).unwrap();
+    key.set_rate_str(&rate).unwrap();
+    assert_eq!((key.amount(), key.rate()), (expected.amount(), expected.rate()));
+});
@@ -0,0 +1,65 @@
+#![no_main]
+
+//! Create the sample keys from arbitrary arguments, and check that the keys convert back to
+//! their arguments and that the keys sort like their arguments.
+
+use arbitrary::{Arbitrary, Result, Unstructured};
+use db_key::KeyWithArgs;
+use db_key_samples::{attrib, derive};
+use libfuzzer_sys::fuzz_target;
+use std::fmt::Debug;
+
+/// Create a key from arbitrary arguments and check that it converts back to the same arguments,
+/// and, when `ordered` is true, that the key and another key sort like their arguments.
+fn round_trip<'a, K>(u: &mut Unstructured<'a>, ordered: bool) -> Result<()>
+where
+    K: KeyWithArgs + Ord + Debug + for<'b> From<&'b K::Args>,
+    K::Args: Arbitrary<'a> + Ord + Debug + for<'b> From<&'b K>,
+{
+    let args = K::Args::arbitrary(u)?;
+    let key = K::from(&args);
+    assert_eq!(K::Args::from(&key), args, "the key {:?} doesn't convert back to its arguments",
+        key);
+    if ordered {
+        let other_args = K::Args::arbitrary(u)?;
+        let other = K::from(&other_args);
+        assert_eq!(args.cmp(&other_args), key.cmp(&other),
+            "the keys {:?} and {:?} of the arguments {:?} and {:?} aren't in the same order",
+            key, other, args, other_args);
+    }
+    Ok(())
+}
+
+fn check(u: &mut Unstructured<'_>) -> Result<()> {
+    round_trip::<attrib::SampleKey>(u, true)?;
+    round_trip::<attrib::MinMaxKey>(u, true)?;
+    round_trip::<attrib::SignedKey>(u, true)?;
+    round_trip::<attrib::NarrowKey>(u, true)?;
+    round_trip::<attrib::BitsKey>(u, true)?;
+    // The tag of the option key sorts `None` last, unlike its arguments.
+    round_trip::<attrib::OptionKey>(u, false)?;
+    round_trip::<attrib::FlowKey>(u, true)?;
+    round_trip::<attrib::EventKey>(u, true)?;
+    round_trip::<attrib::RecordKey>(u, true)?;
+    round_trip::<attrib::MemberKey>(u, true)?;
+    round_trip::<attrib::WordKey>(u, true)?;
+    round_trip::<attrib::LoginKey>(u, true)?;
+    round_trip::<attrib::AmountKey>(u, true)?;
+    round_trip::<attrib::OrderKey>(u, true)?;
+    round_trip::<attrib::ReservedKey>(u, true)?;
+    round_trip::<attrib::ActivityKey>(u, true)?;
+    round_trip::<derive::SampleKey>(u, true)?;
+    round_trip::<derive::MinMaxKey>(u, true)?;
+    round_trip::<derive::OptionalKey>(u, false)?;
+    round_trip::<derive::FlowKey>(u, true)?;
+    round_trip::<derive::EventKey>(u, true)?;
+    round_trip::<derive::MemberKey>(u, true)?;
+    round_trip::<derive::LoginKey>(u, true)?;
+    round_trip::<derive::OrderKey>(u, true)?;
+    round_trip::<derive::ReservedKey>(u, true)?;
+    round_trip::<derive::ActivityKey>(u, true)
+}
+
+fuzz_target!(|data: &[u8]| {
+    let _ = check(&mut Unstructured::new(data));
+});